@@ -0,0 +1,114 @@
+use std::future::{ready, Ready};
+
+use actix_web::dev::Payload;
+use actix_web::http::header::{self, ETag, EntityTag, Header, IF_MATCH};
+use actix_web::{FromRequest, HttpRequest, HttpResponse, HttpResponseBuilder};
+use serde::Serialize;
+
+use crate::api::error::api::ApiError;
+use crate::wrapper::entity::Versioned;
+
+pub(crate) fn etag<T: Versioned>(entity: &T) -> ETag {
+    match entity.derived_state() {
+        Some(state) => ETag(EntityTag::new_strong(format!("{}.{state}", entity.version()))),
+        None => version_etag(entity.version()),
+    }
+}
+
+fn version_etag(version: i32) -> ETag {
+    ETag(EntityTag::new_strong(version.to_string()))
+}
+
+/// The version a tag was made from, without the derived state.
+fn tag_version(tag: &EntityTag) -> &str {
+    tag.tag().split_once('.').map_or(tag.tag(), |(version, _)| version)
+}
+
+/// Sends the entity with its `ETag`.
+pub(crate) fn versioned_response<T: Versioned + Serialize>(
+    mut builder: HttpResponseBuilder,
+    entity: &T,
+) -> HttpResponse {
+    builder.insert_header(etag(entity)).json(entity)
+}
+
+/// The required `If-Match` header of requests that change or delete an entity. Without it the client could
+/// overwrite changes it has never seen.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct IfMatch(Option<header::IfMatch>);
+
+impl IfMatch {
+    /// Reads the precondition from the value an `If-Match` header would have, for requests that carry it elsewhere.
+    pub(crate) fn from_value(value: Option<&str>) -> Self {
+        Self(value.map(|value| match value.trim() {
+            "*" => header::IfMatch::Any,
+            value => header::IfMatch::Items(value.parse::<EntityTag>().into_iter().collect()),
+        }))
+    }
+
+    /// The precondition that the entity still has the version the client last saw.
+    pub(crate) fn version(version: i32) -> Self {
+        Self(Some(header::IfMatch::Items(vec![version_etag(version).0])))
+    }
+
+    /// Fails if the header is missing or the entity was changed since the client fetched it. `*` matches every
+    /// version. Only the version is compared, the derived state may have changed without the client's edit being
+    /// outdated. Checked after the permissions, so clients without access learn nothing about the entity.
+    pub(crate) fn check<T: Versioned>(&self, entity: &T) -> Result<(), ApiError> {
+        let version = entity.version().to_string();
+        let matches = match &self.0 {
+            None => return Err(ApiError::PreconditionRequired()),
+            Some(header::IfMatch::Any) => true,
+            Some(header::IfMatch::Items(tags)) => tags.iter().any(|tag| !tag.weak && tag_version(tag) == version),
+        };
+
+        match matches {
+            true => Ok(()),
+            false => Err(ApiError::PreconditionFailed()),
+        }
+    }
+}
+
+impl FromRequest for IfMatch {
+    type Error = ApiError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        if !req.headers().contains_key(IF_MATCH) {
+            return ready(Ok(Self(None)));
+        }
+
+        // a tag that can not be parsed can not match either
+        ready(Ok(Self(Some(header::IfMatch::parse(req).unwrap_or_else(|_| header::IfMatch::Items(Vec::new()))))))
+    }
+}
+
+/// The optional `If-None-Match` header of a GET, the client already has the entity if its tag still matches.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct IfNoneMatch(Option<header::IfNoneMatch>);
+
+impl IfNoneMatch {
+    /// Sends the body made from the entity along with the entity's `ETag`, or only `304 Not Modified` if the
+    /// client's copy is still current.
+    pub(crate) fn respond<T: Serialize>(&self, etag: ETag, body: &T) -> HttpResponse {
+        let not_modified = match &self.0 {
+            None => false,
+            Some(header::IfNoneMatch::Any) => true,
+            Some(header::IfNoneMatch::Items(tags)) => tags.iter().any(|tag| tag.weak_eq(&etag)),
+        };
+
+        match not_modified {
+            true => HttpResponse::NotModified().insert_header(etag).finish(),
+            false => HttpResponse::Ok().insert_header(etag).json(body),
+        }
+    }
+}
+
+impl FromRequest for IfNoneMatch {
+    type Error = ApiError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(Ok(Self(header::IfNoneMatch::parse(req).ok())))
+    }
+}
@@ -29,3 +29,17 @@ pub(crate) struct InternalServerError(#[to_schema] ApiError);
 #[response(status = 404, description = "Resource not found.", content_type = "application/json")]
 #[allow(dead_code)]
 pub(crate) struct ResourceNotFound(#[to_schema] ApiError);
+
+#[derive(IntoResponses)]
+#[response(
+    status = 412,
+    description = "The resource was changed since the version given in the If-Match header.",
+    content_type = "application/json"
+)]
+#[allow(dead_code)]
+pub(crate) struct PreconditionFailed(#[to_schema] ApiError);
+
+#[derive(IntoResponses)]
+#[response(status = 428, description = "The If-Match header is missing.", content_type = "application/json")]
+#[allow(dead_code)]
+pub(crate) struct PreconditionRequired(#[to_schema] ApiError);
@@ -6,6 +6,7 @@ use croner::errors::CronError;
 use derive_more::{Display, Error};
 use deschuler::cron_builder::config::BuilderConfigBuilderError;
 use redis::RedisError;
+use reqwest::Error as ReqwestError;
 use sea_orm::DbErr;
 use serde::{Serialize, Serializer};
 use tracing::error;
@@ -14,7 +15,9 @@ use validator::{ValidationError, ValidationErrors};
 
 use entity::error::EntityError;
 use utility::datetime::error::TimeError;
+use utility::jwt::error::JwtError;
 use utility::snowflake::error::SnowflakeGeneratorError;
+use utility::totp::error::TotpError;
 
 use crate::api::error::api_codes::ApiCode;
 use crate::api::error::validation::ValidationCode;
@@ -81,7 +84,30 @@ api_errors!(
     (StatusCode::UNAUTHORIZED, ApiCode::INVALID_CREDENTIALS, "Invalid credentials!", InvalidCredentials);
     (StatusCode::UNAUTHORIZED, ApiCode::UNAUTHORIZED, "Unauthorized!", Unauthorized);
     (StatusCode::FORBIDDEN, ApiCode::MISSING_PERMISSIONS, "Missing permissions!", MissingPermissions);
+    (StatusCode::FORBIDDEN, ApiCode::SESSION_REQUIRED, "This route can not be used with an API token!", SessionRequired);
     (StatusCode::UNAUTHORIZED, ApiCode::NO_TOKEN_PROVIDED, "No token provided!", NoTOkenProvided);
+    (StatusCode::UNAUTHORIZED, ApiCode::INVALID_TOTP_CODE, "Invalid one-time password!", InvalidTotpCode);
+    (StatusCode::UNAUTHORIZED, ApiCode::INVALID_TOTP_CHALLENGE, "Invalid or expired two-factor challenge!", InvalidTotpChallenge);
+    (StatusCode::CONFLICT, ApiCode::ACCOUNT_ARCHIVED, "Account is archived!", AccountArchived);
+    (StatusCode::CONFLICT, ApiCode::LAST_OWNER, "The last user with full permissions can not be removed!", LastOwner);
+    (StatusCode::GONE, ApiCode::RESTORE_WINDOW_EXPIRED, "The retention window for restoring has expired!", RestoreWindowExpired);
+    (StatusCode::CONFLICT, ApiCode::TOTP_ALREADY_ENABLED, "Two-factor authentication is already enabled!", TotpAlreadyEnabled);
+    (StatusCode::CONFLICT, ApiCode::TOTP_NOT_ENABLED, "Two-factor authentication is not enabled!", TotpNotEnabled);
+    (StatusCode::NOT_FOUND, ApiCode::OIDC_DISABLED, "Single sign-on is not configured!", OidcDisabled);
+    (StatusCode::FORBIDDEN, ApiCode::OIDC_ACCOUNT_NOT_LINKED, "No user is linked to this identity!", OidcAccountNotLinked);
+    (StatusCode::BAD_REQUEST, ApiCode::INVALID_PASSWORD_RESET_TOKEN, "Invalid or expired password reset token!", InvalidPasswordResetToken);
+    (StatusCode::FORBIDDEN, ApiCode::USER_DISABLED, "The user is disabled!", UserDisabled);
+    (StatusCode::CONFLICT, ApiCode::ADMIN_SELF_MODIFICATION, "Admins can not disable, delete or demote themselves!", AdminSelfModification);
+    (StatusCode::FORBIDDEN, ApiCode::REGISTRATION_CLOSED, "Registration is closed!", RegistrationClosed);
+    (StatusCode::BAD_REQUEST, ApiCode::INVALID_INVITATION_CODE, "Missing, invalid, expired or used up invitation code!", InvalidInvitationCode);
+    (StatusCode::BAD_REQUEST, ApiCode::INVALID_IDEMPOTENCY_KEY, "The idempotency key must be 1 to 255 visible ASCII characters!", InvalidIdempotencyKey);
+    (StatusCode::CONFLICT, ApiCode::IDEMPOTENCY_KEY_REUSED, "The idempotency key was already used for a different request!", IdempotencyKeyReused);
+    (StatusCode::CONFLICT, ApiCode::IDEMPOTENCY_KEY_IN_USE, "A request with the idempotency key is still being processed!", IdempotencyKeyInUse);
+    (StatusCode::PRECONDITION_FAILED, ApiCode::PRECONDITION_FAILED, "The resource was changed since the version given in If-Match!", PreconditionFailed);
+    (StatusCode::PRECONDITION_REQUIRED, ApiCode::PRECONDITION_REQUIRED, "An If-Match header with the ETag of the resource is required!", PreconditionRequired);
+    (StatusCode::FAILED_DEPENDENCY, ApiCode::BATCH_ABORTED, "The batch was rolled back because another operation failed!", BatchAborted);
+    (StatusCode::BAD_REQUEST, ApiCode::INVALID_SYNC_TOKEN, "The sync token was not issued by this server!", InvalidSyncToken);
+    (StatusCode::GONE, ApiCode::SYNC_TOKEN_EXPIRED, "The sync token is older than the deleted entities are kept, a full sync is required!", SyncTokenExpired);
 );
 
 impl ApiError {
@@ -95,6 +121,76 @@ impl ApiError {
         }
     }
 
+    #[allow(non_snake_case)]
+    pub(crate) fn AccountHasRecurringTransactions<T: Serialize>(recurring_transactions: &T) -> Self {
+        Self {
+            status_code: StatusCode::CONFLICT,
+            api_code: ApiCode::ACCOUNT_HAS_RECURRING_TRANSACTIONS,
+            details: "Account is still used by recurring transactions!".to_string(),
+            reference: SerializableStruct::new(recurring_transactions).ok(),
+        }
+    }
+
+    #[allow(non_snake_case)]
+    pub(crate) fn InvalidExpense(details: &str) -> Self {
+        Self {
+            status_code: StatusCode::BAD_REQUEST,
+            api_code: ApiCode::INVALID_EXPENSE,
+            details: details.to_string(),
+            reference: None,
+        }
+    }
+
+    #[allow(non_snake_case)]
+    pub(crate) fn AccountLocked(retry_after_seconds: u64) -> Self {
+        Self {
+            status_code: StatusCode::LOCKED,
+            api_code: ApiCode::ACCOUNT_LOCKED,
+            details: format!("Account is locked! Try again in {} seconds.", retry_after_seconds),
+            reference: None,
+        }
+    }
+
+    #[allow(non_snake_case)]
+    pub(crate) fn TooManyLoginAttempts(retry_after_seconds: u64) -> Self {
+        Self {
+            status_code: StatusCode::TOO_MANY_REQUESTS,
+            api_code: ApiCode::TOO_MANY_LOGIN_ATTEMPTS,
+            details: format!("Too many failed logins! Try again in {} seconds.", retry_after_seconds),
+            reference: None,
+        }
+    }
+
+    #[allow(non_snake_case)]
+    pub(crate) fn InvalidOidcLogin(details: &str) -> Self {
+        Self {
+            status_code: StatusCode::UNAUTHORIZED,
+            api_code: ApiCode::INVALID_OIDC_LOGIN,
+            details: details.to_string(),
+            reference: None,
+        }
+    }
+
+    #[allow(non_snake_case)]
+    pub(crate) fn OidcProviderError(details: &str) -> Self {
+        Self {
+            status_code: StatusCode::BAD_GATEWAY,
+            api_code: ApiCode::OIDC_PROVIDER_ERROR,
+            details: details.to_string(),
+            reference: None,
+        }
+    }
+
+    #[allow(non_snake_case)]
+    pub(crate) fn MailDeliveryFailed(details: &str) -> Self {
+        Self {
+            status_code: StatusCode::INTERNAL_SERVER_ERROR,
+            api_code: ApiCode::MAIL_ERROR,
+            details: format!("Could not deliver mail: {}", details),
+            reference: None,
+        }
+    }
+
     pub(crate) fn from_error_vec(errors: Vec<Self>, status_code: StatusCode) -> Self {
         Self {
             status_code,
@@ -246,3 +342,36 @@ impl From<SnowflakeGeneratorError> for ApiError {
         }
     }
 }
+
+impl From<TotpError> for ApiError {
+    fn from(value: TotpError) -> Self {
+        Self {
+            status_code: StatusCode::INTERNAL_SERVER_ERROR,
+            api_code: ApiCode::TOTP_ERROR,
+            details: value.to_string(),
+            reference: SerializableStruct::new(&value).ok(),
+        }
+    }
+}
+
+impl From<JwtError> for ApiError {
+    fn from(value: JwtError) -> Self {
+        Self {
+            status_code: StatusCode::UNAUTHORIZED,
+            api_code: ApiCode::INVALID_OIDC_LOGIN,
+            details: value.to_string(),
+            reference: SerializableStruct::new(&value).ok(),
+        }
+    }
+}
+
+impl From<ReqwestError> for ApiError {
+    fn from(value: ReqwestError) -> Self {
+        Self {
+            status_code: StatusCode::BAD_GATEWAY,
+            api_code: ApiCode::HTTP_CLIENT_ERROR,
+            details: value.to_string(),
+            reference: None,
+        }
+    }
+}
@@ -45,6 +45,14 @@ api_codes!(
     (1002, INVALID_CREDENTIALS, "Invalid credentials provided!");
     (1004, UNAUTHORIZED, "Unauthorized!");
     (1006, NO_TOKEN_PROVIDED, "No bearer token provided!");
+    (1008, INVALID_TOTP_CODE, "Invalid one-time password provided!");
+    (1010, INVALID_TOTP_CHALLENGE, "Invalid or expired two-factor challenge!");
+    (1012, ACCOUNT_LOCKED, "The account is locked after too many failed logins!");
+    (1014, TOO_MANY_LOGIN_ATTEMPTS, "Too many failed logins, try again later!");
+    (1016, INVALID_OIDC_LOGIN, "The single sign-on login is invalid or expired!");
+    (1018, OIDC_ACCOUNT_NOT_LINKED, "No user is linked to the identity!");
+    (1020, USER_DISABLED, "The user is disabled!");
+    (1022, SESSION_REQUIRED, "The route can only be used with a session, not with an API token!");
 );
 
 // User-causes errors
@@ -53,6 +61,26 @@ api_codes!(
     (1101, SERIALIZATION_ERROR, "Serialization error!");
     (1102, MISSING_PERMISSIONS, "Missing permissions!");
     (1103, CRON_ERROR, "Error while parsing to cron!");
+    (1104, ACCOUNT_ARCHIVED, "The account is archived!");
+    (1105, ACCOUNT_HAS_RECURRING_TRANSACTIONS, "The account is still used by recurring transactions!");
+    (1106, LAST_OWNER, "The last user with full permissions can not be removed!");
+    (1107, INVALID_EXPENSE, "The expense could not be split or settled!");
+    (1108, RESTORE_WINDOW_EXPIRED, "The retention window for restoring has expired!");
+    (1109, TOTP_ALREADY_ENABLED, "Two-factor authentication is already enabled!");
+    (1110, TOTP_NOT_ENABLED, "Two-factor authentication is not enabled!");
+    (1111, INVALID_PASSWORD_RESET_TOKEN, "The password reset token is invalid or expired!");
+    (1112, OIDC_DISABLED, "Single sign-on is not configured!");
+    (1113, ADMIN_SELF_MODIFICATION, "Admins can not disable, delete or demote themselves!");
+    (1114, REGISTRATION_CLOSED, "Registration is closed!");
+    (1115, INVALID_INVITATION_CODE, "The invitation code is missing, invalid, expired or used up!");
+    (1116, INVALID_IDEMPOTENCY_KEY, "The idempotency key is empty, too long or not visible ASCII!");
+    (1117, IDEMPOTENCY_KEY_REUSED, "The idempotency key was already used for a different request!");
+    (1118, IDEMPOTENCY_KEY_IN_USE, "A request with the idempotency key is still being processed!");
+    (1119, PRECONDITION_FAILED, "The resource was changed since the version given in If-Match!");
+    (1120, PRECONDITION_REQUIRED, "The request must contain an If-Match header!");
+    (1121, BATCH_ABORTED, "The batch was rolled back because another operation failed!");
+    (1122, INVALID_SYNC_TOKEN, "The sync token is invalid!");
+    (1123, SYNC_TOKEN_EXPIRED, "The sync token is too old, a full sync is required!");
 );
 
 //validation errors
@@ -68,6 +96,10 @@ api_codes!(
     (1303, CRON_BUILDER_ERROR, "Cron builder error!");
     (1304, TIME_ERROR, "An internal time-error!");
     (1305, SNOWFLAKE_ERROR, "An internal error that occurs when a snowflake could not be generated!");
+    (1306, TOTP_ERROR, "An internal error that occurs when a stored TOTP secret could not be read!");
+    (1307, MAIL_ERROR, "An internal error that occurs when a mail could not be delivered!");
+    (1308, OIDC_PROVIDER_ERROR, "An internal error that occurs when the identity provider misbehaves!");
+    (1309, HTTP_CLIENT_ERROR, "An internal error that occurs when a request to another service failed!");
 );
 
 // misc
@@ -52,6 +52,9 @@ validation_codes!(
 
     // Username related
     (USERNAME_NOT_UNIQUE, "USERNAME_NOT_UNIQUE", "The chosen username is not unique.");
+
+    // Email related
+    (EMAIL_NOT_UNIQUE, "EMAIL_NOT_UNIQUE", "The chosen email is already in use.");
 );
 
 // Account related
@@ -60,6 +63,11 @@ validation_codes!(
     (IBAN_INVALID, "IBAN_INVALID", "IBAN is invalid");
 );
 
+// Permission related
+validation_codes!(
+    (MISSING_READ_PERMISSION, "MISSING_READ_PERMISSION", "The permissions must contain READ.");
+);
+
 // Transaction related
 validation_codes!(
     (SOURCE_AND_DESTINATION_MISSING, "SOURCE_AND_DESTINATION_MISSING", "Source or destination must be present.");
@@ -71,6 +79,17 @@ validation_codes!(
     (INVALID_SPECIAL_FIELD, "INVALID_SPECIAL_FIELD", concatcp!("Invalid special field. Allowed values: {:?}", SPECIALS_STR));
 );
 
+// Batch related
+validation_codes!(
+    (BATCH_ID_MISSING, "BATCH_ID_MISSING", "Updating or deleting needs the id of the entity.");
+    (BATCH_DATA_MISSING, "BATCH_DATA_MISSING", "Creating or updating needs the data of the entity.");
+);
+
+// Response shape related
+validation_codes!(
+    (UNKNOWN_RELATION, "UNKNOWN_RELATION", "Only the relations of the entity can be expanded.");
+);
+
 // Server errors
 validation_codes!(
     (INTERNAL_SERVER_ERROR, "INTERNAL_SERVER_ERROR", "An internal server error occurred blocking us from validating.");
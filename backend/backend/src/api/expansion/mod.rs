@@ -0,0 +1,243 @@
+use std::collections::{HashMap, HashSet};
+use std::mem;
+use std::sync::Arc;
+
+use actix_web::HttpResponse;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, Select};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::{IntoParams, ToSchema};
+use validator::ValidationErrors;
+
+use entity::{account, budget, currency, transaction_template};
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::conditional::{etag, IfNoneMatch};
+use crate::api::error::api::ApiError;
+use crate::api::error::validation::ValidationCode;
+use crate::api::pagination::Pagination;
+use crate::database::entity::find_all;
+use crate::wrapper::entity::account::Account;
+use crate::wrapper::entity::budget::Budget;
+use crate::wrapper::entity::currency::Currency;
+use crate::wrapper::entity::transaction::template::TransactionTemplate;
+use crate::wrapper::entity::{Versioned, WrapperEntity};
+use crate::wrapper::types::phantom::{Identifiable, Phantom};
+
+/// The `expand` and `fields` query parameters that shape the objects a GET route responds with.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct ResponseShape {
+    /// Comma separated relations to embed next to their ids, e.g. `currency,source` embeds the objects of
+    /// `currency_id` and `source_id`. A relation the user can not read is embedded as `null`.
+    expand: Option<String>,
+    /// Comma separated fields to keep, e.g. `id,name,amount`. Expanded relations are always kept.
+    fields: Option<String>,
+}
+
+/// A relation of an entity that can be embedded.
+pub(crate) enum Related<'a> {
+    Account(&'a mut Phantom<Account>),
+    Budget(&'a mut Phantom<Budget>),
+    Currency(&'a mut Phantom<Currency>),
+    TransactionTemplate(&'a mut Phantom<TransactionTemplate>),
+}
+
+pub(crate) trait Expandable: Serialize {
+    /// The names the relations are expanded by, their `_id` field without the suffix.
+    const RELATIONS: &'static [&'static str];
+
+    /// The relation with the given name, `None` if it is not set.
+    fn related(&mut self, name: &str) -> Option<Related<'_>>;
+}
+
+impl ResponseShape {
+    /// Sends one entity like [`IfNoneMatch::respond`]. Embedded objects change without the entity's version, so an
+    /// expanded entity is always sent in full and without an `ETag`.
+    pub(crate) async fn respond<T: Expandable + Versioned>(
+        &self,
+        user_id: Snowflake,
+        entity: T,
+        if_none_match: &IfNoneMatch,
+    ) -> Result<HttpResponse, ApiError> {
+        let expanded = !split(&self.expand).is_empty();
+        let etag = etag(&entity);
+        let body = self.apply(user_id, entity).await?;
+
+        Ok(match expanded {
+            true => HttpResponse::Ok().json(body),
+            false => if_none_match.respond(etag, &body),
+        })
+    }
+
+    pub(crate) async fn apply<T: Expandable>(&self, user_id: Snowflake, entity: T) -> Result<Value, ApiError> {
+        let mut shaped = self.apply_all(user_id, vec![entity]).await?;
+
+        Ok(shaped.remove(0))
+    }
+
+    /// Shapes the page's data, the metadata is kept as it is.
+    pub(crate) async fn apply_page<T>(&self, user_id: Snowflake, mut page: Pagination<T>) -> Result<Value, ApiError>
+    where
+        T: Expandable + ToSchema<'static>,
+    {
+        let data = self.apply_all(user_id, mem::take(&mut page.data)).await?;
+        let mut page = serde_json::to_value(&page)?;
+        page["data"] = Value::Array(data);
+
+        Ok(page)
+    }
+
+    /// Loads the expanded relations of all entities with one query per type instead of one per entity.
+    pub(crate) async fn apply_all<T: Expandable>(
+        &self,
+        user_id: Snowflake,
+        mut entities: Vec<T>,
+    ) -> Result<Vec<Value>, ApiError> {
+        let expand = split(&self.expand);
+        if expand.iter().any(|name| !T::RELATIONS.contains(name)) {
+            let mut errors = ValidationErrors::new();
+            errors.add("expand", ValidationCode::UNKNOWN_RELATION.into());
+            return Err(errors.into());
+        }
+        let fields: Option<HashSet<&str>> = self.fields.as_ref().map(|_| split(&self.fields).into_iter().collect());
+
+        let mut ids = Ids::default();
+        for entity in &mut entities {
+            for name in &expand {
+                if let Some(related) = entity.related(name) {
+                    ids.add(&related);
+                }
+            }
+        }
+        let loaded = Loaded::load(user_id, ids).await?;
+
+        let mut shaped = Vec::with_capacity(entities.len());
+        for mut entity in entities {
+            let mut embedded = Vec::with_capacity(expand.len());
+            for name in &expand {
+                let object = match entity.related(name) {
+                    Some(related) => loaded.embed(related).await?,
+                    None => Value::Null,
+                };
+                embedded.push((name.to_string(), object));
+            }
+
+            let mut value = serde_json::to_value(&entity)?;
+            if let Value::Object(object) = &mut value {
+                if let Some(fields) = &fields {
+                    object.retain(|key, _| fields.contains(key.as_str()));
+                }
+                object.extend(embedded);
+            }
+            shaped.push(value);
+        }
+
+        Ok(shaped)
+    }
+}
+
+fn split(list: &Option<String>) -> Vec<&str> {
+    let mut names: Vec<&str> =
+        list.iter().flat_map(|list| list.split(',')).map(str::trim).filter(|name| !name.is_empty()).collect();
+    names.dedup();
+
+    names
+}
+
+#[derive(Default)]
+struct Ids {
+    accounts: HashSet<Snowflake>,
+    budgets: HashSet<Snowflake>,
+    currencies: HashSet<Snowflake>,
+    templates: HashSet<Snowflake>,
+}
+
+impl Ids {
+    fn add(&mut self, related: &Related<'_>) {
+        match related {
+            Related::Account(phantom) => self.accounts.insert(phantom.get_id()),
+            Related::Budget(phantom) => self.budgets.insert(phantom.get_id()),
+            Related::Currency(phantom) => self.currencies.insert(phantom.get_id()),
+            Related::TransactionTemplate(phantom) => self.templates.insert(phantom.get_id()),
+        };
+    }
+}
+
+/// The related entities the user can read.
+struct Loaded {
+    accounts: HashMap<Snowflake, Arc<Account>>,
+    budgets: HashMap<Snowflake, Arc<Budget>>,
+    currencies: HashMap<Snowflake, Arc<Currency>>,
+    templates: HashMap<Snowflake, Arc<TransactionTemplate>>,
+}
+
+impl Loaded {
+    async fn load(user_id: Snowflake, ids: Ids) -> Result<Self, ApiError> {
+        Ok(Self {
+            accounts: load_readable(account::Entity::find_all_by_user_id(user_id), account::Column::Id, ids.accounts)
+                .await?,
+            budgets: load_readable(budget::Entity::find_all_by_user_id(user_id), budget::Column::Id, ids.budgets)
+                .await?,
+            currencies: load_readable(
+                currency::Entity::find_all_with_no_user_and_user_id(user_id),
+                currency::Column::Id,
+                ids.currencies,
+            )
+            .await?,
+            templates: load_readable(
+                transaction_template::Entity::find_all_by_user_id(user_id),
+                transaction_template::Column::Id,
+                ids.templates,
+            )
+            .await?,
+        })
+    }
+
+    async fn embed(&self, related: Related<'_>) -> Result<Value, ApiError> {
+        match related {
+            Related::Account(phantom) => embed(phantom, &self.accounts).await,
+            Related::Budget(phantom) => embed(phantom, &self.budgets).await,
+            Related::Currency(phantom) => embed(phantom, &self.currencies).await,
+            Related::TransactionTemplate(phantom) => embed(phantom, &self.templates).await,
+        }
+    }
+}
+
+/// The entities with the given ids the select finds, e.g. the ones a user can read.
+pub(crate) async fn load_readable<W, E, C>(
+    select: Select<E>,
+    id: C,
+    ids: HashSet<Snowflake>,
+) -> Result<HashMap<Snowflake, Arc<W>>, ApiError>
+where
+    E: EntityTrait,
+    C: ColumnTrait,
+    W: From<E::Model> + WrapperEntity,
+{
+    if ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let ids: Vec<i64> = ids.iter().map(i64::from).collect();
+    Ok(find_all(select.filter(id.is_in(ids)))
+        .await?
+        .into_iter()
+        .map(W::from)
+        .map(|entity| (entity.get_id(), Arc::new(entity)))
+        .collect())
+}
+
+async fn embed<T>(phantom: &mut Phantom<T>, loaded: &HashMap<Snowflake, Arc<T>>) -> Result<Value, ApiError>
+where
+    T: Identifiable + Serialize + Send + 'static,
+{
+    // relations the user can not read are never fetched
+    let Some(inner) = loaded.get(&phantom.get_id()) else {
+        return Ok(Value::Null);
+    };
+    // primed with the batch loaded entity, so get_inner does not query again
+    phantom.set_inner(inner.clone());
+
+    Ok(serde_json::to_value(&*phantom.get_inner().await?)?)
+}
@@ -0,0 +1,54 @@
+use actix_web::dev::Service;
+use actix_web::web::Json;
+use actix_web::{get, post, web, HttpResponse, Responder};
+
+use crate::api::documentation::response::{Unauthorized, ValidationError};
+use crate::api::error::api::ApiError;
+use crate::api::graphql::dto::GraphqlRequest;
+use crate::api::graphql::{request_data, SCHEMA};
+use crate::util::auth::extract_bearer_token;
+use crate::wrapper::entity::audit::context::Actor;
+use crate::wrapper::entity::user::User;
+use crate::wrapper::types::phantom::Phantom;
+
+pub(crate) fn graphql_controller(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/graphql")
+            .wrap_fn(|req, srv| Actor::scope(extract_bearer_token(req.request()).ok(), srv.call(req)))
+            .service(execute_graphql)
+            .service(graphql_schema),
+    );
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 200, description = "Executed the query. Errors are listed in `errors` and carry the status and code of the REST routes in their `extensions`.", content_type = "application/json", body = Object),
+        ValidationError,
+        Unauthorized,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/graphql",
+    request_body = GraphqlRequest,
+    tag = "GraphQL")]
+#[post("")]
+pub(crate) async fn execute_graphql(
+    user: Phantom<User>,
+    request: Json<GraphqlRequest>,
+) -> Result<impl Responder, ApiError> {
+    let request = request_data(request.into_inner().into(), user.get_id());
+
+    Ok(HttpResponse::Ok().json(SCHEMA.execute(request).await))
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved the GraphQL schema in SDL.", content_type = "text/plain"),
+    ),
+    path = "/api/graphql",
+    tag = "GraphQL")]
+#[get("")]
+pub(crate) async fn graphql_schema() -> impl Responder {
+    HttpResponse::Ok().content_type("text/plain").body(SCHEMA.sdl())
+}
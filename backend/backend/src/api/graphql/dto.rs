@@ -0,0 +1,27 @@
+use async_graphql::Variables;
+use serde::Deserialize;
+use serde_json::Value;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, PartialEq, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GraphqlRequest {
+    pub(crate) query: String,
+    pub(crate) operation_name: Option<String>,
+    #[schema(value_type = Option<Object>)]
+    pub(crate) variables: Option<Value>,
+}
+
+impl From<GraphqlRequest> for async_graphql::Request {
+    fn from(request: GraphqlRequest) -> Self {
+        let mut graphql = Self::new(request.query);
+        if let Some(operation_name) = request.operation_name {
+            graphql = graphql.operation_name(operation_name);
+        }
+        if let Some(variables) = request.variables {
+            graphql = graphql.variables(Variables::from_json(variables));
+        }
+
+        graphql
+    }
+}
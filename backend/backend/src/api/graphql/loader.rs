@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use async_graphql::dataloader::Loader;
+use async_graphql::{Error, ResultExt};
+
+use entity::{account, budget, currency, transaction_template};
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::expansion::load_readable;
+use crate::wrapper::entity::account::Account;
+use crate::wrapper::entity::budget::Budget;
+use crate::wrapper::entity::currency::Currency;
+use crate::wrapper::entity::transaction::template::TransactionTemplate;
+
+/// Loads the `Phantom` relations of all objects in a response with one query per type. Entities the user can not
+/// read are left out, the relation then resolves to `null`.
+pub(crate) struct RelationLoader<T> {
+    user_id: Snowflake,
+    entity: PhantomData<T>,
+}
+
+impl<T> RelationLoader<T> {
+    pub(crate) fn new(user_id: Snowflake) -> Self {
+        Self {
+            user_id,
+            entity: PhantomData,
+        }
+    }
+}
+
+impl Loader<Snowflake> for RelationLoader<Account> {
+    type Value = Arc<Account>;
+    type Error = Error;
+
+    async fn load(&self, keys: &[Snowflake]) -> Result<HashMap<Snowflake, Self::Value>, Self::Error> {
+        let select = account::Entity::find_all_by_user_id(self.user_id);
+        load_readable(select, account::Column::Id, keys.iter().copied().collect()).await.extend()
+    }
+}
+
+impl Loader<Snowflake> for RelationLoader<Budget> {
+    type Value = Arc<Budget>;
+    type Error = Error;
+
+    async fn load(&self, keys: &[Snowflake]) -> Result<HashMap<Snowflake, Self::Value>, Self::Error> {
+        let select = budget::Entity::find_all_by_user_id(self.user_id);
+        load_readable(select, budget::Column::Id, keys.iter().copied().collect()).await.extend()
+    }
+}
+
+impl Loader<Snowflake> for RelationLoader<Currency> {
+    type Value = Arc<Currency>;
+    type Error = Error;
+
+    async fn load(&self, keys: &[Snowflake]) -> Result<HashMap<Snowflake, Self::Value>, Self::Error> {
+        let select = currency::Entity::find_all_with_no_user_and_user_id(self.user_id);
+        load_readable(select, currency::Column::Id, keys.iter().copied().collect()).await.extend()
+    }
+}
+
+impl Loader<Snowflake> for RelationLoader<TransactionTemplate> {
+    type Value = Arc<TransactionTemplate>;
+    type Error = Error;
+
+    async fn load(&self, keys: &[Snowflake]) -> Result<HashMap<Snowflake, Self::Value>, Self::Error> {
+        let select = transaction_template::Entity::find_all_by_user_id(self.user_id);
+        load_readable(select, transaction_template::Column::Id, keys.iter().copied().collect()).await.extend()
+    }
+}
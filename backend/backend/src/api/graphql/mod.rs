@@ -0,0 +1,75 @@
+use std::sync::LazyLock;
+
+use actix_web::http::StatusCode;
+use async_graphql::dataloader::DataLoader;
+use async_graphql::{Context, EmptySubscription, Error, ErrorExtensions, Schema, ID};
+use tracing::error;
+
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::error::api::ApiError;
+use crate::api::graphql::loader::RelationLoader;
+use crate::api::graphql::mutation::MutationRoot;
+use crate::api::graphql::query::QueryRoot;
+use crate::wrapper::entity::account::Account;
+use crate::wrapper::entity::budget::Budget;
+use crate::wrapper::entity::currency::Currency;
+use crate::wrapper::entity::transaction::template::TransactionTemplate;
+
+pub(crate) mod controller;
+pub(crate) mod dto;
+mod loader;
+mod mutation;
+mod object;
+mod pagination;
+mod query;
+
+pub(crate) type FinancrrSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+
+const MAX_DEPTH: usize = 10;
+const MAX_COMPLEXITY: usize = 1000;
+
+pub(crate) static SCHEMA: LazyLock<FinancrrSchema> = LazyLock::new(|| {
+    Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+});
+
+/// The user the request is executed for.
+struct Caller(Snowflake);
+
+/// The data every request is executed with: the user and the loaders, which cache per request.
+pub(crate) fn request_data(request: async_graphql::Request, user_id: Snowflake) -> async_graphql::Request {
+    request
+        .data(Caller(user_id))
+        .data(DataLoader::new(RelationLoader::<Account>::new(user_id), tokio::spawn))
+        .data(DataLoader::new(RelationLoader::<Budget>::new(user_id), tokio::spawn))
+        .data(DataLoader::new(RelationLoader::<Currency>::new(user_id), tokio::spawn))
+        .data(DataLoader::new(RelationLoader::<TransactionTemplate>::new(user_id), tokio::spawn))
+}
+
+fn caller(ctx: &Context<'_>) -> Snowflake {
+    ctx.data_unchecked::<Caller>().0
+}
+
+fn snowflake(id: &ID) -> Result<Snowflake, Error> {
+    Ok(Snowflake::new(id.parse::<i64>()?))
+}
+
+/// The error keeps the status and code it would have had on the REST routes.
+impl ErrorExtensions for ApiError {
+    fn extend(&self) -> Error {
+        if self.status_code == StatusCode::INTERNAL_SERVER_ERROR {
+            error!("Internal server error in GraphQL request: {}", self);
+        }
+
+        Error::new(self.details.clone()).extend_with(|_, extensions| {
+            extensions.set("status", self.status_code.as_u16());
+            extensions.set("code", self.api_code.code);
+            if let Some(reference) = serde_json::to_value(&self.reference).ok().filter(|value| !value.is_null()) {
+                extensions.set("reference", async_graphql::Value::from_json(reference).unwrap_or_default());
+            }
+        })
+    }
+}
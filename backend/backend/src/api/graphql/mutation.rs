@@ -0,0 +1,78 @@
+use async_graphql::{Context, Error, InputObject, Object, ResultExt, ID};
+use serde::Serialize;
+use validator::Validate;
+
+use crate::api::conditional::IfMatch;
+use crate::api::error::api::ApiError;
+use crate::api::graphql::{caller, snowflake};
+use crate::wrapper::entity::transaction::dto::TransactionDTO;
+use crate::wrapper::entity::transaction::Transaction;
+use crate::wrapper::permission::{HasPermissionOrError, Permissions};
+use crate::wrapper::types::phantom::Identifiable;
+
+pub(crate) struct MutationRoot;
+
+/// The body of the transaction routes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, InputObject)]
+pub(crate) struct TransactionInput {
+    source_id: Option<ID>,
+    destination_id: Option<ID>,
+    amount: i64,
+    currency_id: ID,
+    name: String,
+    description: Option<String>,
+    budget_id: Option<ID>,
+    /// An RFC 3339 timestamp.
+    executed_at: String,
+}
+
+impl TryFrom<TransactionInput> for TransactionDTO {
+    type Error = ApiError;
+
+    // read like the body of the REST routes, including its validation
+    fn try_from(input: TransactionInput) -> Result<Self, Self::Error> {
+        let dto: Self = serde_json::from_value(serde_json::to_value(input)?)?;
+        dto.validate()?;
+
+        Ok(dto)
+    }
+}
+
+/// Runs the same checks as the REST routes. Updates and deletes need the version the client last saw, like the
+/// `If-Match` header.
+#[Object]
+impl MutationRoot {
+    async fn create_transaction(&self, ctx: &Context<'_>, input: TransactionInput) -> Result<Transaction, Error> {
+        let dto = TransactionDTO::try_from(input).extend()?;
+        if !dto.check_permissions(caller(ctx)).await.extend()? {
+            return Err(ApiError::Unauthorized()).extend();
+        }
+
+        Transaction::new(dto).await.extend()
+    }
+
+    async fn update_transaction(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        version: i32,
+        input: TransactionInput,
+    ) -> Result<Transaction, Error> {
+        let dto = TransactionDTO::try_from(input).extend()?;
+        let transaction = Transaction::find_by_id(snowflake(&id)?).await.extend()?;
+        transaction.has_permission_or_error(caller(ctx), Permissions::READ_WRITE).await.extend()?;
+        IfMatch::version(version).check(&transaction).extend()?;
+
+        transaction.update(dto).await.extend()
+    }
+
+    /// Returns the id of the deleted transaction.
+    async fn delete_transaction(&self, ctx: &Context<'_>, id: ID, version: i32) -> Result<ID, Error> {
+        let transaction = Transaction::find_by_id(snowflake(&id)?).await.extend()?;
+        transaction.has_permission_or_error(caller(ctx), Permissions::READ_DELETE).await.extend()?;
+        IfMatch::version(version).check(&transaction).extend()?;
+        transaction.delete().await.extend()?;
+
+        Ok(id)
+    }
+}
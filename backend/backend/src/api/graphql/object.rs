@@ -0,0 +1,239 @@
+use std::sync::Arc;
+
+use async_graphql::connection::Connection;
+use async_graphql::dataloader::{DataLoader, Loader};
+use async_graphql::{Context, Error, Object, ID};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use entity::transaction;
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::graphql::loader::RelationLoader;
+use crate::api::graphql::pagination::paginate;
+use crate::wrapper::entity::account::Account;
+use crate::wrapper::entity::budget::Budget;
+use crate::wrapper::entity::currency::Currency;
+use crate::wrapper::entity::transaction::template::TransactionTemplate;
+use crate::wrapper::entity::transaction::Transaction;
+use crate::wrapper::types::phantom::{Identifiable, Phantom};
+
+/// Resolves a relation through the request's loader, so the relations of all objects are loaded together.
+async fn related<T>(ctx: &Context<'_>, phantom: Option<&Phantom<T>>) -> Result<Option<Arc<T>>, Error>
+where
+    T: Identifiable + Send + Sync + 'static,
+    RelationLoader<T>: Loader<Snowflake, Value = Arc<T>, Error = Error>,
+{
+    let Some(phantom) = phantom else {
+        return Ok(None);
+    };
+
+    ctx.data_unchecked::<DataLoader<RelationLoader<T>>>().load_one(phantom.get_id()).await
+}
+
+fn rfc3339(date_time: &OffsetDateTime) -> Result<String, Error> {
+    Ok(date_time.format(&Rfc3339)?)
+}
+
+#[Object]
+impl Account {
+    async fn id(&self) -> ID {
+        ID(self.snowflake.to_string())
+    }
+
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    async fn iban(&self) -> Option<&str> {
+        self.iban.as_deref()
+    }
+
+    async fn balance(&self) -> i64 {
+        self.balance
+    }
+
+    async fn original_balance(&self) -> i64 {
+        self.original_balance
+    }
+
+    async fn currency(&self, ctx: &Context<'_>) -> Result<Option<Arc<Currency>>, Error> {
+        related(ctx, Some(&self.currency_id)).await
+    }
+
+    async fn created_at(&self) -> Result<String, Error> {
+        rfc3339(&self.created_at)
+    }
+
+    async fn archived_at(&self) -> Result<Option<String>, Error> {
+        self.archived_at.as_ref().map(rfc3339).transpose()
+    }
+
+    /// Sent as `If-Match` on the REST routes and as `version` to the mutations.
+    #[graphql(name = "version")]
+    async fn current_version(&self) -> i32 {
+        self.version
+    }
+
+    /// The transactions from or to the account, newest first.
+    async fn transactions(
+        &self,
+        after: Option<String>,
+        first: Option<i32>,
+    ) -> Result<Connection<i64, Transaction>, Error> {
+        let select = transaction::Entity::find_all_by_account_id(self.snowflake);
+        paginate(select, transaction::Column::Id, after, first).await
+    }
+}
+
+#[Object]
+impl Budget {
+    async fn id(&self) -> ID {
+        ID(self.snowflake.to_string())
+    }
+
+    async fn amount(&self) -> i64 {
+        self.amount
+    }
+
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    async fn created_at(&self) -> Result<String, Error> {
+        rfc3339(&self.created_at)
+    }
+
+    #[graphql(name = "version")]
+    async fn current_version(&self) -> i32 {
+        self.version
+    }
+
+    /// The transactions booked on the budget, newest first.
+    async fn transactions(
+        &self,
+        after: Option<String>,
+        first: Option<i32>,
+    ) -> Result<Connection<i64, Transaction>, Error> {
+        let select = transaction::Entity::find_all_by_budget_id(self.snowflake);
+        paginate(select, transaction::Column::Id, after, first).await
+    }
+}
+
+#[Object]
+impl Currency {
+    async fn id(&self) -> ID {
+        ID(self.snowflake.to_string())
+    }
+
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    async fn iso_code(&self) -> Option<&str> {
+        self.iso_code.as_deref()
+    }
+
+    async fn decimal_places(&self) -> i32 {
+        self.decimal_places
+    }
+}
+
+#[Object]
+impl Transaction {
+    async fn id(&self) -> ID {
+        ID(self.snowflake.to_string())
+    }
+
+    async fn source(&self, ctx: &Context<'_>) -> Result<Option<Arc<Account>>, Error> {
+        related(ctx, self.source_id.as_ref()).await
+    }
+
+    async fn destination(&self, ctx: &Context<'_>) -> Result<Option<Arc<Account>>, Error> {
+        related(ctx, self.destination_id.as_ref()).await
+    }
+
+    async fn amount(&self) -> i64 {
+        self.amount
+    }
+
+    async fn currency(&self, ctx: &Context<'_>) -> Result<Option<Arc<Currency>>, Error> {
+        related(ctx, Some(&self.currency_id)).await
+    }
+
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    async fn budget(&self, ctx: &Context<'_>) -> Result<Option<Arc<Budget>>, Error> {
+        related(ctx, self.budget_id.as_ref()).await
+    }
+
+    async fn created_at(&self) -> Result<String, Error> {
+        rfc3339(&self.created_at)
+    }
+
+    async fn executed_at(&self) -> Result<String, Error> {
+        rfc3339(&self.executed_at)
+    }
+
+    #[graphql(name = "version")]
+    async fn current_version(&self) -> i32 {
+        self.version
+    }
+}
+
+#[Object]
+impl TransactionTemplate {
+    async fn id(&self) -> ID {
+        ID(self.snowflake.to_string())
+    }
+
+    async fn source(&self, ctx: &Context<'_>) -> Result<Option<Arc<Account>>, Error> {
+        related(ctx, self.source_id.as_ref()).await
+    }
+
+    async fn destination(&self, ctx: &Context<'_>) -> Result<Option<Arc<Account>>, Error> {
+        related(ctx, self.destination_id.as_ref()).await
+    }
+
+    async fn amount(&self) -> i64 {
+        self.amount
+    }
+
+    async fn currency(&self, ctx: &Context<'_>) -> Result<Option<Arc<Currency>>, Error> {
+        related(ctx, Some(&self.currency_id)).await
+    }
+
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    async fn budget(&self, ctx: &Context<'_>) -> Result<Option<Arc<Budget>>, Error> {
+        related(ctx, self.budget_id.as_ref()).await
+    }
+
+    async fn created_at(&self) -> Result<String, Error> {
+        rfc3339(&self.created_at)
+    }
+}
@@ -0,0 +1,40 @@
+use async_graphql::connection::{query, Connection, Edge};
+use async_graphql::{Error, OutputType, ResultExt};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QuerySelect, Select};
+
+use crate::api::pagination::{DEFAULT_LIMIT, MAX_LIMIT};
+use crate::database::entity::find_all;
+use crate::wrapper::entity::WrapperEntity;
+
+/// Pages forward through a select that is ordered by id, newest first. The cursor is the id of an entity, so pages
+/// stay stable while new entities are created.
+pub(super) async fn paginate<W, E, C>(
+    select: Select<E>,
+    id: C,
+    after: Option<String>,
+    first: Option<i32>,
+) -> Result<Connection<i64, W>, Error>
+where
+    E: EntityTrait,
+    C: ColumnTrait,
+    W: From<E::Model> + WrapperEntity + OutputType,
+{
+    query(after, None, first, None, |after: Option<i64>, _, first, _| async move {
+        let first = first.map_or(DEFAULT_LIMIT, |first| first as u64).min(MAX_LIMIT);
+        let select = match after {
+            Some(after) => select.filter(id.lt(after)),
+            None => select,
+        };
+
+        // one more than asked for tells if there is another page
+        let mut entities = find_all(select.limit(first + 1)).await.extend()?;
+        let has_next_page = entities.len() as u64 > first;
+        entities.truncate(first as usize);
+
+        let mut connection = Connection::new(after.is_some(), has_next_page);
+        connection.edges.extend(entities.into_iter().map(W::from).map(|entity| Edge::new(entity.get_id().id, entity)));
+
+        Ok::<_, Error>(connection)
+    })
+    .await
+}
@@ -0,0 +1,100 @@
+use async_graphql::connection::Connection;
+use async_graphql::{Context, Error, Object, ResultExt, ID};
+
+use entity::{account, budget, currency, transaction, transaction_template};
+
+use crate::api::graphql::pagination::paginate;
+use crate::api::graphql::{caller, snowflake};
+use crate::wrapper::entity::account::Account;
+use crate::wrapper::entity::budget::Budget;
+use crate::wrapper::entity::currency::Currency;
+use crate::wrapper::entity::transaction::template::TransactionTemplate;
+use crate::wrapper::entity::transaction::Transaction;
+use crate::wrapper::permission::{HasPermissionOrError, Permissions};
+use crate::wrapper::types::phantom::Identifiable;
+
+pub(crate) struct QueryRoot;
+
+/// Lists contain what the user can read, newest first. Single entities are checked like on their REST routes.
+#[Object]
+impl QueryRoot {
+    async fn accounts(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        first: Option<i32>,
+    ) -> Result<Connection<i64, Account>, Error> {
+        paginate(account::Entity::find_all_by_user_id(caller(ctx)), account::Column::Id, after, first).await
+    }
+
+    async fn account(&self, ctx: &Context<'_>, id: ID) -> Result<Account, Error> {
+        let account = Account::find_by_id(snowflake(&id)?).await.extend()?;
+        account.has_permission_or_error(caller(ctx), Permissions::READ).await.extend()?;
+
+        Ok(account)
+    }
+
+    async fn budgets(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        first: Option<i32>,
+    ) -> Result<Connection<i64, Budget>, Error> {
+        paginate(budget::Entity::find_all_by_user_id(caller(ctx)), budget::Column::Id, after, first).await
+    }
+
+    async fn budget(&self, ctx: &Context<'_>, id: ID) -> Result<Budget, Error> {
+        let budget = Budget::find_by_id(snowflake(&id)?).await.extend()?;
+        budget.has_permission_or_error(caller(ctx), Permissions::READ).await.extend()?;
+
+        Ok(budget)
+    }
+
+    /// The global currencies and the ones of the user.
+    async fn currencies(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        first: Option<i32>,
+    ) -> Result<Connection<i64, Currency>, Error> {
+        let select = currency::Entity::find_all_with_no_user_and_user_id(caller(ctx));
+        paginate(select, currency::Column::Id, after, first).await
+    }
+
+    async fn currency(&self, ctx: &Context<'_>, id: ID) -> Result<Currency, Error> {
+        Currency::find_by_id_include_user(snowflake(&id)?, caller(ctx)).await.extend()
+    }
+
+    async fn transactions(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        first: Option<i32>,
+    ) -> Result<Connection<i64, Transaction>, Error> {
+        paginate(transaction::Entity::find_all_by_user_id(caller(ctx)), transaction::Column::Id, after, first).await
+    }
+
+    async fn transaction(&self, ctx: &Context<'_>, id: ID) -> Result<Transaction, Error> {
+        let transaction = Transaction::find_by_id(snowflake(&id)?).await.extend()?;
+        transaction.has_permission_or_error(caller(ctx), Permissions::READ).await.extend()?;
+
+        Ok(transaction)
+    }
+
+    async fn transaction_templates(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        first: Option<i32>,
+    ) -> Result<Connection<i64, TransactionTemplate>, Error> {
+        let select = transaction_template::Entity::find_all_by_user_id(caller(ctx));
+        paginate(select, transaction_template::Column::Id, after, first).await
+    }
+
+    async fn transaction_template(&self, ctx: &Context<'_>, id: ID) -> Result<TransactionTemplate, Error> {
+        let template = TransactionTemplate::find_by_id(snowflake(&id)?).await.extend()?;
+        template.has_permission_or_error(caller(ctx), Permissions::READ).await.extend()?;
+
+        Ok(template)
+    }
+}
@@ -0,0 +1,176 @@
+use std::pin::Pin;
+
+use actix_web::body::{to_bytes, BoxBody, MessageBody};
+use actix_web::dev::{Payload, ServiceRequest, ServiceResponse};
+use actix_web::error::{ErrorInternalServerError, PayloadError};
+use actix_web::http::header::{HeaderName, HeaderValue, CONTENT_TYPE, ETAG};
+use actix_web::http::{Method, StatusCode};
+use actix_web::middleware::Next;
+use actix_web::web::Bytes;
+use actix_web::{Error, HttpResponse};
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use futures_util::stream::{once, Stream};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::error;
+
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::error::api::ApiError;
+use crate::config::Config;
+use crate::database::redis::{del, get, set_ex, set_nx_ex};
+use crate::util::auth::authenticate;
+
+pub(crate) const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+pub(crate) const IDEMPOTENCY_REPLAYED_HEADER: &str = "Idempotency-Replayed";
+const MAX_KEY_LENGTH: usize = 255;
+// the headers that describe the body, the rest is added again by the middlewares when replaying
+const STORED_HEADERS: [HeaderName; 2] = [CONTENT_TYPE, ETAG];
+
+/// What is stored under a key. The response is missing while the first request is still being handled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IdempotencyRecord {
+    fingerprint: String,
+    response: Option<StoredResponse>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    // base64, so bodies that are not valid utf-8 survive the round trip
+    body: String,
+}
+
+/// Replays the stored response when a POST, PATCH or DELETE is retried with the same `Idempotency-Key`. Keys are
+/// scoped to the user, and a key reused for a different request is rejected. Server errors are not stored, so the
+/// request can be retried with the same key.
+pub(crate) async fn idempotency(
+    mut req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let is_mutation = matches!(*req.method(), Method::POST | Method::PATCH | Method::DELETE);
+    let key = req.headers().get(IDEMPOTENCY_KEY_HEADER).map(|key| key.to_str().map(str::to_string));
+    let (true, Some(key)) = (is_mutation, key) else {
+        return Ok(next.call(req).await?.map_into_boxed_body());
+    };
+    let key = match key {
+        Ok(key) if !key.is_empty() && key.len() <= MAX_KEY_LENGTH => key,
+        _ => return Err(ApiError::InvalidIdempotencyKey().into()),
+    };
+    // anonymous requests are left to the route, which turns them away or has nothing to duplicate
+    let Ok(user_id) = authenticate(req.request()).await else {
+        return Ok(next.call(req).await?.map_into_boxed_body());
+    };
+
+    let body = req.extract::<Bytes>().await?;
+    let fingerprint = fingerprint(&req, &body);
+    let stream: Pin<Box<dyn Stream<Item = Result<Bytes, PayloadError>>>> = Box::pin(once(async { Ok(body) }));
+    req.set_payload(Payload::from(stream));
+
+    let redis_key = redis_key(user_id, &key);
+    if !claim(&redis_key, &fingerprint).await? {
+        let record = find_record(&redis_key).await?;
+        return match record {
+            Some(record) if record.fingerprint != fingerprint => Err(ApiError::IdempotencyKeyReused().into()),
+            Some(IdempotencyRecord {
+                response: Some(response),
+                ..
+            }) => Ok(req.into_response(replay(response)?)),
+            // the first request is still running, or it expired in between
+            _ => Err(ApiError::IdempotencyKeyInUse().into()),
+        };
+    }
+
+    let response = match next.call(req).await {
+        Ok(response) => response,
+        Err(e) => {
+            release(&redis_key).await;
+            return Err(e);
+        }
+    };
+    if response.status().is_server_error() {
+        release(&redis_key).await;
+        return Ok(response.map_into_boxed_body());
+    }
+
+    let (req, response) = response.into_parts();
+    let (response, body) = response.into_parts();
+    let body = to_bytes(body).await.map_err(|e| ErrorInternalServerError(e.into()))?;
+    let stored = StoredResponse {
+        status: response.status().as_u16(),
+        headers: STORED_HEADERS
+            .iter()
+            .filter_map(|name| {
+                let value = response.headers().get(name)?.to_str().ok()?;
+                Some((name.to_string(), value.to_string()))
+            })
+            .collect(),
+        body: STANDARD.encode(&body),
+    };
+    store(&redis_key, fingerprint, stored).await?;
+
+    Ok(ServiceResponse::new(req, response.set_body(body).map_into_boxed_body()))
+}
+
+fn redis_key(user_id: Snowflake, key: &str) -> String {
+    format!("idempotency:{}:{}", user_id, key)
+}
+
+fn fingerprint(req: &ServiceRequest, body: &Bytes) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(req.method().as_str());
+    hasher.update([0]);
+    hasher.update(req.path());
+    hasher.update([0]);
+    hasher.update(req.query_string());
+    hasher.update([0]);
+    hasher.update(body);
+
+    URL_SAFE_NO_PAD.encode(hasher.finalize())
+}
+
+async fn claim(redis_key: &str, fingerprint: &str) -> Result<bool, ApiError> {
+    let record = IdempotencyRecord {
+        fingerprint: fingerprint.to_string(),
+        response: None,
+    };
+
+    set_nx_ex(redis_key.to_string(), serde_json::to_string(&record)?, Config::get_config().idempotency.ttl_seconds)
+        .await
+}
+
+async fn find_record(redis_key: &str) -> Result<Option<IdempotencyRecord>, ApiError> {
+    let record: Option<String> = get(redis_key.to_string()).await?;
+
+    Ok(record.map(|record| serde_json::from_str(&record)).transpose()?)
+}
+
+async fn store(redis_key: &str, fingerprint: String, response: StoredResponse) -> Result<(), ApiError> {
+    let record = IdempotencyRecord {
+        fingerprint,
+        response: Some(response),
+    };
+
+    set_ex(redis_key.to_string(), serde_json::to_string(&record)?, Config::get_config().idempotency.ttl_seconds).await
+}
+
+// a failed request must not block its retries until the key expires
+async fn release(redis_key: &str) {
+    if let Err(e) = del(redis_key.to_string()).await {
+        error!("Could not release idempotency key {}: {}", redis_key, e);
+    }
+}
+
+fn replay(response: StoredResponse) -> Result<HttpResponse, Error> {
+    let status = StatusCode::from_u16(response.status).map_err(ErrorInternalServerError)?;
+    let body = STANDARD.decode(response.body).map_err(ErrorInternalServerError)?;
+    let mut builder = HttpResponse::build(status);
+    builder.insert_header((IDEMPOTENCY_REPLAYED_HEADER, HeaderValue::from_static("true")));
+    for header in response.headers {
+        builder.insert_header(header);
+    }
+
+    Ok(builder.body(body))
+}
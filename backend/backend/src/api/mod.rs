@@ -1,5 +1,9 @@
+pub(crate) mod conditional;
 pub(crate) mod documentation;
 pub(crate) mod error;
+pub(crate) mod expansion;
+pub(crate) mod graphql;
+pub(crate) mod idempotency;
 pub(crate) mod pagination;
 pub(crate) mod routes;
 pub(crate) mod status;
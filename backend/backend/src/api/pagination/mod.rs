@@ -12,13 +12,25 @@ use utoipa::{IntoParams, ToSchema};
 use validator::Validate;
 
 use crate::api::error::api::ApiError;
+use crate::event::outbox::dead_letter::DeadLetter;
 use crate::wrapper::entity::account::Account;
+use crate::wrapper::entity::api_token::ApiToken;
+use crate::wrapper::entity::audit::AuditLog;
 use crate::wrapper::entity::budget::Budget;
 use crate::wrapper::entity::currency::Currency;
+use crate::wrapper::entity::expense::Expense;
+use crate::wrapper::entity::group::Group;
+use crate::wrapper::entity::invitation::Invitation;
+use crate::wrapper::entity::login_attempt::LoginAttempt;
 use crate::wrapper::entity::session::Session;
+use crate::wrapper::entity::transaction::history::TransactionVersion;
 use crate::wrapper::entity::transaction::recurring::RecurringTransaction;
 use crate::wrapper::entity::transaction::template::TransactionTemplate;
 use crate::wrapper::entity::transaction::Transaction;
+use crate::wrapper::entity::trash::TrashEntry;
+use crate::wrapper::entity::user::User;
+use crate::wrapper::entity::webhook::delivery::WebhookDelivery;
+use crate::wrapper::entity::webhook::Webhook;
 
 pub(crate) const DEFAULT_PAGE: u64 = 1;
 pub(crate) const DEFAULT_LIMIT: u64 = 50;
@@ -27,12 +39,24 @@ pub(crate) const MAX_LIMIT: u64 = 500;
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
 #[aliases(
     PaginatedAccount = Pagination < Account >,
+    PaginatedApiToken = Pagination < ApiToken >,
+    PaginatedAuditLog = Pagination < AuditLog >,
     PaginatedBudget = Pagination < Budget >,
     PaginatedCurrency = Pagination < Currency >,
+    PaginatedExpense = Pagination < Expense >,
+    PaginatedGroup = Pagination < Group >,
+    PaginatedInvitation = Pagination < Invitation >,
+    PaginatedDeadLetter = Pagination < DeadLetter >,
+    PaginatedLoginAttempt = Pagination < LoginAttempt >,
     PaginatedTransaction = Pagination < Transaction >,
     PaginatedTransactionTemplate = Pagination < TransactionTemplate >,
+    PaginatedTransactionVersion = Pagination < TransactionVersion >,
+    PaginatedTrashEntry = Pagination < TrashEntry >,
     PaginatedRecurringTransaction = Pagination < RecurringTransaction >,
     PaginatedSession = Pagination < Session >,
+    PaginatedUser = Pagination < User >,
+    PaginatedWebhook = Pagination < Webhook >,
+    PaginatedWebhookDelivery = Pagination < WebhookDelivery >,
 )]
 pub(crate) struct Pagination<T: Serialize + ToSchema<'static>> {
     #[serde(rename = "_metadata")]
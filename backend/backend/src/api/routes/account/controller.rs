@@ -1,15 +1,23 @@
 use actix_web::http::Uri;
-use actix_web::web::Path;
+use actix_web::web::{Json, Path, Query};
 use actix_web::{delete, get, patch, post, web, HttpResponse, Responder};
+use actix_web_validation::Validated;
 
 use utility::snowflake::entity::Snowflake;
 
-use crate::api::documentation::response::{InternalServerError, ResourceNotFound, Unauthorized, ValidationError};
+use crate::api::conditional::{versioned_response, IfMatch, IfNoneMatch};
+use crate::api::documentation::response::{
+    InternalServerError, PreconditionFailed, PreconditionRequired, ResourceNotFound, Unauthorized, ValidationError,
+};
 use crate::api::error::api::ApiError;
+use crate::api::expansion::ResponseShape;
 use crate::api::pagination::{PageSizeParam, PaginatedAccount, Pagination};
 use crate::wrapper::entity::account::dto::AccountDTO;
 use crate::wrapper::entity::account::Account;
+use crate::wrapper::entity::group::dto::GroupGrantDTO;
 use crate::wrapper::entity::user::User;
+use crate::wrapper::permission::collaborator::dto::CollaboratorDTO;
+use crate::wrapper::permission::collaborator::Shareable;
 use crate::wrapper::permission::{HasPermissionByIdOrError, HasPermissionOrError, Permissions};
 use crate::wrapper::types::phantom::{Identifiable, Phantom};
 
@@ -17,10 +25,20 @@ pub(crate) fn account_controller(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/account")
             .service(get_all_accounts)
+            .service(get_all_archived_accounts)
             .service(get_transactions_from_account)
             .service(create_account)
+            .service(archive_account)
+            .service(unarchive_account)
             .service(delete_account)
             .service(update_account)
+            .service(get_collaborators_of_account)
+            .service(share_account)
+            .service(leave_account)
+            .service(revoke_account_collaborator)
+            .service(get_groups_of_account)
+            .service(share_account_with_group)
+            .service(revoke_account_group)
             .service(get_one_account),
     );
 }
@@ -31,7 +49,7 @@ pub(crate) fn account_controller(cfg: &mut web::ServiceConfig) {
         ValidationError,
         Unauthorized,
     ),
-    params(PageSizeParam),
+    params(PageSizeParam, ResponseShape),
     security(
         ("bearer_token" = [])
     ),
@@ -41,17 +59,47 @@ pub(crate) fn account_controller(cfg: &mut web::ServiceConfig) {
 pub(crate) async fn get_all_accounts(
     user: Phantom<User>,
     page_size: PageSizeParam,
+    shape: Query<ResponseShape>,
     uri: Uri,
 ) -> Result<impl Responder, ApiError> {
     let total = Account::count_all_by_user(user.get_id()).await?;
     let result = Account::find_all_by_user(user.get_id()).await?;
+    let page = PaginatedAccount::new(result, &page_size, total, uri);
 
-    Ok(HttpResponse::Ok().json(PaginatedAccount::new(result, &page_size, total, uri)))
+    Ok(HttpResponse::Ok().json(shape.apply_page(user.get_id(), page).await?))
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved all archived Accounts.", content_type = "application/json", body = PaginatedAccount),
+        ValidationError,
+        Unauthorized,
+    ),
+    params(PageSizeParam, ResponseShape),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/account/archived",
+    tag = "Account")]
+#[get("/archived")]
+pub(crate) async fn get_all_archived_accounts(
+    user: Phantom<User>,
+    page_size: PageSizeParam,
+    shape: Query<ResponseShape>,
+    uri: Uri,
+) -> Result<impl Responder, ApiError> {
+    let total = Account::count_all_archived_by_user(user.get_id()).await?;
+    let result = Account::find_all_archived_by_user_paginated(user.get_id(), &page_size).await?;
+    let page = PaginatedAccount::new(result, &page_size, total, uri);
+
+    Ok(HttpResponse::Ok().json(shape.apply_page(user.get_id(), page).await?))
 }
 
 #[utoipa::path(get,
     responses(
         (status = 200, description = "Successfully retrieved Account.", content_type = "application/json", body = Account),
+        (status = 304, description = "The Account did not change since the ETag given in If-None-Match."),
+        ValidationError,
         Unauthorized,
         ResourceNotFound,
         InternalServerError,
@@ -59,23 +107,26 @@ pub(crate) async fn get_all_accounts(
     security(
         ("bearer_token" = [])
     ),
-    params(("account_id" = Snowflake,)),
+    params(("account_id" = Snowflake,), ResponseShape, ("If-None-Match" = Option<String>, Header, description = "The ETag of the copy the client already has.")),
     path = "/api/v1/account/{account_id}",
     tag = "Account")]
 #[get("/{account_id}")]
 pub(crate) async fn get_one_account(
     user: Phantom<User>,
     account_id: Path<Snowflake>,
+    shape: Query<ResponseShape>,
+    if_none_match: IfNoneMatch,
 ) -> Result<impl Responder, ApiError> {
     let account = Account::find_by_id(account_id.into_inner()).await?;
     account.has_permission_or_error(user.get_id(), Permissions::READ).await?;
 
-    Ok(HttpResponse::Ok().json(account))
+    shape.respond(user.get_id(), account, &if_none_match).await
 }
 
 #[utoipa::path(get,
     responses(
         (status = 200, description = "Successfully retrieved all Transactions.", content_type = "application/json", body = PaginatedTransaction),
+        ValidationError,
         Unauthorized,
         ResourceNotFound,
         InternalServerError,
@@ -83,7 +134,7 @@ pub(crate) async fn get_one_account(
     security(
         ("bearer_token" = [])
     ),
-    params(("account_id" = Snowflake,), PageSizeParam),
+    params(("account_id" = Snowflake,), PageSizeParam, ResponseShape),
     path = "/api/v1/account/{account_id}/transactions",
     tag = "Account")]
 #[get("/{account_id}/transactions")]
@@ -91,6 +142,7 @@ pub(crate) async fn get_transactions_from_account(
     user: Phantom<User>,
     account_id: Path<Snowflake>,
     page_size: PageSizeParam,
+    shape: Query<ResponseShape>,
     uri: Uri,
 ) -> Result<impl Responder, ApiError> {
     let account_id = account_id.into_inner();
@@ -100,7 +152,7 @@ pub(crate) async fn get_transactions_from_account(
     let total = Account::count_transactions_by_account_id(account_id).await?;
     let pagination = Pagination::new(transactions, &page_size, total, uri);
 
-    Ok(HttpResponse::Ok().json(pagination))
+    Ok(HttpResponse::Ok().json(shape.apply_page(user.get_id(), pagination).await?))
 }
 
 #[utoipa::path(post,
@@ -119,7 +171,9 @@ request_body = AccountDTO,
 tag = "Account")]
 #[post("")]
 pub(crate) async fn create_account(user: Phantom<User>, account: AccountDTO) -> Result<impl Responder, ApiError> {
-    Ok(HttpResponse::Created().json(Account::new(account, user.get_id()).await?))
+    let account = Account::new(account, user.get_id()).await?;
+
+    Ok(versioned_response(HttpResponse::Created(), &account))
 }
 
 #[utoipa::path(delete,
@@ -127,21 +181,25 @@ pub(crate) async fn create_account(user: Phantom<User>, account: AccountDTO) ->
         (status = 204, description = "Successfully deleted an Account."),
         Unauthorized,
         ResourceNotFound,
+        PreconditionFailed,
+        PreconditionRequired,
         InternalServerError,
     ),
     security(
         ("bearer_token" = [])
     ),
-    params(("account_id" = Snowflake,)),
+    params(("account_id" = Snowflake,), ("If-Match" = String, Header, description = "The ETag the client last saw, or `*` to skip the check.")),
     path = "/api/v1/account/{account_id}",
     tag = "Account")]
 #[delete("/{account_id}")]
 pub(crate) async fn delete_account(
     user: Phantom<User>,
     account_id: Path<Snowflake>,
+    if_match: IfMatch,
 ) -> Result<impl Responder, ApiError> {
     let account = Account::find_by_id(account_id.into_inner()).await?;
     account.has_permission_or_error(user.get_id(), Permissions::READ_DELETE).await?;
+    if_match.check(&account)?;
 
     account.delete().await?;
 
@@ -154,12 +212,14 @@ responses(
 Unauthorized,
 ValidationError,
 ResourceNotFound,
+PreconditionFailed,
+PreconditionRequired,
 InternalServerError,
 ),
 security(
 ("bearer_token" = [])
 ),
-params(("account_id" = Snowflake,)),
+params(("account_id" = Snowflake,), ("If-Match" = String, Header, description = "The ETag the client last saw, or `*` to skip the check.")),
 path = "/api/v1/account/{account_id}",
 request_body = AccountDTO,
 tag = "Account")]
@@ -168,11 +228,253 @@ pub(crate) async fn update_account(
     user: Phantom<User>,
     updated_account: AccountDTO,
     account_id: Path<Snowflake>,
+    if_match: IfMatch,
 ) -> Result<impl Responder, ApiError> {
     let account = Account::find_by_id(account_id.into_inner()).await?;
     account.has_permission_or_error(user.get_id(), Permissions::READ_WRITE).await?;
+    if_match.check(&account)?;
 
     let account = account.update(updated_account).await?;
 
-    Ok(HttpResponse::Ok().json(account))
+    Ok(versioned_response(HttpResponse::Ok(), &account))
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 200, description = "Successfully archived an Account.", content_type = "application/json", body = Account),
+        (status = 409, description = "The Account is still used by recurring transactions.", content_type = "application/json", body = ApiError),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("account_id" = Snowflake,)),
+    path = "/api/v1/account/{account_id}/archive",
+    tag = "Account")]
+#[post("/{account_id}/archive")]
+pub(crate) async fn archive_account(
+    user: Phantom<User>,
+    account_id: Path<Snowflake>,
+) -> Result<impl Responder, ApiError> {
+    let account = Account::find_by_id(account_id.into_inner()).await?;
+    account.has_permission_or_error(user.get_id(), Permissions::READ_WRITE).await?;
+
+    let account = account.archive().await?;
+
+    Ok(versioned_response(HttpResponse::Ok(), &account))
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 200, description = "Successfully unarchived an Account.", content_type = "application/json", body = Account),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("account_id" = Snowflake,)),
+    path = "/api/v1/account/{account_id}/unarchive",
+    tag = "Account")]
+#[post("/{account_id}/unarchive")]
+pub(crate) async fn unarchive_account(
+    user: Phantom<User>,
+    account_id: Path<Snowflake>,
+) -> Result<impl Responder, ApiError> {
+    let account = Account::find_by_id(account_id.into_inner()).await?;
+    account.has_permission_or_error(user.get_id(), Permissions::READ_WRITE).await?;
+
+    let account = account.unarchive().await?;
+
+    Ok(versioned_response(HttpResponse::Ok(), &account))
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved all Collaborators of the Account.", content_type = "application/json", body = [Collaborator]),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("account_id" = Snowflake,)),
+    path = "/api/v1/account/{account_id}/collaborators",
+    tag = "Account")]
+#[get("/{account_id}/collaborators")]
+pub(crate) async fn get_collaborators_of_account(
+    user: Phantom<User>,
+    account_id: Path<Snowflake>,
+) -> Result<impl Responder, ApiError> {
+    let account = Account::find_by_id(account_id.into_inner()).await?;
+    account.has_permission_or_error(user.get_id(), Permissions::READ).await?;
+
+    Ok(HttpResponse::Ok().json(account.find_collaborators().await?))
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 200, description = "Successfully shared the Account.", content_type = "application/json", body = Collaborator),
+        (status = 409, description = "The last user with full permissions can not be downgraded.", content_type = "application/json", body = ApiError),
+        Unauthorized,
+        ValidationError,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("account_id" = Snowflake,)),
+    path = "/api/v1/account/{account_id}/collaborators",
+    request_body = CollaboratorDTO,
+    tag = "Account")]
+#[post("/{account_id}/collaborators")]
+pub(crate) async fn share_account(
+    user: Phantom<User>,
+    account_id: Path<Snowflake>,
+    collaborator: Validated<Json<CollaboratorDTO>>,
+) -> Result<impl Responder, ApiError> {
+    let account = Account::find_by_id(account_id.into_inner()).await?;
+    account.has_permission_or_error(user.get_id(), Permissions::all()).await?;
+
+    let collaborator = account.share(collaborator.into_inner().into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(collaborator))
+}
+
+#[utoipa::path(delete,
+    responses(
+        (status = 204, description = "Successfully left the Account."),
+        (status = 409, description = "The last user with full permissions can not leave.", content_type = "application/json", body = ApiError),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("account_id" = Snowflake,)),
+    path = "/api/v1/account/{account_id}/collaborators/@me",
+    tag = "Account")]
+#[delete("/{account_id}/collaborators/@me")]
+pub(crate) async fn leave_account(
+    user: Phantom<User>,
+    account_id: Path<Snowflake>,
+) -> Result<impl Responder, ApiError> {
+    let account = Account::find_by_id(account_id.into_inner()).await?;
+    account.revoke(user.get_id()).await?;
+
+    Ok(HttpResponse::NoContent())
+}
+
+#[utoipa::path(delete,
+    responses(
+        (status = 204, description = "Successfully revoked the access of a Collaborator."),
+        (status = 409, description = "The last user with full permissions can not be removed.", content_type = "application/json", body = ApiError),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("account_id" = Snowflake,), ("user_id" = Snowflake,)),
+    path = "/api/v1/account/{account_id}/collaborators/{user_id}",
+    tag = "Account")]
+#[delete("/{account_id}/collaborators/{user_id}")]
+pub(crate) async fn revoke_account_collaborator(
+    user: Phantom<User>,
+    path: Path<(Snowflake, Snowflake)>,
+) -> Result<impl Responder, ApiError> {
+    let (account_id, user_id) = path.into_inner();
+    let account = Account::find_by_id(account_id).await?;
+    account.has_permission_or_error(user.get_id(), Permissions::all()).await?;
+
+    account.revoke(user_id).await?;
+
+    Ok(HttpResponse::NoContent())
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved all Groups the Account is shared with.", content_type = "application/json", body = [GroupGrant]),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("account_id" = Snowflake,)),
+    path = "/api/v1/account/{account_id}/groups",
+    tag = "Account")]
+#[get("/{account_id}/groups")]
+pub(crate) async fn get_groups_of_account(
+    user: Phantom<User>,
+    account_id: Path<Snowflake>,
+) -> Result<impl Responder, ApiError> {
+    let account = Account::find_by_id(account_id.into_inner()).await?;
+    account.has_permission_or_error(user.get_id(), Permissions::READ).await?;
+
+    Ok(HttpResponse::Ok().json(account.find_group_grants().await?))
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 200, description = "Successfully shared the Account with a Group.", content_type = "application/json", body = GroupGrant),
+        Unauthorized,
+        ValidationError,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("account_id" = Snowflake,)),
+    path = "/api/v1/account/{account_id}/groups",
+    request_body = GroupGrantDTO,
+    tag = "Account")]
+#[post("/{account_id}/groups")]
+pub(crate) async fn share_account_with_group(
+    user: Phantom<User>,
+    account_id: Path<Snowflake>,
+    grant: Validated<Json<GroupGrantDTO>>,
+) -> Result<impl Responder, ApiError> {
+    let account = Account::find_by_id(account_id.into_inner()).await?;
+    account.has_permission_or_error(user.get_id(), Permissions::all()).await?;
+
+    let grant = account.share_with_group(user.get_id(), grant.into_inner().into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(grant))
+}
+
+#[utoipa::path(delete,
+    responses(
+        (status = 204, description = "Successfully revoked the access of a Group."),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("account_id" = Snowflake,), ("group_id" = Snowflake,)),
+    path = "/api/v1/account/{account_id}/groups/{group_id}",
+    tag = "Account")]
+#[delete("/{account_id}/groups/{group_id}")]
+pub(crate) async fn revoke_account_group(
+    user: Phantom<User>,
+    path: Path<(Snowflake, Snowflake)>,
+) -> Result<impl Responder, ApiError> {
+    let (account_id, group_id) = path.into_inner();
+    let account = Account::find_by_id(account_id).await?;
+    account.has_permission_or_error(user.get_id(), Permissions::all()).await?;
+
+    account.revoke_group(group_id).await?;
+
+    Ok(HttpResponse::NoContent())
 }
@@ -0,0 +1,14 @@
+use actix_web::web;
+
+use crate::api::routes::admin::dead_letter::controller::admin_dead_letter_controller;
+use crate::api::routes::admin::invitation::controller::admin_invitation_controller;
+use crate::api::routes::admin::user::controller::admin_user_controller;
+
+pub(crate) fn admin_controller(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/admin")
+            .configure(admin_user_controller)
+            .configure(admin_invitation_controller)
+            .configure(admin_dead_letter_controller),
+    );
+}
@@ -0,0 +1,129 @@
+use actix_web::http::Uri;
+use actix_web::web::Path;
+use actix_web::{delete, get, post, web, HttpResponse, Responder};
+
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::documentation::response::{InternalServerError, ResourceNotFound, Unauthorized, ValidationError};
+use crate::api::error::api::ApiError;
+use crate::api::pagination::{PageSizeParam, Pagination};
+use crate::event::outbox::dead_letter::DeadLetter;
+use crate::wrapper::entity::user::User;
+
+pub(crate) fn admin_dead_letter_controller(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/dead-letter")
+            .service(get_all_dead_letters)
+            .service(get_one_dead_letter)
+            .service(retry_dead_letter)
+            .service(delete_dead_letter),
+    );
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved all events whose listeners kept failing, the latest failure first.", content_type = "application/json", body = PaginatedDeadLetter),
+        (status = 403, description = "Only admins can manage dead letters."),
+        ValidationError,
+        Unauthorized,
+        InternalServerError,
+    ),
+    params(PageSizeParam),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/admin/dead-letter",
+    tag = "Admin"
+)]
+#[get("")]
+pub(crate) async fn get_all_dead_letters(
+    admin: User,
+    page_size: PageSizeParam,
+    uri: Uri,
+) -> Result<impl Responder, ApiError> {
+    admin.require_admin()?;
+    let total = DeadLetter::count_all().await?;
+    let dead_letters = DeadLetter::find_all_paginated(&page_size).await?;
+
+    Ok(HttpResponse::Ok().json(Pagination::new(dead_letters, &page_size, total, uri)))
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved the dead letter.", content_type = "application/json", body = DeadLetter),
+        (status = 403, description = "Only admins can manage dead letters."),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    params(("dead_letter_id" = Snowflake,)),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/admin/dead-letter/{dead_letter_id}",
+    tag = "Admin"
+)]
+#[get("/{dead_letter_id}")]
+pub(crate) async fn get_one_dead_letter(
+    admin: User,
+    dead_letter_id: Path<Snowflake>,
+) -> Result<impl Responder, ApiError> {
+    admin.require_admin()?;
+    let dead_letter = DeadLetter::find_by_id(dead_letter_id.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(dead_letter))
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 204, description = "The event is handed to its listener again, with all attempts available."),
+        (status = 403, description = "Only admins can manage dead letters."),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    params(("dead_letter_id" = Snowflake,)),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/admin/dead-letter/{dead_letter_id}/retry",
+    tag = "Admin"
+)]
+#[post("/{dead_letter_id}/retry")]
+pub(crate) async fn retry_dead_letter(
+    admin: User,
+    dead_letter_id: Path<Snowflake>,
+) -> Result<impl Responder, ApiError> {
+    admin.require_admin()?;
+    let dead_letter = DeadLetter::find_by_id(dead_letter_id.into_inner()).await?;
+    dead_letter.retry().await?;
+
+    Ok(HttpResponse::NoContent())
+}
+
+#[utoipa::path(delete,
+    responses(
+        (status = 204, description = "Successfully discarded the dead letter, the listener never handles the event."),
+        (status = 403, description = "Only admins can manage dead letters."),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    params(("dead_letter_id" = Snowflake,)),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/admin/dead-letter/{dead_letter_id}",
+    tag = "Admin"
+)]
+#[delete("/{dead_letter_id}")]
+pub(crate) async fn delete_dead_letter(
+    admin: User,
+    dead_letter_id: Path<Snowflake>,
+) -> Result<impl Responder, ApiError> {
+    admin.require_admin()?;
+    let dead_letter = DeadLetter::find_by_id(dead_letter_id.into_inner()).await?;
+    dead_letter.delete().await?;
+
+    Ok(HttpResponse::NoContent())
+}
@@ -0,0 +1,98 @@
+use actix_web::http::Uri;
+use actix_web::web::{Json, Path};
+use actix_web::{delete, get, post, web, HttpResponse, Responder};
+use actix_web_validation::Validated;
+
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::documentation::response::{InternalServerError, ResourceNotFound, Unauthorized, ValidationError};
+use crate::api::error::api::ApiError;
+use crate::api::pagination::{PageSizeParam, Pagination};
+use crate::wrapper::entity::invitation::dto::InvitationDTO;
+use crate::wrapper::entity::invitation::Invitation;
+use crate::wrapper::entity::user::User;
+use crate::wrapper::types::phantom::Identifiable;
+
+pub(crate) fn admin_invitation_controller(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/invitation").service(get_all_invitations).service(create_invitation).service(delete_invitation),
+    );
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved all Invitations.", content_type = "application/json", body = PaginatedInvitation),
+        (status = 403, description = "Only admins can manage invitations."),
+        ValidationError,
+        Unauthorized,
+        InternalServerError,
+    ),
+    params(PageSizeParam),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/admin/invitation",
+    tag = "Admin"
+)]
+#[get("")]
+pub(crate) async fn get_all_invitations(
+    admin: User,
+    page_size: PageSizeParam,
+    uri: Uri,
+) -> Result<impl Responder, ApiError> {
+    admin.require_admin()?;
+    let total = Invitation::count_all().await?;
+    let invitations = Invitation::find_all_paginated(&page_size).await?;
+
+    Ok(HttpResponse::Ok().json(Pagination::new(invitations, &page_size, total, uri)))
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 201, description = "Successfully created the Invitation. The code is only returned this once.", content_type = "application/json", body = CreatedInvitation),
+        (status = 403, description = "Only admins can manage invitations."),
+        ValidationError,
+        Unauthorized,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/admin/invitation",
+    request_body = InvitationDTO,
+    tag = "Admin"
+)]
+#[post("")]
+pub(crate) async fn create_invitation(
+    admin: User,
+    invitation: Validated<Json<InvitationDTO>>,
+) -> Result<impl Responder, ApiError> {
+    admin.require_admin()?;
+    let created = Invitation::create(admin.snowflake, invitation.into_inner().into_inner()).await?;
+
+    Ok(HttpResponse::Created().json(created))
+}
+
+#[utoipa::path(delete,
+    responses(
+        (status = 204, description = "Successfully deleted the Invitation, its code can no longer be used."),
+        (status = 403, description = "Only admins can manage invitations."),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    params(("invitation_id" = Snowflake,)),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/admin/invitation/{invitation_id}",
+    tag = "Admin"
+)]
+#[delete("/{invitation_id}")]
+pub(crate) async fn delete_invitation(admin: User, invitation_id: Path<Snowflake>) -> Result<impl Responder, ApiError> {
+    admin.require_admin()?;
+    let invitation = Invitation::find_by_id(invitation_id.into_inner()).await?;
+    invitation.delete().await?;
+
+    Ok(HttpResponse::NoContent())
+}
@@ -0,0 +1,4 @@
+pub(crate) mod controller;
+pub(crate) mod dead_letter;
+pub(crate) mod invitation;
+pub(crate) mod user;
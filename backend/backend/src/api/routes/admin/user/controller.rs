@@ -0,0 +1,340 @@
+use actix_web::http::Uri;
+use actix_web::web::{Json, Path, Query};
+use actix_web::{delete, get, post, web, HttpResponse, Responder};
+use actix_web_validation::Validated;
+
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::documentation::response::{InternalServerError, ResourceNotFound, Unauthorized, ValidationError};
+use crate::api::error::api::ApiError;
+use crate::api::pagination::{PageSizeParam, Pagination};
+use crate::database::transaction::in_transaction;
+use crate::wrapper::entity::audit::{AuditAction, AuditLog};
+use crate::wrapper::entity::login_attempt::lockout::LoginLockout;
+use crate::wrapper::entity::session::dto::PublicSession;
+use crate::wrapper::entity::session::Session;
+use crate::wrapper::entity::user::dto::{AdminPasswordReset, UserCreation, UserFilter};
+use crate::wrapper::entity::user::User;
+use crate::wrapper::types::phantom::Identifiable;
+
+pub(crate) fn admin_user_controller(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/user")
+            .service(get_all_users)
+            .service(create_user)
+            .service(get_one_user)
+            .service(delete_user)
+            .service(disable_user)
+            .service(enable_user)
+            .service(reset_user_password)
+            .service(grant_admin)
+            .service(revoke_admin)
+            .service(get_user_sessions)
+            .service(unlock_user),
+    );
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved all Users.", content_type = "application/json", body = PaginatedUser),
+        (status = 403, description = "Only admins can manage users."),
+        ValidationError,
+        Unauthorized,
+        InternalServerError,
+    ),
+    params(PageSizeParam, UserFilter),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/admin/user",
+    tag = "Admin"
+)]
+#[get("")]
+pub(crate) async fn get_all_users(
+    admin: User,
+    filter: Query<UserFilter>,
+    page_size: PageSizeParam,
+    uri: Uri,
+) -> Result<impl Responder, ApiError> {
+    admin.require_admin()?;
+    let filter = filter.into_inner();
+    let total = User::count_all(&filter).await?;
+    let users = User::find_all_paginated(&filter, &page_size).await?;
+
+    Ok(HttpResponse::Ok().json(Pagination::new(users, &page_size, total, uri)))
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 201, description = "Successfully created the User.", content_type = "application/json", body = User),
+        (status = 403, description = "Only admins can manage users."),
+        ValidationError,
+        Unauthorized,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/admin/user",
+    request_body = UserCreation,
+    tag = "Admin"
+)]
+#[post("")]
+pub(crate) async fn create_user(
+    admin: User,
+    creation: Validated<Json<UserCreation>>,
+) -> Result<impl Responder, ApiError> {
+    admin.require_admin()?;
+    let user = User::create(creation.into_inner().into_inner()).await?;
+
+    Ok(HttpResponse::Created().json(user))
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved the User.", content_type = "application/json", body = User),
+        (status = 403, description = "Only admins can manage users."),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    params(("user_id" = Snowflake,)),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/admin/user/{user_id}",
+    tag = "Admin"
+)]
+#[get("/{user_id}")]
+pub(crate) async fn get_one_user(admin: User, user_id: Path<Snowflake>) -> Result<impl Responder, ApiError> {
+    admin.require_admin()?;
+    let user = User::find_by_id(user_id.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(user))
+}
+
+#[utoipa::path(delete,
+    responses(
+        (status = 204, description = "Successfully deleted the User and all of its Sessions."),
+        (status = 403, description = "Only admins can manage users."),
+        (status = 409, description = "Admins can not delete themselves."),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    params(("user_id" = Snowflake,)),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/admin/user/{user_id}",
+    tag = "Admin"
+)]
+#[delete("/{user_id}")]
+pub(crate) async fn delete_user(admin: User, user_id: Path<Snowflake>) -> Result<impl Responder, ApiError> {
+    let user = find_other_user(&admin, user_id.into_inner()).await?;
+    user.delete().await?;
+
+    Ok(HttpResponse::NoContent())
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 200, description = "Successfully disabled the User. All of its Sessions were signed out.", content_type = "application/json", body = User),
+        (status = 403, description = "Only admins can manage users."),
+        (status = 409, description = "Admins can not disable themselves."),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    params(("user_id" = Snowflake,)),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/admin/user/{user_id}/disable",
+    tag = "Admin"
+)]
+#[post("/{user_id}/disable")]
+pub(crate) async fn disable_user(admin: User, user_id: Path<Snowflake>) -> Result<impl Responder, ApiError> {
+    let user = find_other_user(&admin, user_id.into_inner()).await?;
+    let disabled = user.set_disabled(true).await?;
+
+    Ok(HttpResponse::Ok().json(disabled))
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 200, description = "Successfully enabled the User.", content_type = "application/json", body = User),
+        (status = 403, description = "Only admins can manage users."),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    params(("user_id" = Snowflake,)),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/admin/user/{user_id}/enable",
+    tag = "Admin"
+)]
+#[post("/{user_id}/enable")]
+pub(crate) async fn enable_user(admin: User, user_id: Path<Snowflake>) -> Result<impl Responder, ApiError> {
+    admin.require_admin()?;
+    let user = User::find_by_id(user_id.into_inner()).await?;
+    let enabled = user.set_disabled(false).await?;
+
+    Ok(HttpResponse::Ok().json(enabled))
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 204, description = "Successfully reset the password of the User. All of its Sessions were signed out."),
+        (status = 403, description = "Only admins can manage users."),
+        ValidationError,
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    params(("user_id" = Snowflake,)),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/admin/user/{user_id}/password",
+    request_body = AdminPasswordReset,
+    tag = "Admin"
+)]
+#[post("/{user_id}/password")]
+pub(crate) async fn reset_user_password(
+    admin: User,
+    user_id: Path<Snowflake>,
+    password_reset: Validated<Json<AdminPasswordReset>>,
+) -> Result<impl Responder, ApiError> {
+    admin.require_admin()?;
+    let user = User::find_by_id(user_id.into_inner()).await?;
+    in_transaction(async {
+        user.change_password(&password_reset.new_password).await?;
+        AuditLog::record_action(&user, AuditAction::ResetPassword).await?;
+        Session::delete_all_with_user(user.snowflake, None).await
+    })
+    .await?;
+
+    Ok(HttpResponse::NoContent())
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 200, description = "Successfully made the User an admin.", content_type = "application/json", body = User),
+        (status = 403, description = "Only admins can manage users."),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    params(("user_id" = Snowflake,)),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/admin/user/{user_id}/admin",
+    tag = "Admin"
+)]
+#[post("/{user_id}/admin")]
+pub(crate) async fn grant_admin(admin: User, user_id: Path<Snowflake>) -> Result<impl Responder, ApiError> {
+    admin.require_admin()?;
+    let user = User::find_by_id(user_id.into_inner()).await?;
+    let promoted = user.set_admin(true).await?;
+
+    Ok(HttpResponse::Ok().json(promoted))
+}
+
+#[utoipa::path(delete,
+    responses(
+        (status = 200, description = "Successfully revoked the admin rights of the User.", content_type = "application/json", body = User),
+        (status = 403, description = "Only admins can manage users."),
+        (status = 409, description = "Admins can not demote themselves."),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    params(("user_id" = Snowflake,)),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/admin/user/{user_id}/admin",
+    tag = "Admin"
+)]
+#[delete("/{user_id}/admin")]
+pub(crate) async fn revoke_admin(admin: User, user_id: Path<Snowflake>) -> Result<impl Responder, ApiError> {
+    let user = find_other_user(&admin, user_id.into_inner()).await?;
+    let demoted = user.set_admin(false).await?;
+
+    Ok(HttpResponse::Ok().json(demoted))
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved the Sessions of the User.", content_type = "application/json", body = PaginatedSession),
+        (status = 403, description = "Only admins can manage users."),
+        ValidationError,
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    params(("user_id" = Snowflake,), PageSizeParam),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/admin/user/{user_id}/session",
+    tag = "Admin"
+)]
+#[get("/{user_id}/session")]
+pub(crate) async fn get_user_sessions(
+    admin: User,
+    user_id: Path<Snowflake>,
+    page_size: PageSizeParam,
+    uri: Uri,
+) -> Result<impl Responder, ApiError> {
+    admin.require_admin()?;
+    let user = User::find_by_id(user_id.into_inner()).await?;
+    let sessions = Session::find_all_by_user_paginated(user.snowflake, &page_size).await?;
+    let public_sessions: Vec<PublicSession> = sessions.into_iter().map(PublicSession::from).collect();
+    let total = Session::count_all_by_user(user.snowflake).await?;
+
+    Ok(HttpResponse::Ok().json(Pagination::new(public_sessions, &page_size, total, uri)))
+}
+
+#[utoipa::path(delete,
+    responses(
+        (status = 204, description = "Successfully lifted the lockout and cleared the failed logins of the User."),
+        (status = 403, description = "Only admins can manage users."),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    params(("user_id" = Snowflake,)),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/admin/user/{user_id}/lockout",
+    tag = "Admin"
+)]
+#[delete("/{user_id}/lockout")]
+pub(crate) async fn unlock_user(admin: User, user_id: Path<Snowflake>) -> Result<impl Responder, ApiError> {
+    admin.require_admin()?;
+    let user = User::find_by_id(user_id.into_inner()).await?;
+    // the lockout lives in redis and can not be rolled back, so it is lifted after the audit entry is written
+    in_transaction(async {
+        AuditLog::record_action(&user, AuditAction::Unlock).await?;
+        LoginLockout::unlock(&user.username).await
+    })
+    .await?;
+
+    Ok(HttpResponse::NoContent())
+}
+
+// admins must not lock themselves out by disabling, deleting or demoting their own user
+async fn find_other_user(admin: &User, user_id: Snowflake) -> Result<User, ApiError> {
+    admin.require_admin()?;
+    if admin.snowflake == user_id {
+        return Err(ApiError::AdminSelfModification());
+    }
+
+    User::find_by_id(user_id).await
+}
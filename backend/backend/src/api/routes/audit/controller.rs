@@ -0,0 +1,48 @@
+use actix_web::http::Uri;
+use actix_web::web::Query;
+use actix_web::{get, web, HttpResponse, Responder};
+
+use crate::api::documentation::response::{InternalServerError, Unauthorized, ValidationError};
+use crate::api::error::api::ApiError;
+use crate::api::pagination::{PageSizeParam, Pagination};
+use crate::wrapper::entity::audit::dto::AuditLogFilter;
+use crate::wrapper::entity::audit::AuditLog;
+use crate::wrapper::entity::user::User;
+
+pub(crate) fn audit_controller(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::scope("/audit").service(get_audit_log));
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved the audit log. Admins see every entry, everyone else only the entries they caused or that belong to entities they can access.", content_type = "application/json", body = PaginatedAuditLog),
+        ValidationError,
+        Unauthorized,
+        InternalServerError,
+    ),
+    params(PageSizeParam, AuditLogFilter),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/audit",
+    tag = "Audit"
+)]
+#[get("")]
+pub(crate) async fn get_audit_log(
+    user: User,
+    filter: Query<AuditLogFilter>,
+    page_size: PageSizeParam,
+    uri: Uri,
+) -> Result<impl Responder, ApiError> {
+    let filter = filter.into_inner();
+    let (total, entries) = if user.is_admin {
+        (AuditLog::count_all(&filter).await?, AuditLog::find_all_paginated(&filter, &page_size).await?)
+    } else {
+        (
+            AuditLog::count_all_by_user(user.snowflake, &filter).await?,
+            AuditLog::find_all_by_user_paginated(user.snowflake, &filter, &page_size).await?,
+        )
+    };
+
+    Ok(HttpResponse::Ok().json(Pagination::new(entries, &page_size, total, uri)))
+}
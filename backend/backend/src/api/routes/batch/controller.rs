@@ -0,0 +1,37 @@
+use actix_web::web::Json;
+use actix_web::{post, web, HttpResponse, Responder};
+use actix_web_validation::Validated;
+
+use crate::api::documentation::response::{InternalServerError, Unauthorized, ValidationError};
+use crate::api::error::api::ApiError;
+use crate::wrapper::batch::dto::BatchDTO;
+use crate::wrapper::batch::execute;
+use crate::wrapper::entity::user::User;
+use crate::wrapper::types::phantom::Phantom;
+
+pub(crate) fn batch_controller(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::scope("/batch").service(run_batch));
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 200, description = "Ran the operations, the result of each one has the status and body its single-item route would have responded with.", content_type = "application/json", body = BatchResponse),
+        ValidationError,
+        Unauthorized,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/batch",
+    request_body = BatchDTO,
+    tag = "Batch")]
+#[post("")]
+pub(crate) async fn run_batch(
+    user: Phantom<User>,
+    batch: Validated<Json<BatchDTO>>,
+) -> Result<impl Responder, ApiError> {
+    let response = execute(user.get_id(), batch.into_inner().into_inner()).await;
+
+    Ok(HttpResponse::Ok().json(response))
+}
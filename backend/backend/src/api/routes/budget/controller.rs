@@ -1,16 +1,23 @@
 use actix_web::http::Uri;
-use actix_web::web::{Json, Path};
+use actix_web::web::{Json, Path, Query};
 use actix_web::{delete, get, patch, post, web, HttpResponse, Responder};
 use actix_web_validation::Validated;
 
 use utility::snowflake::entity::Snowflake;
 
-use crate::api::documentation::response::{InternalServerError, ResourceNotFound, Unauthorized, ValidationError};
+use crate::api::conditional::{versioned_response, IfMatch, IfNoneMatch};
+use crate::api::documentation::response::{
+    InternalServerError, PreconditionFailed, PreconditionRequired, ResourceNotFound, Unauthorized, ValidationError,
+};
 use crate::api::error::api::ApiError;
+use crate::api::expansion::ResponseShape;
 use crate::api::pagination::{PageSizeParam, Pagination};
 use crate::wrapper::entity::budget::dto::BudgetDTO;
 use crate::wrapper::entity::budget::Budget;
+use crate::wrapper::entity::group::dto::GroupGrantDTO;
 use crate::wrapper::entity::user::User;
+use crate::wrapper::permission::collaborator::dto::CollaboratorDTO;
+use crate::wrapper::permission::collaborator::Shareable;
 use crate::wrapper::permission::{HasPermissionByIdOrError, HasPermissionOrError, Permissions};
 use crate::wrapper::types::phantom::{Identifiable, Phantom};
 
@@ -22,6 +29,13 @@ pub(crate) fn budget_controller(cfg: &mut web::ServiceConfig) {
             .service(create_budget)
             .service(delete_budget)
             .service(update_budget)
+            .service(get_collaborators_of_budget)
+            .service(share_budget)
+            .service(leave_budget)
+            .service(revoke_budget_collaborator)
+            .service(get_groups_of_budget)
+            .service(share_budget_with_group)
+            .service(revoke_budget_group)
             .service(get_one_budget),
     );
 }
@@ -33,7 +47,7 @@ pub(crate) fn budget_controller(cfg: &mut web::ServiceConfig) {
         Unauthorized,
         InternalServerError,
     ),
-    params(PageSizeParam),
+    params(PageSizeParam, ResponseShape),
     security(
         ("bearer_token" = [])
     ),
@@ -44,17 +58,22 @@ pub(crate) fn budget_controller(cfg: &mut web::ServiceConfig) {
 pub(crate) async fn get_all_budgets(
     user: Phantom<User>,
     page_size: PageSizeParam,
+    shape: Query<ResponseShape>,
     uri: Uri,
 ) -> Result<impl Responder, ApiError> {
     let total = Budget::count_all_by_user(user.get_id()).await?;
     let budgets = Budget::find_all_by_user_paginated(user.get_id(), &page_size).await?;
 
-    Ok(HttpResponse::Ok().json(Pagination::new(budgets, &page_size, total, uri)))
+    let page = Pagination::new(budgets, &page_size, total, uri);
+
+    Ok(HttpResponse::Ok().json(shape.apply_page(user.get_id(), page).await?))
 }
 
 #[utoipa::path(get,
     responses(
         (status = 200, description = "Successfully retrieved the Budget.", content_type = "application/json", body = Budget),
+        (status = 304, description = "The Budget did not change since the ETag given in If-None-Match."),
+        ValidationError,
         Unauthorized,
         ResourceNotFound,
         InternalServerError,
@@ -62,7 +81,7 @@ pub(crate) async fn get_all_budgets(
     security(
         ("bearer_token" = [])
     ),
-    params(("budget_id" = Snowflake,)),
+    params(("budget_id" = Snowflake,), ResponseShape, ("If-None-Match" = Option<String>, Header, description = "The ETag of the copy the client already has.")),
     path = "/api/v1/budget/{budget_id}",
     tag = "Budget"
 )]
@@ -70,16 +89,19 @@ pub(crate) async fn get_all_budgets(
 pub(crate) async fn get_one_budget(
     user: Phantom<User>,
     budget_id: Path<Snowflake>,
+    shape: Query<ResponseShape>,
+    if_none_match: IfNoneMatch,
 ) -> Result<impl Responder, ApiError> {
     let budget = Budget::find_by_id(budget_id.into_inner()).await?;
     budget.has_permission_or_error(user.get_id(), Permissions::READ).await?;
 
-    Ok(HttpResponse::Ok().json(budget))
+    shape.respond(user.get_id(), budget, &if_none_match).await
 }
 
 #[utoipa::path(get,
     responses(
         (status = 200, description = "Successfully retrieved the Transactions.", content_type = "application/json", body = PaginatedTransaction),
+        ValidationError,
         Unauthorized,
         ResourceNotFound,
         InternalServerError,
@@ -87,7 +109,7 @@ pub(crate) async fn get_one_budget(
     security(
         ("bearer_token" = [])
     ),
-    params(("budget_id" = Snowflake,), PageSizeParam),
+    params(("budget_id" = Snowflake,), PageSizeParam, ResponseShape),
     path = "/api/v1/budget/{budget_id}/transactions",
     tag = "Budget"
 )]
@@ -96,6 +118,7 @@ pub(crate) async fn get_transactions_from_budget(
     user: Phantom<User>,
     budget_id: Path<Snowflake>,
     page_size: PageSizeParam,
+    shape: Query<ResponseShape>,
     uri: Uri,
 ) -> Result<impl Responder, ApiError> {
     let budget_id = budget_id.into_inner();
@@ -103,8 +126,9 @@ pub(crate) async fn get_transactions_from_budget(
 
     let transactions = Budget::find_related_transactions_paginated(budget_id, &page_size).await?;
     let total = Budget::count_related_transactions(budget_id).await?;
+    let page = Pagination::new(transactions, &page_size, total, uri);
 
-    Ok(HttpResponse::Ok().json(Pagination::new(transactions, &page_size, total, uri)))
+    Ok(HttpResponse::Ok().json(shape.apply_page(user.get_id(), page).await?))
 }
 
 #[utoipa::path(post,
@@ -117,6 +141,7 @@ pub(crate) async fn get_transactions_from_budget(
     security(
         ("bearer_token" = [])
     ),
+    request_body = BudgetDTO,
     path = "/api/v1/budget",
     tag = "Budget"
 )]
@@ -127,7 +152,7 @@ pub(crate) async fn create_budget(
 ) -> Result<impl Responder, ApiError> {
     let budget = Budget::new(user.get_id(), budget.into_inner().into_inner()).await?;
 
-    Ok(HttpResponse::Created().json(budget))
+    Ok(versioned_response(HttpResponse::Created(), &budget))
 }
 
 #[utoipa::path(delete,
@@ -135,19 +160,26 @@ pub(crate) async fn create_budget(
         (status = 204, description = "Successfully deleted the Budget."),
         Unauthorized,
         ResourceNotFound,
+        PreconditionFailed,
+        PreconditionRequired,
         InternalServerError,
     ),
     security(
         ("bearer_token" = [])
     ),
-    params(("budget_id" = Snowflake,)),
+    params(("budget_id" = Snowflake,), ("If-Match" = String, Header, description = "The ETag the client last saw, or `*` to skip the check.")),
     path = "/api/v1/budget/{budget_id}",
     tag = "Budget"
 )]
 #[delete("/{budget_id}")]
-pub(crate) async fn delete_budget(user: Phantom<User>, budget_id: Path<Snowflake>) -> Result<impl Responder, ApiError> {
+pub(crate) async fn delete_budget(
+    user: Phantom<User>,
+    budget_id: Path<Snowflake>,
+    if_match: IfMatch,
+) -> Result<impl Responder, ApiError> {
     let budget = Budget::find_by_id(budget_id.into_inner()).await?;
     budget.has_permission_or_error(user.get_id(), Permissions::READ_DELETE).await?;
+    if_match.check(&budget)?;
 
     budget.delete().await?;
 
@@ -160,12 +192,14 @@ pub(crate) async fn delete_budget(user: Phantom<User>, budget_id: Path<Snowflake
         ValidationError,
         Unauthorized,
         ResourceNotFound,
+        PreconditionFailed,
+        PreconditionRequired,
         InternalServerError,
     ),
     security(
         ("bearer_token" = [])
     ),
-    params(("budget_id" = Snowflake,)),
+    params(("budget_id" = Snowflake,), ("If-Match" = String, Header, description = "The ETag the client last saw, or `*` to skip the check.")),
     path = "/api/v1/budget/{budget_id}",
     tag = "Budget"
 )]
@@ -174,11 +208,197 @@ pub(crate) async fn update_budget(
     user: Phantom<User>,
     budget_id: Path<Snowflake>,
     budget_dto: Json<BudgetDTO>,
+    if_match: IfMatch,
 ) -> Result<impl Responder, ApiError> {
     let budget = Budget::find_by_id(budget_id.into_inner()).await?;
     budget.has_permission_or_error(user.get_id(), Permissions::READ_WRITE).await?;
+    if_match.check(&budget)?;
 
     let budget = budget.update(budget_dto.into_inner()).await?;
 
-    Ok(HttpResponse::Ok().json(budget))
+    Ok(versioned_response(HttpResponse::Ok(), &budget))
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved all Collaborators of the Budget.", content_type = "application/json", body = [Collaborator]),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("budget_id" = Snowflake,)),
+    path = "/api/v1/budget/{budget_id}/collaborators",
+    tag = "Budget")]
+#[get("/{budget_id}/collaborators")]
+pub(crate) async fn get_collaborators_of_budget(
+    user: Phantom<User>,
+    budget_id: Path<Snowflake>,
+) -> Result<impl Responder, ApiError> {
+    let budget = Budget::find_by_id(budget_id.into_inner()).await?;
+    budget.has_permission_or_error(user.get_id(), Permissions::READ).await?;
+
+    Ok(HttpResponse::Ok().json(budget.find_collaborators().await?))
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 200, description = "Successfully shared the Budget.", content_type = "application/json", body = Collaborator),
+        (status = 409, description = "The last user with full permissions can not be downgraded.", content_type = "application/json", body = ApiError),
+        Unauthorized,
+        ValidationError,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("budget_id" = Snowflake,)),
+    path = "/api/v1/budget/{budget_id}/collaborators",
+    request_body = CollaboratorDTO,
+    tag = "Budget")]
+#[post("/{budget_id}/collaborators")]
+pub(crate) async fn share_budget(
+    user: Phantom<User>,
+    budget_id: Path<Snowflake>,
+    collaborator: Validated<Json<CollaboratorDTO>>,
+) -> Result<impl Responder, ApiError> {
+    let budget = Budget::find_by_id(budget_id.into_inner()).await?;
+    budget.has_permission_or_error(user.get_id(), Permissions::all()).await?;
+
+    let collaborator = budget.share(collaborator.into_inner().into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(collaborator))
+}
+
+#[utoipa::path(delete,
+    responses(
+        (status = 204, description = "Successfully left the Budget."),
+        (status = 409, description = "The last user with full permissions can not leave.", content_type = "application/json", body = ApiError),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("budget_id" = Snowflake,)),
+    path = "/api/v1/budget/{budget_id}/collaborators/@me",
+    tag = "Budget")]
+#[delete("/{budget_id}/collaborators/@me")]
+pub(crate) async fn leave_budget(user: Phantom<User>, budget_id: Path<Snowflake>) -> Result<impl Responder, ApiError> {
+    let budget = Budget::find_by_id(budget_id.into_inner()).await?;
+    budget.revoke(user.get_id()).await?;
+
+    Ok(HttpResponse::NoContent())
+}
+
+#[utoipa::path(delete,
+    responses(
+        (status = 204, description = "Successfully revoked the access of a Collaborator."),
+        (status = 409, description = "The last user with full permissions can not be removed.", content_type = "application/json", body = ApiError),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("budget_id" = Snowflake,), ("user_id" = Snowflake,)),
+    path = "/api/v1/budget/{budget_id}/collaborators/{user_id}",
+    tag = "Budget")]
+#[delete("/{budget_id}/collaborators/{user_id}")]
+pub(crate) async fn revoke_budget_collaborator(
+    user: Phantom<User>,
+    path: Path<(Snowflake, Snowflake)>,
+) -> Result<impl Responder, ApiError> {
+    let (budget_id, user_id) = path.into_inner();
+    let budget = Budget::find_by_id(budget_id).await?;
+    budget.has_permission_or_error(user.get_id(), Permissions::all()).await?;
+
+    budget.revoke(user_id).await?;
+
+    Ok(HttpResponse::NoContent())
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved all Groups the Budget is shared with.", content_type = "application/json", body = [GroupGrant]),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("budget_id" = Snowflake,)),
+    path = "/api/v1/budget/{budget_id}/groups",
+    tag = "Budget")]
+#[get("/{budget_id}/groups")]
+pub(crate) async fn get_groups_of_budget(
+    user: Phantom<User>,
+    budget_id: Path<Snowflake>,
+) -> Result<impl Responder, ApiError> {
+    let budget = Budget::find_by_id(budget_id.into_inner()).await?;
+    budget.has_permission_or_error(user.get_id(), Permissions::READ).await?;
+
+    Ok(HttpResponse::Ok().json(budget.find_group_grants().await?))
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 200, description = "Successfully shared the Budget with a Group.", content_type = "application/json", body = GroupGrant),
+        Unauthorized,
+        ValidationError,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("budget_id" = Snowflake,)),
+    path = "/api/v1/budget/{budget_id}/groups",
+    request_body = GroupGrantDTO,
+    tag = "Budget")]
+#[post("/{budget_id}/groups")]
+pub(crate) async fn share_budget_with_group(
+    user: Phantom<User>,
+    budget_id: Path<Snowflake>,
+    grant: Validated<Json<GroupGrantDTO>>,
+) -> Result<impl Responder, ApiError> {
+    let budget = Budget::find_by_id(budget_id.into_inner()).await?;
+    budget.has_permission_or_error(user.get_id(), Permissions::all()).await?;
+
+    let grant = budget.share_with_group(user.get_id(), grant.into_inner().into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(grant))
+}
+
+#[utoipa::path(delete,
+    responses(
+        (status = 204, description = "Successfully revoked the access of a Group."),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("budget_id" = Snowflake,), ("group_id" = Snowflake,)),
+    path = "/api/v1/budget/{budget_id}/groups/{group_id}",
+    tag = "Budget")]
+#[delete("/{budget_id}/groups/{group_id}")]
+pub(crate) async fn revoke_budget_group(
+    user: Phantom<User>,
+    path: Path<(Snowflake, Snowflake)>,
+) -> Result<impl Responder, ApiError> {
+    let (budget_id, group_id) = path.into_inner();
+    let budget = Budget::find_by_id(budget_id).await?;
+    budget.has_permission_or_error(user.get_id(), Permissions::all()).await?;
+
+    budget.revoke_group(group_id).await?;
+
+    Ok(HttpResponse::NoContent())
 }
@@ -0,0 +1,132 @@
+use actix_web::web::{Json, Path};
+use actix_web::{delete, get, post, web, HttpResponse, Responder};
+use actix_web_validation::Validated;
+
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::documentation::response::{InternalServerError, ResourceNotFound, Unauthorized, ValidationError};
+use crate::api::error::api::ApiError;
+use crate::wrapper::entity::expense::dto::ExpenseDTO;
+use crate::wrapper::entity::expense::ledger::Ledger;
+use crate::wrapper::entity::expense::Expense;
+use crate::wrapper::entity::group::member::GroupRole;
+use crate::wrapper::entity::user::User;
+use crate::wrapper::permission::{HasPermissionOrError, Permissions};
+use crate::wrapper::types::phantom::{Identifiable, Phantom};
+
+pub(crate) fn expense_controller(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/expense")
+            .service(get_debts)
+            .service(create_expense)
+            .service(delete_expense)
+            .service(get_one_expense),
+    );
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved the outstanding debts per counterparty.", content_type = "application/json", body = [CounterpartyDebt]),
+        Unauthorized,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/expense/debts",
+    tag = "Expense"
+)]
+#[get("/debts")]
+pub(crate) async fn get_debts(user: Phantom<User>) -> Result<impl Responder, ApiError> {
+    Ok(HttpResponse::Ok().json(Ledger::find_counterparty_debts_of_user(user.get_id()).await?))
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved the Expense.", content_type = "application/json", body = Expense),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("expense_id" = Snowflake,)),
+    path = "/api/v1/expense/{expense_id}",
+    tag = "Expense"
+)]
+#[get("/{expense_id}")]
+pub(crate) async fn get_one_expense(
+    user: Phantom<User>,
+    expense_id: Path<Snowflake>,
+) -> Result<impl Responder, ApiError> {
+    let expense = Expense::find_by_id(expense_id.into_inner()).await?;
+    expense.group_id.fetch_inner().await?.membership_or_error(user.get_id()).await?;
+
+    Ok(HttpResponse::Ok().json(expense))
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 201, description = "Successfully split the Transaction.", content_type = "application/json", body = Expense),
+        (status = 400, description = "The split does not add up or involves users outside of the group.", content_type = "application/json", body = ApiError),
+        ValidationError,
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/expense",
+    request_body = ExpenseDTO,
+    tag = "Expense"
+)]
+#[post("")]
+pub(crate) async fn create_expense(
+    user: Phantom<User>,
+    expense: Validated<Json<ExpenseDTO>>,
+) -> Result<impl Responder, ApiError> {
+    let expense = expense.into_inner().into_inner();
+    let group = expense.group_id.fetch_inner().await?;
+    group.has_role_or_error(user.get_id(), &[GroupRole::Owner, GroupRole::Member]).await?;
+    // splitting records the caller as payer, so reading the transaction is not enough
+    expense.transaction_id.fetch_inner().await?.has_permission_or_error(user.get_id(), Permissions::READ_WRITE).await?;
+
+    let expense = Expense::new(user.get_id(), expense).await?;
+
+    Ok(HttpResponse::Created().json(expense))
+}
+
+#[utoipa::path(delete,
+    responses(
+        (status = 204, description = "Successfully deleted the Expense."),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("expense_id" = Snowflake,)),
+    path = "/api/v1/expense/{expense_id}",
+    tag = "Expense"
+)]
+#[delete("/{expense_id}")]
+pub(crate) async fn delete_expense(
+    user: Phantom<User>,
+    expense_id: Path<Snowflake>,
+) -> Result<impl Responder, ApiError> {
+    let expense = Expense::find_by_id(expense_id.into_inner()).await?;
+    let group = expense.group_id.fetch_inner().await?;
+    // the payer and the owners of the group are allowed to remove an expense
+    if expense.payer_id.get_id() != user.get_id() {
+        group.has_role_or_error(user.get_id(), &[GroupRole::Owner]).await?;
+    } else {
+        group.membership_or_error(user.get_id()).await?;
+    }
+
+    expense.delete().await?;
+
+    Ok(HttpResponse::NoContent())
+}
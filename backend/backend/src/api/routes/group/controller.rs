@@ -0,0 +1,360 @@
+use actix_web::http::Uri;
+use actix_web::web::{Json, Path};
+use actix_web::{delete, get, patch, post, web, HttpResponse, Responder};
+use actix_web_validation::Validated;
+
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::documentation::response::{InternalServerError, ResourceNotFound, Unauthorized, ValidationError};
+use crate::api::error::api::ApiError;
+use crate::api::pagination::{PageSizeParam, Pagination};
+use crate::wrapper::entity::expense::dto::SettlementDTO;
+use crate::wrapper::entity::expense::ledger::Ledger;
+use crate::wrapper::entity::expense::settlement::Settlement;
+use crate::wrapper::entity::expense::Expense;
+use crate::wrapper::entity::group::dto::{GroupDTO, GroupMemberDTO};
+use crate::wrapper::entity::group::member::GroupRole;
+use crate::wrapper::entity::group::Group;
+use crate::wrapper::entity::user::User;
+use crate::wrapper::types::phantom::{Identifiable, Phantom};
+
+pub(crate) fn group_controller(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/group")
+            .service(get_all_groups)
+            .service(create_group)
+            .service(get_members_of_group)
+            .service(set_group_member)
+            .service(leave_group)
+            .service(remove_group_member)
+            .service(get_expenses_of_group)
+            .service(get_balances_of_group)
+            .service(settle_up)
+            .service(delete_group)
+            .service(update_group)
+            .service(get_one_group),
+    );
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved the Groups.", content_type = "application/json", body = PaginatedGroup),
+        ValidationError,
+        Unauthorized,
+        InternalServerError,
+    ),
+    params(PageSizeParam),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/group",
+    tag = "Group"
+)]
+#[get("")]
+pub(crate) async fn get_all_groups(
+    user: Phantom<User>,
+    page_size: PageSizeParam,
+    uri: Uri,
+) -> Result<impl Responder, ApiError> {
+    let total = Group::count_all_by_user(user.get_id()).await?;
+    let groups = Group::find_all_by_user_paginated(user.get_id(), &page_size).await?;
+
+    Ok(HttpResponse::Ok().json(Pagination::new(groups, &page_size, total, uri)))
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved the Group.", content_type = "application/json", body = Group),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("group_id" = Snowflake,)),
+    path = "/api/v1/group/{group_id}",
+    tag = "Group"
+)]
+#[get("/{group_id}")]
+pub(crate) async fn get_one_group(user: Phantom<User>, group_id: Path<Snowflake>) -> Result<impl Responder, ApiError> {
+    let group = Group::find_by_id(group_id.into_inner()).await?;
+    group.membership_or_error(user.get_id()).await?;
+
+    Ok(HttpResponse::Ok().json(group))
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 201, description = "Successfully created the Group.", content_type = "application/json", body = Group),
+        ValidationError,
+        Unauthorized,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/group",
+    request_body = GroupDTO,
+    tag = "Group"
+)]
+#[post("")]
+pub(crate) async fn create_group(
+    user: Phantom<User>,
+    group: Validated<Json<GroupDTO>>,
+) -> Result<impl Responder, ApiError> {
+    let group = Group::new(user.get_id(), group.into_inner().into_inner()).await?;
+
+    Ok(HttpResponse::Created().json(group))
+}
+
+#[utoipa::path(patch,
+    responses(
+        (status = 200, description = "Successfully updated the Group.", content_type = "application/json", body = Group),
+        ValidationError,
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("group_id" = Snowflake,)),
+    path = "/api/v1/group/{group_id}",
+    request_body = GroupDTO,
+    tag = "Group"
+)]
+#[patch("/{group_id}")]
+pub(crate) async fn update_group(
+    user: Phantom<User>,
+    group_id: Path<Snowflake>,
+    group_dto: Validated<Json<GroupDTO>>,
+) -> Result<impl Responder, ApiError> {
+    let group = Group::find_by_id(group_id.into_inner()).await?;
+    group.has_role_or_error(user.get_id(), &[GroupRole::Owner]).await?;
+
+    let group = group.update(group_dto.into_inner().into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(group))
+}
+
+#[utoipa::path(delete,
+    responses(
+        (status = 204, description = "Successfully deleted the Group."),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("group_id" = Snowflake,)),
+    path = "/api/v1/group/{group_id}",
+    tag = "Group"
+)]
+#[delete("/{group_id}")]
+pub(crate) async fn delete_group(user: Phantom<User>, group_id: Path<Snowflake>) -> Result<impl Responder, ApiError> {
+    let group = Group::find_by_id(group_id.into_inner()).await?;
+    group.has_role_or_error(user.get_id(), &[GroupRole::Owner]).await?;
+
+    group.delete().await?;
+
+    Ok(HttpResponse::NoContent())
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved the members of the Group.", content_type = "application/json", body = [GroupMember]),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("group_id" = Snowflake,)),
+    path = "/api/v1/group/{group_id}/members",
+    tag = "Group"
+)]
+#[get("/{group_id}/members")]
+pub(crate) async fn get_members_of_group(
+    user: Phantom<User>,
+    group_id: Path<Snowflake>,
+) -> Result<impl Responder, ApiError> {
+    let group = Group::find_by_id(group_id.into_inner()).await?;
+    group.membership_or_error(user.get_id()).await?;
+
+    Ok(HttpResponse::Ok().json(group.find_members().await?))
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 200, description = "Successfully added or updated a member of the Group.", content_type = "application/json", body = GroupMember),
+        (status = 409, description = "The last owner of the Group can not be demoted.", content_type = "application/json", body = ApiError),
+        ValidationError,
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("group_id" = Snowflake,)),
+    path = "/api/v1/group/{group_id}/members",
+    request_body = GroupMemberDTO,
+    tag = "Group"
+)]
+#[post("/{group_id}/members")]
+pub(crate) async fn set_group_member(
+    user: Phantom<User>,
+    group_id: Path<Snowflake>,
+    member: Validated<Json<GroupMemberDTO>>,
+) -> Result<impl Responder, ApiError> {
+    let group = Group::find_by_id(group_id.into_inner()).await?;
+    group.has_role_or_error(user.get_id(), &[GroupRole::Owner]).await?;
+
+    let member = group.set_member(member.into_inner().into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(member))
+}
+
+#[utoipa::path(delete,
+    responses(
+        (status = 204, description = "Successfully left the Group."),
+        (status = 409, description = "The last owner of the Group can not leave.", content_type = "application/json", body = ApiError),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("group_id" = Snowflake,)),
+    path = "/api/v1/group/{group_id}/members/@me",
+    tag = "Group"
+)]
+#[delete("/{group_id}/members/@me")]
+pub(crate) async fn leave_group(user: Phantom<User>, group_id: Path<Snowflake>) -> Result<impl Responder, ApiError> {
+    let group = Group::find_by_id(group_id.into_inner()).await?;
+    group.remove_member(user.get_id()).await?;
+
+    Ok(HttpResponse::NoContent())
+}
+
+#[utoipa::path(delete,
+    responses(
+        (status = 204, description = "Successfully removed a member from the Group."),
+        (status = 409, description = "The last owner of the Group can not be removed.", content_type = "application/json", body = ApiError),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("group_id" = Snowflake,), ("user_id" = Snowflake,)),
+    path = "/api/v1/group/{group_id}/members/{user_id}",
+    tag = "Group"
+)]
+#[delete("/{group_id}/members/{user_id}")]
+pub(crate) async fn remove_group_member(
+    user: Phantom<User>,
+    path: Path<(Snowflake, Snowflake)>,
+) -> Result<impl Responder, ApiError> {
+    let (group_id, user_id) = path.into_inner();
+    let group = Group::find_by_id(group_id).await?;
+    group.has_role_or_error(user.get_id(), &[GroupRole::Owner]).await?;
+
+    group.remove_member(user_id).await?;
+
+    Ok(HttpResponse::NoContent())
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved the Expenses of the Group.", content_type = "application/json", body = PaginatedExpense),
+        ValidationError,
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("group_id" = Snowflake,), PageSizeParam),
+    path = "/api/v1/group/{group_id}/expenses",
+    tag = "Group"
+)]
+#[get("/{group_id}/expenses")]
+pub(crate) async fn get_expenses_of_group(
+    user: Phantom<User>,
+    group_id: Path<Snowflake>,
+    page_size: PageSizeParam,
+    uri: Uri,
+) -> Result<impl Responder, ApiError> {
+    let group = Group::find_by_id(group_id.into_inner()).await?;
+    group.membership_or_error(user.get_id()).await?;
+
+    let total = Expense::count_all_by_group(group.snowflake).await?;
+    let expenses = Expense::find_all_by_group_paginated(group.snowflake, &page_size).await?;
+
+    Ok(HttpResponse::Ok().json(Pagination::new(expenses, &page_size, total, uri)))
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved the net balances and simplified debts of the Group.", content_type = "application/json", body = GroupBalances),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("group_id" = Snowflake,)),
+    path = "/api/v1/group/{group_id}/balances",
+    tag = "Group"
+)]
+#[get("/{group_id}/balances")]
+pub(crate) async fn get_balances_of_group(
+    user: Phantom<User>,
+    group_id: Path<Snowflake>,
+) -> Result<impl Responder, ApiError> {
+    let group = Group::find_by_id(group_id.into_inner()).await?;
+    group.membership_or_error(user.get_id()).await?;
+
+    let ledger = Ledger::load(group.snowflake).await?;
+
+    Ok(HttpResponse::Ok().json(ledger.group_balances()))
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 201, description = "Successfully recorded the settle-up payment.", content_type = "application/json", body = Settlement),
+        (status = 400, description = "The payment can not be booked between the given accounts.", content_type = "application/json", body = ApiError),
+        ValidationError,
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    params(("group_id" = Snowflake,)),
+    path = "/api/v1/group/{group_id}/settlements",
+    request_body = SettlementDTO,
+    tag = "Group"
+)]
+#[post("/{group_id}/settlements")]
+pub(crate) async fn settle_up(
+    user: Phantom<User>,
+    group_id: Path<Snowflake>,
+    settlement: Validated<Json<SettlementDTO>>,
+) -> Result<impl Responder, ApiError> {
+    let group = Group::find_by_id(group_id.into_inner()).await?;
+    group.has_role_or_error(user.get_id(), &[GroupRole::Owner, GroupRole::Member]).await?;
+
+    let settlement = Settlement::new(&group, user.get_id(), settlement.into_inner().into_inner()).await?;
+
+    Ok(HttpResponse::Created().json(settlement))
+}
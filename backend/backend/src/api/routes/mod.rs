@@ -1,7 +1,17 @@
 pub(crate) mod account;
+pub(crate) mod admin;
+pub(crate) mod audit;
+pub(crate) mod batch;
 pub(crate) mod budget;
 pub(crate) mod currency;
+pub(crate) mod expense;
+pub(crate) mod group;
 pub(crate) mod openapi;
+pub(crate) mod realtime;
 pub(crate) mod session;
+pub(crate) mod sync;
+pub(crate) mod token;
 pub(crate) mod transaction;
+pub(crate) mod trash;
 pub(crate) mod user;
+pub(crate) mod webhook;
@@ -0,0 +1,99 @@
+use std::convert::Infallible;
+use std::time::Duration;
+
+use actix_web::http::header::{CACHE_CONTROL, CONTENT_ENCODING};
+use actix_web::rt::spawn;
+use actix_web::web::{Bytes, Payload, Query};
+use actix_web::{get, web, HttpRequest, HttpResponse};
+use futures_util::stream::{select, StreamExt};
+use tokio::time::interval;
+use tokio_stream::wrappers::IntervalStream;
+use tracing::error;
+
+use crate::api::documentation::response::{InternalServerError, Unauthorized, ValidationError};
+use crate::api::error::api::ApiError;
+use crate::config::Config;
+use crate::wrapper::entity::user::User;
+use crate::wrapper::realtime::dto::RealtimeQuery;
+use crate::wrapper::realtime::socket::run_socket;
+use crate::wrapper::realtime::RealtimeHub;
+use crate::wrapper::types::phantom::Phantom;
+
+const SSE_HEARTBEAT: &[u8] = b": heartbeat\n\n";
+
+pub(crate) fn realtime_controller(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::scope("/realtime").service(realtime_events).service(realtime_socket));
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "A stream of server-sent events. Every `data` field holds a RealtimeEvent, a `resync` event tells the client to reload its data because events were missed.", content_type = "text/event-stream", body = RealtimeEvent),
+        ValidationError,
+        Unauthorized,
+        InternalServerError,
+    ),
+    params(RealtimeQuery),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/realtime/events",
+    tag = "Realtime"
+)]
+#[get("/events")]
+pub(crate) async fn realtime_events(
+    user: Phantom<User>,
+    request: HttpRequest,
+    query: Query<RealtimeQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let last_event_id = query.last_event_id(request.headers());
+    let heartbeat = Duration::from_secs(Config::get_config().realtime.heartbeat_seconds);
+
+    let messages = RealtimeHub::subscribe(user.get_id(), last_event_id).filter_map(|message| async move {
+        match message.to_sse() {
+            Ok(text) => Some(Bytes::from(text)),
+            Err(e) => {
+                error!("Could not serialize realtime message: {}", e);
+                None
+            }
+        }
+    });
+    let heartbeats = IntervalStream::new(interval(heartbeat)).map(|_| Bytes::from_static(SSE_HEARTBEAT));
+    let body = select(messages, heartbeats).map(Ok::<_, Infallible>);
+
+    // compressing would hold the events back until the encoder's buffer is full
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((CACHE_CONTROL, "no-cache"))
+        .insert_header((CONTENT_ENCODING, "identity"))
+        .streaming(body))
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 101, description = "Upgraded to a WebSocket. Every text frame holds a RealtimeMessage as JSON."),
+        ValidationError,
+        Unauthorized,
+        InternalServerError,
+    ),
+    params(RealtimeQuery),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/realtime/socket",
+    tag = "Realtime"
+)]
+#[get("/socket")]
+pub(crate) async fn realtime_socket(
+    user: Phantom<User>,
+    request: HttpRequest,
+    body: Payload,
+    query: Query<RealtimeQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let last_event_id = query.last_event_id(request.headers());
+    let heartbeat = Duration::from_secs(Config::get_config().realtime.heartbeat_seconds);
+    let (response, session, incoming) = actix_ws::handle(&request, body)?;
+
+    spawn(run_socket(session, incoming, RealtimeHub::subscribe(user.get_id(), last_event_id), heartbeat));
+
+    Ok(response)
+}
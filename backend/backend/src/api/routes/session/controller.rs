@@ -2,15 +2,21 @@ use actix_web::http::Uri;
 use actix_web::web::{Json, Path};
 use actix_web::{delete, get, patch, post, web, HttpResponse, Responder};
 use actix_web_validation::Validated;
+use entity::utility::time::get_now;
 use utility::snowflake::entity::Snowflake;
 
 use crate::api::documentation::response::ValidationError;
 use crate::api::documentation::response::{InternalServerError, ResourceNotFound, Unauthorized};
 use crate::api::error::api::ApiError;
 use crate::api::pagination::{PageSizeParam, Pagination};
-use crate::wrapper::entity::session::dto::PublicSession;
+use crate::util::client::ClientInfo;
+use crate::wrapper::entity::login_attempt::lockout::LoginLockout;
+use crate::wrapper::entity::session::challenge::TotpChallenge;
+use crate::wrapper::entity::session::dto::{OidcCallback, PublicSession, TotpLogin};
+use crate::wrapper::entity::session::oidc::OidcAuthorization;
 use crate::wrapper::entity::session::Session;
 use crate::wrapper::entity::user::dto::Credentials;
+use crate::wrapper::entity::user::totp::UserTotp;
 use crate::wrapper::entity::user::User;
 use crate::wrapper::permission::{HasPermissionOrError, Permissions};
 use crate::wrapper::types::phantom::Phantom;
@@ -25,6 +31,9 @@ pub(crate) fn session_controller(cfg: &mut web::ServiceConfig) {
             .service(delete_session)
             .service(delete_all_sessions)
             .service(create_session)
+            .service(authorize_oidc)
+            .service(complete_oidc_login)
+            .service(complete_totp_challenge)
             .service(get_one_session),
     );
 }
@@ -178,7 +187,7 @@ pub(crate) async fn delete_session(
 )]
 #[delete("")]
 pub(crate) async fn delete_all_sessions(user: Phantom<User>) -> Result<impl Responder, ApiError> {
-    Session::delete_all_with_user(user.get_id()).await?;
+    Session::delete_all_with_user(user.get_id(), None).await?;
 
     Ok(HttpResponse::NoContent())
 }
@@ -186,18 +195,94 @@ pub(crate) async fn delete_all_sessions(user: Phantom<User>) -> Result<impl Resp
 #[utoipa::path(post,
     responses(
         (status = 201, description = "Successfully created a new Session.", content_type = "application/json", body = Session),
+        (status = 202, description = "The User has two-factor authentication enabled. Answer the challenge at /api/v1/session/totp to create the Session.", content_type = "application/json", body = TotpChallenge),
+        (status = 423, description = "The User is locked after too many failed logins."),
+        (status = 429, description = "Too many failed logins for the username or IP, try again later."),
         ValidationError,
         Unauthorized,
         InternalServerError,
     ),
+    request_body = Credentials,
     path = "/api/v1/session",
     tag = "Session"
 )]
 #[post("")]
-pub(crate) async fn create_session(credentials: Validated<Json<Credentials>>) -> Result<impl Responder, ApiError> {
+pub(crate) async fn create_session(
+    client: ClientInfo,
+    credentials: Validated<Json<Credentials>>,
+) -> Result<impl Responder, ApiError> {
     let credentials = credentials.into_inner().into_inner();
-    let user = User::authenticate(&credentials).await?;
-    let session = Session::new(user, credentials).await?;
+    let user = LoginLockout::authenticate(&credentials, &client).await?;
+    if UserTotp::find_confirmed_by_user(user.snowflake).await?.is_some() {
+        let challenge = TotpChallenge::new(&user, credentials).await?;
+
+        return Ok(HttpResponse::Accepted().json(challenge));
+    }
+    LoginLockout::register_success(&user, &client).await?;
+    let session = Session::new(user, credentials, &client).await?;
+
+    Ok(HttpResponse::Created().json(session))
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 201, description = "Successfully answered the challenge and created a new Session.", content_type = "application/json", body = Session),
+        (status = 423, description = "The User is locked after too many failed logins."),
+        (status = 429, description = "Too many failed logins for the username or IP, try again later."),
+        ValidationError,
+        Unauthorized,
+        InternalServerError,
+    ),
+    path = "/api/v1/session/totp",
+    request_body = TotpLogin,
+    tag = "Session"
+)]
+#[post("/totp")]
+pub(crate) async fn complete_totp_challenge(
+    client: ClientInfo,
+    login: Validated<Json<TotpLogin>>,
+) -> Result<impl Responder, ApiError> {
+    let login = login.into_inner().into_inner();
+    let session = TotpChallenge::complete(&login.challenge_token, &login.code, get_now(), &client).await?;
+
+    Ok(HttpResponse::Created().json(session))
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully started a single sign-on login. Send the user to the authorization URL.", content_type = "application/json", body = OidcAuthorization),
+        (status = 404, description = "Single sign-on is not configured."),
+        InternalServerError,
+    ),
+    path = "/api/v1/session/oidc/authorize",
+    tag = "Session"
+)]
+#[get("/oidc/authorize")]
+pub(crate) async fn authorize_oidc() -> Result<impl Responder, ApiError> {
+    let authorization = OidcAuthorization::new().await?;
+
+    Ok(HttpResponse::Ok().json(authorization))
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 201, description = "Successfully signed in through the identity provider and created a new Session.", content_type = "application/json", body = Session),
+        (status = 403, description = "No User is linked to the identity and auto-provisioning is disabled."),
+        (status = 404, description = "Single sign-on is not configured."),
+        ValidationError,
+        Unauthorized,
+        InternalServerError,
+    ),
+    path = "/api/v1/session/oidc/callback",
+    request_body = OidcCallback,
+    tag = "Session"
+)]
+#[post("/oidc/callback")]
+pub(crate) async fn complete_oidc_login(
+    client: ClientInfo,
+    callback: Validated<Json<OidcCallback>>,
+) -> Result<impl Responder, ApiError> {
+    let session = OidcAuthorization::complete(callback.into_inner().into_inner(), &client).await?;
 
     Ok(HttpResponse::Created().json(session))
 }
@@ -0,0 +1,54 @@
+use actix_web::web::{Json, Query};
+use actix_web::{get, post, web, HttpResponse, Responder};
+use actix_web_validation::Validated;
+
+use crate::api::documentation::response::{InternalServerError, Unauthorized, ValidationError};
+use crate::api::error::api::ApiError;
+use crate::wrapper::entity::user::User;
+use crate::wrapper::sync::dto::{SyncPushDTO, SyncQuery};
+use crate::wrapper::sync::{pull, push};
+use crate::wrapper::types::phantom::Phantom;
+
+pub(crate) fn sync_controller(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::scope("/sync").service(pull_changes).service(push_changes));
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved the changes since the sync token.", content_type = "application/json", body = SyncPull),
+        (status = 400, description = "The sync token is invalid.", content_type = "application/json", body = ApiError),
+        (status = 410, description = "The sync token is too old, the data has to be loaded again.", content_type = "application/json", body = ApiError),
+        Unauthorized,
+        InternalServerError,
+    ),
+    params(SyncQuery),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/sync",
+    tag = "Sync")]
+#[get("")]
+pub(crate) async fn pull_changes(user: Phantom<User>, query: Query<SyncQuery>) -> Result<impl Responder, ApiError> {
+    Ok(HttpResponse::Ok().json(pull(user.get_id(), query.token.as_deref()).await?))
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 200, description = "Applied the changes, conflicting ones come with the current state of the entity.", content_type = "application/json", body = [SyncPushResult]),
+        ValidationError,
+        Unauthorized,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/sync",
+    request_body = SyncPushDTO,
+    tag = "Sync")]
+#[post("")]
+pub(crate) async fn push_changes(
+    user: Phantom<User>,
+    changes: Validated<Json<SyncPushDTO>>,
+) -> Result<impl Responder, ApiError> {
+    Ok(HttpResponse::Ok().json(push(user.get_id(), changes.into_inner().into_inner()).await?))
+}
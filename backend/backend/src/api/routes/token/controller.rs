@@ -0,0 +1,93 @@
+use actix_web::http::Uri;
+use actix_web::web::{Json, Path};
+use actix_web::{delete, get, post, web, HttpResponse, Responder};
+use actix_web_validation::Validated;
+
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::documentation::response::{InternalServerError, ResourceNotFound, Unauthorized, ValidationError};
+use crate::api::error::api::ApiError;
+use crate::api::pagination::{PageSizeParam, Pagination};
+use crate::wrapper::entity::api_token::dto::ApiTokenDTO;
+use crate::wrapper::entity::api_token::ApiToken;
+use crate::wrapper::entity::user::User;
+use crate::wrapper::types::phantom::Phantom;
+
+pub(crate) fn api_token_controller(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::scope("/token").service(get_all_api_tokens).service(create_api_token).service(delete_api_token));
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved all API tokens of the User.", content_type = "application/json", body = PaginatedApiToken),
+        ValidationError,
+        Unauthorized,
+        InternalServerError,
+    ),
+    params(PageSizeParam),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/token",
+    tag = "Api-Token"
+)]
+#[get("")]
+pub(crate) async fn get_all_api_tokens(
+    user: Phantom<User>,
+    page_size: PageSizeParam,
+    uri: Uri,
+) -> Result<impl Responder, ApiError> {
+    let total = ApiToken::count_all_by_user(user.get_id()).await?;
+    let api_tokens = ApiToken::find_all_by_user_paginated(user.get_id(), &page_size).await?;
+
+    Ok(HttpResponse::Ok().json(Pagination::new(api_tokens, &page_size, total, uri)))
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 201, description = "Successfully created the API token. The token is only shown in this response.", content_type = "application/json", body = CreatedApiToken),
+        ValidationError,
+        Unauthorized,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/token",
+    request_body = ApiTokenDTO,
+    tag = "Api-Token"
+)]
+#[post("")]
+pub(crate) async fn create_api_token(
+    user: Phantom<User>,
+    api_token: Validated<Json<ApiTokenDTO>>,
+) -> Result<impl Responder, ApiError> {
+    let created = ApiToken::create(user.get_id(), api_token.into_inner().into_inner()).await?;
+
+    Ok(HttpResponse::Created().json(created))
+}
+
+#[utoipa::path(delete,
+    responses(
+        (status = 204, description = "Successfully revoked the API token."),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    params(("token_id" = Snowflake,)),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/token/{token_id}",
+    tag = "Api-Token"
+)]
+#[delete("/{token_id}")]
+pub(crate) async fn delete_api_token(
+    user: Phantom<User>,
+    token_id: Path<Snowflake>,
+) -> Result<impl Responder, ApiError> {
+    let api_token = ApiToken::find_by_id_and_user(token_id.into_inner(), user.get_id()).await?;
+    api_token.delete().await?;
+
+    Ok(HttpResponse::NoContent())
+}
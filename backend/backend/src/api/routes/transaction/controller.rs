@@ -1,15 +1,20 @@
 use actix_web::http::Uri;
-use actix_web::web::{Json, Path};
+use actix_web::web::{Json, Path, Query};
 use actix_web::{delete, get, patch, post, web, HttpResponse, Responder};
 use actix_web_validation::Validated;
 use utility::snowflake::entity::Snowflake;
 
-use crate::api::documentation::response::{InternalServerError, Unauthorized, ValidationError};
+use crate::api::conditional::{versioned_response, IfMatch, IfNoneMatch};
+use crate::api::documentation::response::{
+    InternalServerError, PreconditionFailed, PreconditionRequired, ResourceNotFound, Unauthorized, ValidationError,
+};
 use crate::api::error::api::ApiError;
+use crate::api::expansion::ResponseShape;
 use crate::api::pagination::{PageSizeParam, Pagination};
 use crate::api::routes::transaction::recurring::controller::recurring_transaction_controller;
 use crate::api::routes::transaction::template::controller::transaction_template_controller;
 use crate::wrapper::entity::transaction::dto::{TransactionDTO, TransactionFromTemplate};
+use crate::wrapper::entity::transaction::history::TransactionVersion;
 use crate::wrapper::entity::transaction::Transaction;
 use crate::wrapper::entity::user::User;
 use crate::wrapper::permission::{HasPermissionOrError, Permissions};
@@ -25,6 +30,8 @@ pub(crate) fn transaction_controller(cfg: &mut web::ServiceConfig) {
             .service(create_from_transaction_template)
             .service(delete_transaction)
             .service(update_transaction)
+            .service(get_transaction_history)
+            .service(restore_transaction_version)
             .service(get_one_transaction),
     );
 }
@@ -36,7 +43,7 @@ pub(crate) fn transaction_controller(cfg: &mut web::ServiceConfig) {
         Unauthorized,
         InternalServerError,
     ),
-    params(PageSizeParam),
+    params(PageSizeParam, ResponseShape),
     security(
         ("bearer_token" = [])
     ),
@@ -46,36 +53,42 @@ pub(crate) fn transaction_controller(cfg: &mut web::ServiceConfig) {
 pub(crate) async fn get_all_transactions(
     user: Phantom<User>,
     page_size: PageSizeParam,
+    shape: Query<ResponseShape>,
     uri: Uri,
 ) -> Result<impl Responder, ApiError> {
     let total = Transaction::count_all_by_user(user.get_id()).await?;
     let transactions = Transaction::find_all_by_user_paginated(user.get_id(), &page_size).await?;
+    let page = Pagination::new(transactions, &page_size, total, uri);
 
-    Ok(HttpResponse::Ok().json(Pagination::new(transactions, &page_size, total, uri)))
+    Ok(HttpResponse::Ok().json(shape.apply_page(user.get_id(), page).await?))
 }
 
 #[utoipa::path(get,
     responses(
         (status = 200, description = "Successfully retrieved Transaction.", content_type = "application/json", body = Transaction),
+        (status = 304, description = "The Transaction did not change since the ETag given in If-None-Match."),
+        ValidationError,
         Unauthorized,
         InternalServerError,
     ),
     security(
         ("bearer_token" = [])
     ),
-    params(("transaction_id" = Snowflake,)),
+    params(("transaction_id" = Snowflake,), ResponseShape, ("If-None-Match" = Option<String>, Header, description = "The ETag of the copy the client already has.")),
     path = "/api/v1/transaction/{transaction_id}",
     tag = "Transaction")]
 #[get("/{transaction_id}")]
 pub(crate) async fn get_one_transaction(
     user: Phantom<User>,
     transaction_id: Path<Snowflake>,
+    shape: Query<ResponseShape>,
+    if_none_match: IfNoneMatch,
 ) -> Result<impl Responder, ApiError> {
     let transaction_id = transaction_id.into_inner();
     let transaction = Transaction::find_by_id(transaction_id).await?;
     transaction.has_permission_or_error(user.get_id(), Permissions::READ).await?;
 
-    Ok(HttpResponse::Ok().json(transaction))
+    shape.respond(user.get_id(), transaction, &if_none_match).await
 }
 
 #[utoipa::path(post,
@@ -101,7 +114,9 @@ pub(crate) async fn create_transaction(
         return Err(ApiError::Unauthorized());
     }
 
-    Ok(HttpResponse::Created().json(Transaction::new(transaction).await?))
+    let transaction = Transaction::new(transaction).await?;
+
+    Ok(versioned_response(HttpResponse::Created(), &transaction))
 }
 
 #[utoipa::path(post,
@@ -127,29 +142,35 @@ pub(crate) async fn create_from_transaction_template(
     template.has_permission_or_error(user.get_id(), Permissions::READ).await?;
     let dto = TransactionDTO::from_template(template, transaction_from_template.executed_at).await?;
 
-    Ok(HttpResponse::Created().json(Transaction::new(dto).await?))
+    let transaction = Transaction::new(dto).await?;
+
+    Ok(versioned_response(HttpResponse::Created(), &transaction))
 }
 
 #[utoipa::path(delete,
     responses(
         (status = 204, description = "Successfully deleted Transaction."),
         Unauthorized,
+        PreconditionFailed,
+        PreconditionRequired,
         InternalServerError,
     ),
     security(
         ("bearer_token" = [])
     ),
-    params(("transaction_id" = Snowflake,)),
+    params(("transaction_id" = Snowflake,), ("If-Match" = String, Header, description = "The ETag the client last saw, or `*` to skip the check.")),
     path = "/api/v1/transaction/{transaction_id}",
     tag = "Transaction")]
 #[delete("/{transaction_id}")]
 pub(crate) async fn delete_transaction(
     user: Phantom<User>,
     transaction_id: Path<Snowflake>,
+    if_match: IfMatch,
 ) -> Result<impl Responder, ApiError> {
     let transaction_id = transaction_id.into_inner();
     let transaction = Transaction::find_by_id(transaction_id).await?;
     transaction.has_permission_or_error(user.get_id(), Permissions::READ_DELETE).await?;
+    if_match.check(&transaction)?;
 
     transaction.delete().await?;
 
@@ -160,12 +181,14 @@ pub(crate) async fn delete_transaction(
 responses(
 (status = 200, description = "Successfully updated Transaction.", content_type = "application/json", body = Transaction),
 Unauthorized,
+PreconditionFailed,
+PreconditionRequired,
 InternalServerError,
 ),
 security(
 ("bearer_token" = [])
 ),
-params(("transaction_id" = Snowflake,)),
+params(("transaction_id" = Snowflake,), ("If-Match" = String, Header, description = "The ETag the client last saw, or `*` to skip the check.")),
 path = "/api/v1/transaction/{transaction_id}",
 request_body = TransactionDTO,
 tag = "Transaction")]
@@ -174,13 +197,78 @@ pub(crate) async fn update_transaction(
     user: Phantom<User>,
     transaction_dto: Validated<Json<TransactionDTO>>,
     transaction_id: Path<Snowflake>,
+    if_match: IfMatch,
 ) -> Result<impl Responder, ApiError> {
     let transaction_dto = transaction_dto.into_inner().into_inner();
 
     let transaction = Transaction::find_by_id(transaction_id.into_inner()).await?;
     transaction.has_permission_or_error(user.get_id(), Permissions::READ_WRITE).await?;
+    if_match.check(&transaction)?;
 
     let transaction = transaction.update(transaction_dto).await?;
 
-    Ok(HttpResponse::Ok().json(transaction))
+    Ok(versioned_response(HttpResponse::Ok(), &transaction))
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved the revisions of the Transaction, newest first.", content_type = "application/json", body = PaginatedTransactionVersion),
+        ValidationError,
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    params(("transaction_id" = Snowflake,), PageSizeParam),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/transaction/{transaction_id}/history",
+    tag = "Transaction")]
+#[get("/{transaction_id}/history")]
+pub(crate) async fn get_transaction_history(
+    user: Phantom<User>,
+    transaction_id: Path<Snowflake>,
+    page_size: PageSizeParam,
+    uri: Uri,
+) -> Result<impl Responder, ApiError> {
+    let transaction = Transaction::find_by_id(transaction_id.into_inner()).await?;
+    transaction.has_permission_or_error(user.get_id(), Permissions::READ).await?;
+
+    let total = TransactionVersion::count_all_by_transaction(transaction.snowflake).await?;
+    let versions = TransactionVersion::find_all_by_transaction_paginated(transaction.snowflake, &page_size).await?;
+
+    Ok(HttpResponse::Ok().json(Pagination::new(versions, &page_size, total, uri)))
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 200, description = "Successfully restored the Transaction to the given revision.", content_type = "application/json", body = Transaction),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    params(("transaction_id" = Snowflake,), ("revision" = i32,)),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/transaction/{transaction_id}/history/{revision}/restore",
+    tag = "Transaction")]
+#[post("/{transaction_id}/history/{revision}/restore")]
+pub(crate) async fn restore_transaction_version(
+    user: Phantom<User>,
+    path: Path<(Snowflake, i32)>,
+) -> Result<impl Responder, ApiError> {
+    let (transaction_id, revision) = path.into_inner();
+    let transaction = Transaction::find_by_id(transaction_id).await?;
+    transaction.has_permission_or_error(user.get_id(), Permissions::READ_WRITE).await?;
+
+    let dto = TransactionVersion::find(transaction.snowflake, revision).await?.to_dto();
+    if !dto.check_permissions(user.get_id()).await? {
+        return Err(ApiError::Unauthorized());
+    }
+
+    // going through update fires TransactionUpdate, which fixes up balances and budgets
+    let transaction = transaction.update(dto).await?;
+
+    Ok(versioned_response(HttpResponse::Ok(), &transaction))
 }
@@ -1,11 +1,12 @@
 use actix_web::http::Uri;
-use actix_web::web::{Path, ServiceConfig};
+use actix_web::web::{Path, Query, ServiceConfig};
 use actix_web::{delete, get, patch, post, web, HttpResponse, Responder};
 
 use utility::snowflake::entity::Snowflake;
 
-use crate::api::documentation::response::{InternalServerError, Unauthorized};
+use crate::api::documentation::response::{InternalServerError, Unauthorized, ValidationError};
 use crate::api::error::api::ApiError;
+use crate::api::expansion::ResponseShape;
 use crate::api::pagination::{PageSizeParam, Pagination};
 use crate::wrapper::entity::transaction::recurring::dto::RecurringTransactionDTO;
 use crate::wrapper::entity::transaction::recurring::RecurringTransaction;
@@ -27,10 +28,11 @@ pub(crate) fn recurring_transaction_controller(cfg: &mut ServiceConfig) {
 #[utoipa::path(get,
     responses(
         (status = 200, description = "Successfully retrieved all Recurring Transactions.", content_type = "application/json", body = PaginatedRecurringTransaction),
+        ValidationError,
         Unauthorized,
         InternalServerError,
     ),
-    params(PageSizeParam),
+    params(PageSizeParam, ResponseShape),
     security(
         ("bearer_token" = [])
     ),
@@ -40,35 +42,39 @@ pub(crate) fn recurring_transaction_controller(cfg: &mut ServiceConfig) {
 pub(crate) async fn get_all_recurring_transactions(
     user: Phantom<User>,
     page_size: PageSizeParam,
+    shape: Query<ResponseShape>,
     uri: Uri,
 ) -> Result<impl Responder, ApiError> {
     let total = RecurringTransaction::count_all_by_user_id(user.get_id()).await?;
     let transactions = RecurringTransaction::find_all_by_user_id_paginated(user.get_id(), &page_size).await?;
+    let page = Pagination::new(transactions, &page_size, total, uri);
 
-    Ok(HttpResponse::Ok().json(Pagination::new(transactions, &page_size, total, uri)))
+    Ok(HttpResponse::Ok().json(shape.apply_page(user.get_id(), page).await?))
 }
 
 #[utoipa::path(get,
     responses(
         (status = 200, description = "Successfully retrieved Recurring Transaction.", content_type = "application/json", body = RecurringTransaction),
+        ValidationError,
         Unauthorized,
         InternalServerError,
     ),
     security(
         ("bearer_token" = [])
     ),
-    params(("recurring_transaction_id" = Snowflake,)),
+    params(("recurring_transaction_id" = Snowflake,), ResponseShape),
     path = "/api/v1/transaction/recurring/{recurring_transaction_id}",
     tag = "Recurring-Transaction")]
 #[get("/{recurring_transaction_id}")]
 pub(crate) async fn get_one_recurring_transaction(
     user: Phantom<User>,
     recurring_transaction_id: Path<Snowflake>,
+    shape: Query<ResponseShape>,
 ) -> Result<impl Responder, ApiError> {
     let transaction = RecurringTransaction::find_by_id(recurring_transaction_id.into_inner()).await?;
     transaction.has_permission_or_error(user.get_id(), Permissions::READ).await?;
 
-    Ok(HttpResponse::Ok().json(transaction))
+    Ok(HttpResponse::Ok().json(shape.apply(user.get_id(), transaction).await?))
 }
 
 #[utoipa::path(post,
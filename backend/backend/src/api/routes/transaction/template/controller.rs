@@ -1,5 +1,5 @@
 use actix_web::http::Uri;
-use actix_web::web::Json;
+use actix_web::web::{Json, Query};
 use actix_web::{delete, get, patch, post, web, HttpResponse, Responder};
 use actix_web_validation::Validated;
 use web::{Path, ServiceConfig};
@@ -8,6 +8,7 @@ use utility::snowflake::entity::Snowflake;
 
 use crate::api::documentation::response::{InternalServerError, Unauthorized, ValidationError};
 use crate::api::error::api::ApiError;
+use crate::api::expansion::ResponseShape;
 use crate::api::pagination::{PageSizeParam, Pagination};
 use crate::wrapper::entity::transaction::template::dto::TransactionTemplateDTO;
 use crate::wrapper::entity::transaction::template::TransactionTemplate;
@@ -33,7 +34,7 @@ pub(crate) fn transaction_template_controller(cfg: &mut ServiceConfig) {
         Unauthorized,
         InternalServerError,
     ),
-    params(PageSizeParam),
+    params(PageSizeParam, ResponseShape),
     security(
         ("bearer_token" = [])
     ),
@@ -43,35 +44,39 @@ pub(crate) fn transaction_template_controller(cfg: &mut ServiceConfig) {
 pub(crate) async fn get_all_transaction_templates(
     user: Phantom<User>,
     page_size: PageSizeParam,
+    shape: Query<ResponseShape>,
     uri: Uri,
 ) -> Result<impl Responder, ApiError> {
     let total = TransactionTemplate::count_all_by_user_id(user.get_id()).await?;
     let transactions = TransactionTemplate::find_all_by_user_id_paginated(user.get_id(), &page_size).await?;
+    let page = Pagination::new(transactions, &page_size, total, uri);
 
-    Ok(HttpResponse::Ok().json(Pagination::new(transactions, &page_size, total, uri)))
+    Ok(HttpResponse::Ok().json(shape.apply_page(user.get_id(), page).await?))
 }
 
 #[utoipa::path(get,
     responses(
         (status = 200, description = "Successfully retrieved Transaction.", content_type = "application/json", body = TransactionTemplate),
+        ValidationError,
         Unauthorized,
         InternalServerError,
     ),
     security(
         ("bearer_token" = [])
     ),
-    params(("template_id" = Snowflake,)),
+    params(("template_id" = Snowflake,), ResponseShape),
     path = "/api/v1/transaction/template/{template_id}",
     tag = "Transaction-Template")]
 #[get("/{template_id}")]
 pub(crate) async fn get_one_transaction_template(
     user: Phantom<User>,
     template_id: Path<Snowflake>,
+    shape: Query<ResponseShape>,
 ) -> Result<impl Responder, ApiError> {
     let transaction = TransactionTemplate::find_by_id(template_id.into_inner()).await?;
     transaction.has_permission_or_error(user.get_id(), Permissions::READ).await?;
 
-    Ok(HttpResponse::Ok().json(transaction))
+    Ok(HttpResponse::Ok().json(shape.apply(user.get_id(), transaction).await?))
 }
 
 #[utoipa::path(post,
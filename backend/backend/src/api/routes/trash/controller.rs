@@ -0,0 +1,72 @@
+use actix_web::http::Uri;
+use actix_web::web::Path;
+use actix_web::{get, post, web, HttpResponse, Responder};
+
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::documentation::response::{InternalServerError, ResourceNotFound, Unauthorized, ValidationError};
+use crate::api::error::api::ApiError;
+use crate::api::pagination::{PageSizeParam, Pagination};
+use crate::wrapper::entity::trash::TrashEntry;
+use crate::wrapper::entity::user::User;
+use crate::wrapper::permission::Permissions;
+use crate::wrapper::types::phantom::Phantom;
+
+pub(crate) fn trash_controller(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::scope("/trash").service(get_trash).service(restore_trash_entry));
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved all deleted entities that can still be restored.", content_type = "application/json", body = PaginatedTrashEntry),
+        ValidationError,
+        Unauthorized,
+        InternalServerError,
+    ),
+    params(PageSizeParam),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/trash",
+    tag = "Trash"
+)]
+#[get("")]
+pub(crate) async fn get_trash(
+    user: Phantom<User>,
+    page_size: PageSizeParam,
+    uri: Uri,
+) -> Result<impl Responder, ApiError> {
+    let total = TrashEntry::count_all_by_user(user.get_id()).await?;
+    let entries = TrashEntry::find_all_by_user_paginated(user.get_id(), &page_size).await?;
+
+    Ok(HttpResponse::Ok().json(Pagination::new(entries, &page_size, total, uri)))
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 204, description = "Successfully restored the entity and everything that was deleted along with it."),
+        Unauthorized,
+        ResourceNotFound,
+        (status = 410, description = "The retention window for restoring has expired."),
+        InternalServerError,
+    ),
+    params(("entity_type" = String,), ("entity_id" = Snowflake,)),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/trash/{entity_type}/{entity_id}/restore",
+    tag = "Trash"
+)]
+#[post("/{entity_type}/{entity_id}/restore")]
+pub(crate) async fn restore_trash_entry(
+    user: Phantom<User>,
+    path: Path<(String, Snowflake)>,
+) -> Result<impl Responder, ApiError> {
+    let (entity_type, entity_id) = path.into_inner();
+    let entry = TrashEntry::find(&entity_type, entity_id).await?;
+    entry.has_permission_or_error(user.get_id(), Permissions::READ_DELETE).await?;
+
+    entry.restore().await?;
+
+    Ok(HttpResponse::NoContent())
+}
@@ -1,13 +1,34 @@
-use actix_web::{get, post, web, HttpResponse, Responder};
+use actix_web::http::Uri;
+use actix_web::web::Json;
+use actix_web::{delete, get, patch, post, web, HttpResponse, Responder};
+use actix_web_validation::Validated;
 
 use crate::api::documentation::response::{InternalServerError, ResourceNotFound, Unauthorized, ValidationError};
 use crate::api::error::api::ApiError;
-use crate::wrapper::entity::user::dto::UserRegistration;
+use crate::api::pagination::{PageSizeParam, Pagination};
+use crate::api::routes::user::totp::controller::totp_controller;
+use crate::wrapper::entity::login_attempt::LoginAttempt;
+use crate::wrapper::entity::session::Session;
+use crate::wrapper::entity::user::dto::{
+    PasswordChange, PasswordResetConfirmation, PasswordResetRequest, UserDeletion, UserProfile, UserRegistration,
+};
+use crate::wrapper::entity::user::password_reset::PasswordReset;
 use crate::wrapper::entity::user::User;
 use crate::wrapper::types::phantom::Phantom;
 
 pub(crate) fn user_controller(cfg: &mut web::ServiceConfig) {
-    cfg.service(web::scope("/user").service(me).service(register));
+    cfg.service(
+        web::scope("/user")
+            .configure(totp_controller)
+            .service(me)
+            .service(update_me)
+            .service(delete_me)
+            .service(change_password)
+            .service(get_login_activity)
+            .service(register)
+            .service(request_password_reset)
+            .service(confirm_password_reset),
+    );
 }
 
 #[utoipa::path(get,
@@ -31,6 +52,8 @@ pub(crate) async fn me(mut user: Phantom<User>) -> Result<impl Responder, ApiErr
 #[utoipa::path(post,
 responses(
 (status = 201, description = "Successfully registered.", content_type = "application/json", body = User),
+(status = 400, description = "The invitation code is missing, invalid, expired or used up."),
+(status = 403, description = "Registration is closed."),
 (status = 409, description = "User is signed in."),
 ValidationError,
 InternalServerError,
@@ -45,3 +68,138 @@ pub(crate) async fn register(registration: UserRegistration) -> Result<impl Resp
 
     Ok(HttpResponse::Created().json(user))
 }
+
+#[utoipa::path(patch,
+    responses(
+        (status = 200, description = "Successfully updated your profile.", content_type = "application/json", body = User),
+        ValidationError,
+        Unauthorized,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/user/@me",
+    request_body = UserProfile,
+    tag = "User"
+)]
+#[patch("/@me")]
+pub(crate) async fn update_me(user: User, profile: Validated<Json<UserProfile>>) -> Result<impl Responder, ApiError> {
+    let user = user.update_profile(profile.into_inner().into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(user))
+}
+
+#[utoipa::path(delete,
+    responses(
+        (status = 204, description = "Successfully deleted your User and all of its Sessions."),
+        ValidationError,
+        Unauthorized,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/user/@me",
+    request_body = UserDeletion,
+    tag = "User"
+)]
+#[delete("/@me")]
+pub(crate) async fn delete_me(user: User, deletion: Validated<Json<UserDeletion>>) -> Result<impl Responder, ApiError> {
+    user.verify_password(&deletion.password).await?;
+    user.delete().await?;
+
+    Ok(HttpResponse::NoContent())
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 204, description = "Successfully changed your password. All other Sessions were signed out."),
+        ValidationError,
+        Unauthorized,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/user/@me/password",
+    request_body = PasswordChange,
+    tag = "User"
+)]
+#[post("/@me/password")]
+pub(crate) async fn change_password(
+    session: Session,
+    password_change: Validated<Json<PasswordChange>>,
+) -> Result<impl Responder, ApiError> {
+    let user = &session.user;
+    user.verify_password(&password_change.old_password).await?;
+    user.change_password(&password_change.new_password).await?;
+    Session::delete_all_with_user(user.snowflake, Some(session.snowflake)).await?;
+
+    Ok(HttpResponse::NoContent())
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 204, description = "If the email belongs to a User, a password reset token was mailed to it."),
+        ValidationError,
+        InternalServerError,
+    ),
+    path = "/api/v1/user/password-reset",
+    request_body = PasswordResetRequest,
+    tag = "User"
+)]
+#[post("/password-reset")]
+pub(crate) async fn request_password_reset(
+    reset_request: Validated<Json<PasswordResetRequest>>,
+) -> Result<impl Responder, ApiError> {
+    PasswordReset::request(&reset_request.email).await?;
+
+    Ok(HttpResponse::NoContent())
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 204, description = "Successfully reset the password. All Sessions of the User were signed out."),
+        (status = 400, description = "The token is invalid, expired or was already used."),
+        ValidationError,
+        InternalServerError,
+    ),
+    path = "/api/v1/user/password-reset/confirm",
+    request_body = PasswordResetConfirmation,
+    tag = "User"
+)]
+#[post("/password-reset/confirm")]
+pub(crate) async fn confirm_password_reset(
+    confirmation: Validated<Json<PasswordResetConfirmation>>,
+) -> Result<impl Responder, ApiError> {
+    PasswordReset::confirm(&confirmation.token, &confirmation.new_password).await?;
+
+    Ok(HttpResponse::NoContent())
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved your recent logins, newest first.", content_type = "application/json", body = PaginatedLoginAttempt),
+        ValidationError,
+        Unauthorized,
+        InternalServerError,
+    ),
+    params(PageSizeParam),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/user/@me/login-activity",
+    tag = "User"
+)]
+#[get("/@me/login-activity")]
+pub(crate) async fn get_login_activity(
+    user: Phantom<User>,
+    page_size: PageSizeParam,
+    uri: Uri,
+) -> Result<impl Responder, ApiError> {
+    let total = LoginAttempt::count_all_by_user(user.get_id()).await?;
+    let attempts = LoginAttempt::find_all_by_user_paginated(user.get_id(), &page_size).await?;
+
+    Ok(HttpResponse::Ok().json(Pagination::new(attempts, &page_size, total, uri)))
+}
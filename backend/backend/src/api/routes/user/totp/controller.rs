@@ -0,0 +1,120 @@
+use actix_web::web::Json;
+use actix_web::{delete, post, web, HttpResponse, Responder};
+use actix_web_validation::Validated;
+
+use entity::utility::time::get_now;
+
+use crate::api::documentation::response::{InternalServerError, Unauthorized, ValidationError};
+use crate::api::error::api::ApiError;
+use crate::wrapper::entity::user::totp::dto::{TotpCode, TotpDisable};
+use crate::wrapper::entity::user::totp::UserTotp;
+use crate::wrapper::entity::user::User;
+
+pub(crate) fn totp_controller(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/@me/totp")
+            .service(enroll_totp)
+            .service(confirm_totp)
+            .service(regenerate_recovery_codes)
+            .service(disable_totp),
+    );
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 200, description = "Successfully started the enrollment. The secret only becomes active once it is confirmed.", content_type = "application/json", body = TotpEnrollment),
+        (status = 409, description = "Two-factor authentication is already enabled."),
+        Unauthorized,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/user/@me/totp",
+    tag = "User"
+)]
+#[post("")]
+pub(crate) async fn enroll_totp(user: User) -> Result<impl Responder, ApiError> {
+    let enrollment = UserTotp::enroll(&user).await?;
+
+    Ok(HttpResponse::Ok().json(enrollment))
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 200, description = "Successfully enabled two-factor authentication. The recovery codes are only shown once.", content_type = "application/json", body = TotpRecoveryCodes),
+        (status = 409, description = "Two-factor authentication is already enabled or was never enrolled."),
+        ValidationError,
+        Unauthorized,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/user/@me/totp/confirm",
+    request_body = TotpCode,
+    tag = "User"
+)]
+#[post("/confirm")]
+pub(crate) async fn confirm_totp(user: User, code: Validated<Json<TotpCode>>) -> Result<impl Responder, ApiError> {
+    let totp = UserTotp::find_by_user(user.snowflake).await?.ok_or_else(ApiError::TotpNotEnabled)?;
+    let recovery_codes = totp.confirm(&code.code, get_now()).await?;
+
+    Ok(HttpResponse::Ok().json(recovery_codes))
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 200, description = "Successfully replaced all recovery codes.", content_type = "application/json", body = TotpRecoveryCodes),
+        (status = 409, description = "Two-factor authentication is not enabled."),
+        ValidationError,
+        Unauthorized,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/user/@me/totp/recovery-codes",
+    request_body = TotpCode,
+    tag = "User"
+)]
+#[post("/recovery-codes")]
+pub(crate) async fn regenerate_recovery_codes(
+    user: User,
+    code: Validated<Json<TotpCode>>,
+) -> Result<impl Responder, ApiError> {
+    let mut totp = UserTotp::find_confirmed_by_user(user.snowflake).await?.ok_or_else(ApiError::TotpNotEnabled)?;
+    totp.verify_or_error(&code.code, get_now()).await?;
+
+    Ok(HttpResponse::Ok().json(totp.regenerate_recovery_codes().await?))
+}
+
+#[utoipa::path(delete,
+    responses(
+        (status = 204, description = "Successfully disabled two-factor authentication."),
+        (status = 409, description = "Two-factor authentication is not enabled."),
+        ValidationError,
+        Unauthorized,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/user/@me/totp",
+    request_body = TotpDisable,
+    tag = "User"
+)]
+#[delete("")]
+pub(crate) async fn disable_totp(
+    user: User,
+    disable: Validated<Json<TotpDisable>>,
+) -> Result<impl Responder, ApiError> {
+    let disable = disable.into_inner().into_inner();
+    user.verify_password(&disable.password).await?;
+    let mut totp = UserTotp::find_confirmed_by_user(user.snowflake).await?.ok_or_else(ApiError::TotpNotEnabled)?;
+    totp.verify_or_error(&disable.code, get_now()).await?;
+
+    totp.disable().await?;
+
+    Ok(HttpResponse::NoContent())
+}
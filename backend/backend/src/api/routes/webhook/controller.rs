@@ -0,0 +1,211 @@
+use actix_web::http::Uri;
+use actix_web::web::{Json, Path};
+use actix_web::{delete, get, patch, post, web, HttpResponse, Responder};
+use actix_web_validation::Validated;
+
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::documentation::response::{InternalServerError, ResourceNotFound, Unauthorized, ValidationError};
+use crate::api::error::api::ApiError;
+use crate::api::pagination::{PageSizeParam, Pagination};
+use crate::wrapper::entity::user::User;
+use crate::wrapper::entity::webhook::delivery::WebhookDelivery;
+use crate::wrapper::entity::webhook::dto::WebhookDTO;
+use crate::wrapper::entity::webhook::Webhook;
+use crate::wrapper::types::phantom::Phantom;
+
+pub(crate) fn webhook_controller(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/webhook")
+            .service(get_all_webhooks)
+            .service(create_webhook)
+            .service(get_one_webhook)
+            .service(update_webhook)
+            .service(delete_webhook)
+            .service(get_webhook_deliveries)
+            .service(redeliver_webhook_delivery),
+    );
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved all Webhooks of the User.", content_type = "application/json", body = PaginatedWebhook),
+        ValidationError,
+        Unauthorized,
+        InternalServerError,
+    ),
+    params(PageSizeParam),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/webhook",
+    tag = "Webhook"
+)]
+#[get("")]
+pub(crate) async fn get_all_webhooks(
+    user: Phantom<User>,
+    page_size: PageSizeParam,
+    uri: Uri,
+) -> Result<impl Responder, ApiError> {
+    let total = Webhook::count_all_by_user(user.get_id()).await?;
+    let webhooks = Webhook::find_all_by_user_paginated(user.get_id(), &page_size).await?;
+
+    Ok(HttpResponse::Ok().json(Pagination::new(webhooks, &page_size, total, uri)))
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 201, description = "Successfully created the Webhook. The signing secret is only shown in this response.", content_type = "application/json", body = CreatedWebhook),
+        ValidationError,
+        Unauthorized,
+        InternalServerError,
+    ),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/webhook",
+    request_body = WebhookDTO,
+    tag = "Webhook"
+)]
+#[post("")]
+pub(crate) async fn create_webhook(
+    user: Phantom<User>,
+    webhook: Validated<Json<WebhookDTO>>,
+) -> Result<impl Responder, ApiError> {
+    let created = Webhook::create(user.get_id(), webhook.into_inner().into_inner()).await?;
+
+    Ok(HttpResponse::Created().json(created))
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved the Webhook.", content_type = "application/json", body = Webhook),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    params(("webhook_id" = Snowflake,)),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/webhook/{webhook_id}",
+    tag = "Webhook"
+)]
+#[get("/{webhook_id}")]
+pub(crate) async fn get_one_webhook(
+    user: Phantom<User>,
+    webhook_id: Path<Snowflake>,
+) -> Result<impl Responder, ApiError> {
+    let webhook = Webhook::find_by_id_and_user(webhook_id.into_inner(), user.get_id()).await?;
+
+    Ok(HttpResponse::Ok().json(webhook))
+}
+
+#[utoipa::path(patch,
+    responses(
+        (status = 200, description = "Successfully updated the Webhook.", content_type = "application/json", body = Webhook),
+        ValidationError,
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    params(("webhook_id" = Snowflake,)),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/webhook/{webhook_id}",
+    request_body = WebhookDTO,
+    tag = "Webhook"
+)]
+#[patch("/{webhook_id}")]
+pub(crate) async fn update_webhook(
+    user: Phantom<User>,
+    webhook_id: Path<Snowflake>,
+    updated_webhook: Validated<Json<WebhookDTO>>,
+) -> Result<impl Responder, ApiError> {
+    let webhook = Webhook::find_by_id_and_user(webhook_id.into_inner(), user.get_id()).await?;
+    let webhook = webhook.update(updated_webhook.into_inner().into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(webhook))
+}
+
+#[utoipa::path(delete,
+    responses(
+        (status = 204, description = "Successfully deleted the Webhook and its delivery log."),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    params(("webhook_id" = Snowflake,)),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/webhook/{webhook_id}",
+    tag = "Webhook"
+)]
+#[delete("/{webhook_id}")]
+pub(crate) async fn delete_webhook(
+    user: Phantom<User>,
+    webhook_id: Path<Snowflake>,
+) -> Result<impl Responder, ApiError> {
+    let webhook = Webhook::find_by_id_and_user(webhook_id.into_inner(), user.get_id()).await?;
+    webhook.delete().await?;
+
+    Ok(HttpResponse::NoContent())
+}
+
+#[utoipa::path(get,
+    responses(
+        (status = 200, description = "Successfully retrieved the deliveries of the Webhook, newest first.", content_type = "application/json", body = PaginatedWebhookDelivery),
+        ValidationError,
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    params(("webhook_id" = Snowflake,), PageSizeParam),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/webhook/{webhook_id}/delivery",
+    tag = "Webhook"
+)]
+#[get("/{webhook_id}/delivery")]
+pub(crate) async fn get_webhook_deliveries(
+    user: Phantom<User>,
+    webhook_id: Path<Snowflake>,
+    page_size: PageSizeParam,
+    uri: Uri,
+) -> Result<impl Responder, ApiError> {
+    let webhook = Webhook::find_by_id_and_user(webhook_id.into_inner(), user.get_id()).await?;
+    let total = WebhookDelivery::count_all_by_webhook(webhook.snowflake).await?;
+    let deliveries = WebhookDelivery::find_all_by_webhook_paginated(webhook.snowflake, &page_size).await?;
+
+    Ok(HttpResponse::Ok().json(Pagination::new(deliveries, &page_size, total, uri)))
+}
+
+#[utoipa::path(post,
+    responses(
+        (status = 200, description = "Sent the delivery again, the response contains the outcome of the attempt.", content_type = "application/json", body = WebhookDelivery),
+        Unauthorized,
+        ResourceNotFound,
+        InternalServerError,
+    ),
+    params(("webhook_id" = Snowflake,), ("delivery_id" = Snowflake,)),
+    security(
+        ("bearer_token" = [])
+    ),
+    path = "/api/v1/webhook/{webhook_id}/delivery/{delivery_id}/redeliver",
+    tag = "Webhook"
+)]
+#[post("/{webhook_id}/delivery/{delivery_id}/redeliver")]
+pub(crate) async fn redeliver_webhook_delivery(
+    user: Phantom<User>,
+    path: Path<(Snowflake, Snowflake)>,
+) -> Result<impl Responder, ApiError> {
+    let (webhook_id, delivery_id) = path.into_inner();
+    let webhook = Webhook::find_by_id_and_user(webhook_id, user.get_id()).await?;
+    let delivery = WebhookDelivery::find_by_id_and_webhook(delivery_id, webhook.snowflake).await?;
+    let delivery = delivery.deliver(&webhook).await?;
+
+    Ok(HttpResponse::Ok().json(delivery))
+}
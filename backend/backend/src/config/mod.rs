@@ -1,7 +1,13 @@
 use std::env;
 use std::str::FromStr;
 
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::Serialize;
 use tracing::log::LevelFilter;
+use utoipa::ToSchema;
+
+use utility::totp::ENCRYPTION_KEY_LENGTH;
 
 use crate::CONFIG;
 
@@ -16,6 +22,18 @@ pub(crate) struct Config {
     pub(crate) cors: CorsConfig,
     pub(crate) session: SessionConfig,
     pub(crate) rate_limiter: RateLimiterConfig,
+    pub(crate) trash: TrashConfig,
+    pub(crate) totp: TotpConfig,
+    pub(crate) mail: MailConfig,
+    pub(crate) password_reset: PasswordResetConfig,
+    pub(crate) login_lockout: LoginLockoutConfig,
+    pub(crate) oidc: OidcConfig,
+    pub(crate) registration: RegistrationConfig,
+    pub(crate) webhook: WebhookConfig,
+    pub(crate) realtime: RealtimeConfig,
+    pub(crate) event_outbox: EventOutboxConfig,
+    pub(crate) idempotency: IdempotencyConfig,
+    pub(crate) sync: SyncConfig,
 }
 
 #[derive(Debug, Clone)]
@@ -50,6 +68,105 @@ pub(crate) struct RateLimiterConfig {
     pub(crate) duration_seconds: u64,
 }
 
+#[derive(Debug, Clone)]
+pub(crate) struct TrashConfig {
+    pub(crate) retention_days: u64,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct TotpConfig {
+    pub(crate) issuer: String,
+    pub(crate) challenge_lifetime_seconds: u64,
+    /// The key the secrets are encrypted with at rest.
+    pub(crate) encryption_key: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct MailConfig {
+    pub(crate) transport: String,
+    pub(crate) from: String,
+    pub(crate) sendmail_command: String,
+    pub(crate) file_directory: String,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct PasswordResetConfig {
+    pub(crate) lifetime_minutes: u64,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct LoginLockoutConfig {
+    pub(crate) backoff_threshold: u64,
+    pub(crate) max_backoff_seconds: u64,
+    pub(crate) lockout_threshold: u64,
+    pub(crate) lockout_seconds: u64,
+    pub(crate) failure_window_seconds: u64,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct OidcConfig {
+    /// Single sign-on is disabled without an issuer.
+    pub(crate) issuer_url: Option<String>,
+    pub(crate) client_id: String,
+    pub(crate) client_secret: String,
+    pub(crate) redirect_url: String,
+    pub(crate) scopes: String,
+    pub(crate) link_by_email: bool,
+    pub(crate) auto_provision: bool,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct RegistrationConfig {
+    pub(crate) mode: RegistrationMode,
+}
+
+/// Who can call `POST /user/register`. Admins can always create users.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum RegistrationMode {
+    Open,
+    /// Registering requires an invitation code.
+    InviteOnly,
+    Closed,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct WebhookConfig {
+    pub(crate) max_attempts: u32,
+    pub(crate) retry_base_seconds: u64,
+    pub(crate) timeout_seconds: u64,
+    /// Allows webhooks to loopback and private network addresses, which are refused by default.
+    pub(crate) allow_private_addresses: bool,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct RealtimeConfig {
+    /// How many recent events are kept in memory so reconnecting clients can resume.
+    pub(crate) replay_buffer_size: usize,
+    pub(crate) heartbeat_seconds: u64,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct EventOutboxConfig {
+    /// Attempts per subscriber before an event is moved to the dead-letter list.
+    pub(crate) max_attempts: u32,
+    pub(crate) retry_base_seconds: u64,
+    /// How long handled events are kept before they are deleted.
+    pub(crate) retention_days: i64,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct IdempotencyConfig {
+    /// How long a response is replayed for retries with the same idempotency key.
+    pub(crate) ttl_seconds: u64,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct SyncConfig {
+    pub(crate) lag_seconds: u64,
+    pub(crate) page_size: u64,
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct CorsConfig {
     pub(crate) allowed_origins: Vec<String>,
@@ -72,6 +189,18 @@ impl Config {
             cors: CorsConfig::build_config(),
             session: SessionConfig::build_config(),
             rate_limiter: RateLimiterConfig::build_config(),
+            trash: TrashConfig::build_config(),
+            totp: TotpConfig::build_config(),
+            mail: MailConfig::build_config(),
+            password_reset: PasswordResetConfig::build_config(),
+            login_lockout: LoginLockoutConfig::build_config(),
+            oidc: OidcConfig::build_config(),
+            registration: RegistrationConfig::build_config(),
+            webhook: WebhookConfig::build_config(),
+            realtime: RealtimeConfig::build_config(),
+            event_outbox: EventOutboxConfig::build_config(),
+            idempotency: IdempotencyConfig::build_config(),
+            sync: SyncConfig::build_config(),
         }
     }
 
@@ -158,6 +287,186 @@ impl RateLimiterConfig {
     }
 }
 
+impl TrashConfig {
+    pub(crate) fn build_config() -> Self {
+        Self {
+            retention_days: get_env_or_default("TRASH_RETENTION_DAYS", "30")
+                .parse::<u64>()
+                .expect("Could not parse TRASH_RETENTION_DAYS to u64!"),
+        }
+    }
+}
+
+impl TotpConfig {
+    pub(crate) fn build_config() -> Self {
+        Self {
+            issuer: get_env_or_default("TOTP_ISSUER", "financrr"),
+            challenge_lifetime_seconds: get_env_or_default("TOTP_CHALLENGE_LIFETIME_SECONDS", "300")
+                .parse::<u64>()
+                .expect("Could not parse TOTP_CHALLENGE_LIFETIME_SECONDS to u64!"),
+            encryption_key: Self::parse_encryption_key(&get_env_or_error("TOTP_ENCRYPTION_KEY")),
+        }
+    }
+
+    fn parse_encryption_key(encoded: &str) -> Vec<u8> {
+        let key = STANDARD.decode(encoded).expect("Could not decode TOTP_ENCRYPTION_KEY as base64!");
+        if key.len() != ENCRYPTION_KEY_LENGTH {
+            panic!("TOTP_ENCRYPTION_KEY must be {} bytes long!", ENCRYPTION_KEY_LENGTH);
+        }
+
+        key
+    }
+}
+
+impl MailConfig {
+    pub(crate) fn build_config() -> Self {
+        Self {
+            transport: get_env_or_default("MAIL_TRANSPORT", "log"),
+            from: get_env_or_default("MAIL_FROM", "financrr <noreply@localhost>"),
+            sendmail_command: get_env_or_default("MAIL_SENDMAIL_COMMAND", "sendmail"),
+            file_directory: get_env_or_default("MAIL_FILE_DIRECTORY", "mails"),
+        }
+    }
+}
+
+impl PasswordResetConfig {
+    pub(crate) fn build_config() -> Self {
+        Self {
+            lifetime_minutes: get_env_or_default("PASSWORD_RESET_LIFETIME_MINUTES", "30")
+                .parse::<u64>()
+                .expect("Could not parse PASSWORD_RESET_LIFETIME_MINUTES to u64!"),
+        }
+    }
+}
+
+impl LoginLockoutConfig {
+    pub(crate) fn build_config() -> Self {
+        Self {
+            backoff_threshold: get_env_or_default("LOGIN_BACKOFF_THRESHOLD", "3")
+                .parse::<u64>()
+                .expect("Could not parse LOGIN_BACKOFF_THRESHOLD to u64!"),
+            max_backoff_seconds: get_env_or_default("LOGIN_MAX_BACKOFF_SECONDS", "300")
+                .parse::<u64>()
+                .expect("Could not parse LOGIN_MAX_BACKOFF_SECONDS to u64!"),
+            lockout_threshold: get_env_or_default("LOGIN_LOCKOUT_THRESHOLD", "10")
+                .parse::<u64>()
+                .expect("Could not parse LOGIN_LOCKOUT_THRESHOLD to u64!"),
+            lockout_seconds: get_env_or_default("LOGIN_LOCKOUT_SECONDS", "3600")
+                .parse::<u64>()
+                .expect("Could not parse LOGIN_LOCKOUT_SECONDS to u64!"),
+            failure_window_seconds: get_env_or_default("LOGIN_FAILURE_WINDOW_SECONDS", "3600")
+                .parse::<u64>()
+                .expect("Could not parse LOGIN_FAILURE_WINDOW_SECONDS to u64!"),
+        }
+    }
+}
+
+impl OidcConfig {
+    pub(crate) fn build_config() -> Self {
+        let issuer_url = get_env_or_default("OIDC_ISSUER_URL", "");
+
+        Self {
+            issuer_url: (!issuer_url.is_empty()).then(|| issuer_url.trim_end_matches('/').to_string()),
+            client_id: get_env_or_default("OIDC_CLIENT_ID", ""),
+            client_secret: get_env_or_default("OIDC_CLIENT_SECRET", ""),
+            redirect_url: get_env_or_default("OIDC_REDIRECT_URL", ""),
+            scopes: get_env_or_default("OIDC_SCOPES", "openid email profile"),
+            link_by_email: get_env_or_default("OIDC_LINK_BY_EMAIL", "true")
+                .parse::<bool>()
+                .expect("Could not parse OIDC_LINK_BY_EMAIL to bool!"),
+            auto_provision: get_env_or_default("OIDC_AUTO_PROVISION", "false")
+                .parse::<bool>()
+                .expect("Could not parse OIDC_AUTO_PROVISION to bool!"),
+        }
+    }
+}
+
+impl RegistrationConfig {
+    pub(crate) fn build_config() -> Self {
+        let mode = match get_env_or_default("REGISTRATION_MODE", "open").as_str() {
+            "open" => RegistrationMode::Open,
+            "invite_only" => RegistrationMode::InviteOnly,
+            "closed" => RegistrationMode::Closed,
+            mode => panic!("Unknown REGISTRATION_MODE '{}', expected open, invite_only or closed!", mode),
+        };
+
+        Self {
+            mode,
+        }
+    }
+}
+
+impl WebhookConfig {
+    pub(crate) fn build_config() -> Self {
+        Self {
+            max_attempts: get_env_or_default("WEBHOOK_MAX_ATTEMPTS", "5")
+                .parse::<u32>()
+                .expect("Could not parse WEBHOOK_MAX_ATTEMPTS to u32!"),
+            retry_base_seconds: get_env_or_default("WEBHOOK_RETRY_BASE_SECONDS", "30")
+                .parse::<u64>()
+                .expect("Could not parse WEBHOOK_RETRY_BASE_SECONDS to u64!"),
+            timeout_seconds: get_env_or_default("WEBHOOK_TIMEOUT_SECONDS", "10")
+                .parse::<u64>()
+                .expect("Could not parse WEBHOOK_TIMEOUT_SECONDS to u64!"),
+            allow_private_addresses: get_env_or_default("WEBHOOK_ALLOW_PRIVATE_ADDRESSES", "false")
+                .parse::<bool>()
+                .expect("Could not parse WEBHOOK_ALLOW_PRIVATE_ADDRESSES to bool!"),
+        }
+    }
+}
+
+impl RealtimeConfig {
+    pub(crate) fn build_config() -> Self {
+        Self {
+            replay_buffer_size: get_env_or_default("REALTIME_REPLAY_BUFFER_SIZE", "1000")
+                .parse::<usize>()
+                .expect("Could not parse REALTIME_REPLAY_BUFFER_SIZE to usize!"),
+            heartbeat_seconds: get_env_or_default("REALTIME_HEARTBEAT_SECONDS", "15")
+                .parse::<u64>()
+                .expect("Could not parse REALTIME_HEARTBEAT_SECONDS to u64!"),
+        }
+    }
+}
+
+impl EventOutboxConfig {
+    pub(crate) fn build_config() -> Self {
+        Self {
+            max_attempts: get_env_or_default("EVENT_OUTBOX_MAX_ATTEMPTS", "5")
+                .parse::<u32>()
+                .expect("Could not parse EVENT_OUTBOX_MAX_ATTEMPTS to u32!"),
+            retry_base_seconds: get_env_or_default("EVENT_OUTBOX_RETRY_BASE_SECONDS", "10")
+                .parse::<u64>()
+                .expect("Could not parse EVENT_OUTBOX_RETRY_BASE_SECONDS to u64!"),
+            retention_days: get_env_or_default("EVENT_OUTBOX_RETENTION_DAYS", "7")
+                .parse::<i64>()
+                .expect("Could not parse EVENT_OUTBOX_RETENTION_DAYS to i64!"),
+        }
+    }
+}
+
+impl IdempotencyConfig {
+    pub(crate) fn build_config() -> Self {
+        Self {
+            ttl_seconds: get_env_or_default("IDEMPOTENCY_TTL_SECONDS", "86400")
+                .parse::<u64>()
+                .expect("Could not parse IDEMPOTENCY_TTL_SECONDS to u64!"),
+        }
+    }
+}
+
+impl SyncConfig {
+    pub(crate) fn build_config() -> Self {
+        Self {
+            lag_seconds: get_env_or_default("SYNC_LAG_SECONDS", "5")
+                .parse::<u64>()
+                .expect("Could not parse SYNC_LAG_SECONDS to u64!"),
+            page_size: get_env_or_default("SYNC_PAGE_SIZE", "500")
+                .parse::<u64>()
+                .expect("Could not parse SYNC_PAGE_SIZE to u64!"),
+        }
+    }
+}
+
 pub(crate) fn get_env_or_error(key: &str) -> String {
     env::var(key).unwrap_or_else(|_| panic!("'{}' env variable must be set!", key))
 }
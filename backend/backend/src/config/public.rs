@@ -1,13 +1,15 @@
 use serde::Serialize;
 use utoipa::ToSchema;
 
-use crate::config::Config;
+use crate::config::{Config, RegistrationMode};
 
 #[derive(Debug, Serialize, ToSchema)]
 pub(crate) struct PublicConfig {
     pub(crate) session_lifetime_hours: u64,
     pub(crate) rate_limiter_limit: u64,
     pub(crate) rate_limiter_duration_seconds: u64,
+    pub(crate) oidc_enabled: bool,
+    pub(crate) registration_mode: RegistrationMode,
 }
 
 impl PublicConfig {
@@ -25,6 +27,8 @@ impl From<&Config> for PublicConfig {
             session_lifetime_hours: config.session.lifetime_hours,
             rate_limiter_limit: config.rate_limiter.limit,
             rate_limiter_duration_seconds: config.rate_limiter.duration_seconds,
+            oidc_enabled: config.oidc.issuer_url.is_some(),
+            registration_mode: config.registration.mode,
         }
     }
 }
@@ -1,14 +1,34 @@
-use sea_orm::{ActiveModelBehavior, DeleteMany, EntityTrait, IntoActiveModel, PaginatorTrait, Select};
+use sea_orm::{
+    ActiveModelBehavior, ActiveModelTrait, DbErr, DeleteMany, EntityTrait, FromQueryResult, IntoActiveModel,
+    PaginatorTrait, Select, SelectModel, Selector, UpdateMany, UpdateOne,
+};
 
 use crate::api::error::api::ApiError;
 use crate::api::pagination::PageSizeParam;
 use crate::database::connection::get_database_connection;
+use crate::database::transaction::current_transaction;
+
+/// Runs the statement on the current transaction if there is one, on the connection pool otherwise.
+macro_rules! with_connection {
+    (|$connection:ident| $statement:expr) => {
+        match current_transaction() {
+            Some(ambient) => {
+                let $connection = &ambient.transaction;
+                $statement
+            }
+            None => {
+                let $connection = get_database_connection();
+                $statement
+            }
+        }
+    };
+}
 
 pub(crate) async fn find_one<T>(select_stm: Select<T>) -> Result<Option<T::Model>, ApiError>
 where
     T: EntityTrait,
 {
-    let model = select_stm.one(get_database_connection()).await.map_err(ApiError::from)?;
+    let model = with_connection!(|connection| select_stm.one(connection).await).map_err(ApiError::from)?;
 
     Ok(model)
 }
@@ -18,7 +38,12 @@ pub(crate) async fn find_one_or_error<T: EntityTrait>(select_stm: Select<T>) ->
 }
 
 pub(crate) async fn find_all<T: EntityTrait>(select_stm: Select<T>) -> Result<Vec<T::Model>, ApiError> {
-    select_stm.all(get_database_connection()).await.map_err(ApiError::from)
+    with_connection!(|connection| select_stm.all(connection).await).map_err(ApiError::from)
+}
+
+/// Like [find_all], for statements that select into a model other than the entity's own.
+pub(crate) async fn find_all_as<M: FromQueryResult>(select_stm: Selector<SelectModel<M>>) -> Result<Vec<M>, ApiError> {
+    with_connection!(|connection| select_stm.all(connection).await).map_err(ApiError::from)
 }
 
 pub(crate) async fn find_all_paginated<T: EntityTrait>(
@@ -28,17 +53,17 @@ pub(crate) async fn find_all_paginated<T: EntityTrait>(
 where
     <T as EntityTrait>::Model: Sync,
 {
-    PaginatorTrait::paginate(select_stm, get_database_connection(), page_size.limit)
+    with_connection!(|connection| PaginatorTrait::paginate(select_stm, connection, page_size.limit)
         .fetch_page(page_size.page - 1)
-        .await
-        .map_err(ApiError::from)
+        .await)
+    .map_err(ApiError::from)
 }
 
 pub(crate) async fn count<T: EntityTrait>(select_stm: Select<T>) -> Result<u64, ApiError>
 where
     <T as EntityTrait>::Model: Sync,
 {
-    PaginatorTrait::count(select_stm, get_database_connection()).await.map_err(ApiError::from)
+    with_connection!(|connection| PaginatorTrait::count(select_stm, connection).await).map_err(ApiError::from)
 }
 
 pub(crate) async fn insert<T>(active_model: T) -> Result<<T::Entity as EntityTrait>::Model, ApiError>
@@ -46,7 +71,7 @@ where
     <T::Entity as EntityTrait>::Model: IntoActiveModel<T>,
     T: ActiveModelBehavior + Send,
 {
-    active_model.insert(get_database_connection()).await.map_err(ApiError::from)
+    with_connection!(|connection| active_model.insert(connection).await).map_err(ApiError::from)
 }
 
 pub(crate) async fn update<T>(active_model: T) -> Result<<T::Entity as EntityTrait>::Model, ApiError>
@@ -54,9 +79,35 @@ where
     <T::Entity as EntityTrait>::Model: IntoActiveModel<T>,
     T: ActiveModelBehavior + Send,
 {
-    active_model.update(get_database_connection()).await.map_err(ApiError::from)
+    with_connection!(|connection| active_model.update(connection).await).map_err(ApiError::from)
+}
+
+/// Fails with `PreconditionFailed` if no row matched the update's filter, e.g. because the version changed.
+pub(crate) async fn update_one<T>(update: UpdateOne<T>) -> Result<<T::Entity as EntityTrait>::Model, ApiError>
+where
+    <T::Entity as EntityTrait>::Model: IntoActiveModel<T>,
+    T: ActiveModelTrait + Send,
+{
+    with_connection!(|connection| update.exec(connection).await).map_err(|e| match e {
+        DbErr::RecordNotUpdated => ApiError::PreconditionFailed(),
+        e => ApiError::from(e),
+    })
+}
+
+/// Returns the number of updated rows.
+pub(crate) async fn update_many<T: EntityTrait>(update: UpdateMany<T>) -> Result<u64, ApiError> {
+    with_connection!(|connection| update.exec(connection).await)
+        .map(|result| result.rows_affected)
+        .map_err(ApiError::from)
 }
 
 pub(crate) async fn delete<T: EntityTrait>(delete: DeleteMany<T>) -> Result<(), ApiError> {
-    delete.exec(get_database_connection()).await.map(|_| ()).map_err(ApiError::from)
+    with_connection!(|connection| delete.exec(connection).await).map(|_| ()).map_err(ApiError::from)
+}
+
+/// Returns the number of deleted rows.
+pub(crate) async fn delete_many<T: EntityTrait>(delete: DeleteMany<T>) -> Result<u64, ApiError> {
+    with_connection!(|connection| delete.exec(connection).await)
+        .map(|result| result.rows_affected)
+        .map_err(ApiError::from)
 }
@@ -1,3 +1,4 @@
 pub(crate) mod connection;
 pub(crate) mod entity;
 pub(crate) mod redis;
+pub(crate) mod transaction;
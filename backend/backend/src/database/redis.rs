@@ -12,6 +12,15 @@ pub(crate) async fn set_ex(key: String, value: String, expiration_timestamp: u64
     Ok(conn.set_ex(key, value, expiration_timestamp).await?)
 }
 
+/// Sets the key only if it does not exist yet, returns whether it was set.
+pub(crate) async fn set_nx_ex(key: String, value: String, seconds: u64) -> Result<bool, ApiError> {
+    let mut conn = get_redis_connection().await?;
+    let result: Option<String> =
+        redis::cmd("SET").arg(key).arg(value).arg("NX").arg("EX").arg(seconds).query_async(&mut conn).await?;
+
+    Ok(result.is_some())
+}
+
 pub(crate) async fn zadd(key: String, member: String, score: f64) -> Result<(), ApiError> {
     let mut conn = get_redis_connection().await?;
     Ok(conn.zadd(key, member, score).await?)
@@ -22,6 +31,24 @@ pub(crate) async fn get<T: FromRedisValue>(key: String) -> Result<T, ApiError> {
     Ok(conn.get(key).await?)
 }
 
+pub(crate) async fn incr(key: String) -> Result<u64, ApiError> {
+    let mut conn = get_redis_connection().await?;
+    Ok(conn.incr(key, 1).await?)
+}
+
+pub(crate) async fn expire(key: String, seconds: i64) -> Result<(), ApiError> {
+    let mut conn = get_redis_connection().await?;
+    Ok(conn.expire(key, seconds).await?)
+}
+
+/// Remaining seconds until the key expires, `None` if it does not exist or never expires.
+pub(crate) async fn ttl(key: String) -> Result<Option<u64>, ApiError> {
+    let mut conn = get_redis_connection().await?;
+    let ttl: i64 = conn.ttl(key).await?;
+
+    Ok(u64::try_from(ttl).ok())
+}
+
 pub(crate) async fn del(key: String) -> Result<(), ApiError> {
     let mut conn = get_redis_connection().await?;
     Ok(conn.del(key).await?)
@@ -0,0 +1,72 @@
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use futures_util::future::BoxFuture;
+use sea_orm::{DatabaseTransaction, TransactionTrait};
+use tokio::spawn;
+
+use crate::api::error::api::ApiError;
+use crate::database::connection::get_database_connection;
+
+tokio::task_local! {
+    static TRANSACTION: Arc<AmbientTransaction>;
+}
+
+/// The transaction the database helpers use while a future runs inside [in_transaction].
+pub(crate) struct AmbientTransaction {
+    pub(crate) transaction: DatabaseTransaction,
+    after_commit: Mutex<Vec<BoxFuture<'static, ()>>>,
+}
+
+/// Runs the future in a database transaction that every database helper called from it takes part in. It is
+/// committed if the future succeeds and rolled back otherwise. Nested calls join the outer transaction.
+/// Tasks spawned from the future do not see the transaction.
+pub(crate) async fn in_transaction<T, F>(future: F) -> Result<T, ApiError>
+where
+    F: Future<Output = Result<T, ApiError>>,
+{
+    if current_transaction().is_some() {
+        return future.await;
+    }
+
+    let ambient = Arc::new(AmbientTransaction {
+        transaction: get_database_connection().begin().await?,
+        after_commit: Mutex::new(Vec::new()),
+    });
+    let result = TRANSACTION.scope(ambient.clone(), future).await;
+    let ambient = Arc::into_inner(ambient).expect("The database transaction is still in use!");
+
+    match result {
+        Ok(value) => {
+            ambient.transaction.commit().await?;
+            for task in ambient.after_commit.into_inner().expect("After commit lock is poisoned!") {
+                spawn(task);
+            }
+
+            Ok(value)
+        }
+        Err(e) => {
+            ambient.transaction.rollback().await?;
+
+            Err(e)
+        }
+    }
+}
+
+pub(crate) fn current_transaction() -> Option<Arc<AmbientTransaction>> {
+    TRANSACTION.try_with(Arc::clone).ok()
+}
+
+/// Spawns the task once the current transaction is committed, so it can see everything written in it. Without a
+/// transaction it is spawned right away, and if the transaction is rolled back it is dropped.
+pub(crate) fn after_commit<F>(task: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    match current_transaction() {
+        Some(ambient) => ambient.after_commit.lock().expect("After commit lock is poisoned!").push(Box::pin(task)),
+        None => {
+            spawn(task);
+        }
+    }
+}
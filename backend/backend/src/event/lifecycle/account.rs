@@ -0,0 +1,24 @@
+use crate::lifecycle_event;
+use crate::wrapper::entity::account::Account;
+
+lifecycle_event! {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub(crate) struct AccountCreation {
+        pub(crate) account: Account,
+    }
+}
+
+lifecycle_event! {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub(crate) struct AccountDeletion {
+        pub(crate) account: Account,
+    }
+}
+
+lifecycle_event! {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub(crate) struct AccountUpdate {
+        pub(crate) old_account: Account,
+        pub(crate) new_account: Account,
+    }
+}
@@ -0,0 +1,24 @@
+use crate::lifecycle_event;
+use crate::wrapper::entity::budget::Budget;
+
+lifecycle_event! {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub(crate) struct BudgetCreation {
+        pub(crate) budget: Budget,
+    }
+}
+
+lifecycle_event! {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub(crate) struct BudgetDeletion {
+        pub(crate) budget: Budget,
+    }
+}
+
+lifecycle_event! {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub(crate) struct BudgetUpdate {
+        pub(crate) old_budget: Budget,
+        pub(crate) new_budget: Budget,
+    }
+}
@@ -1 +1,4 @@
+pub(crate) mod account;
+pub(crate) mod budget;
+pub(crate) mod session;
 pub(crate) mod transaction;
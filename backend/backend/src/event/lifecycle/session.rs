@@ -0,0 +1,21 @@
+use utility::snowflake::entity::Snowflake;
+
+use crate::lifecycle_event;
+
+// only ids are carried, events are persisted and the session token must not end up in the outbox
+
+lifecycle_event! {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub(crate) struct SessionCreated {
+        pub(crate) session_id: Snowflake,
+        pub(crate) user_id: Snowflake,
+    }
+}
+
+lifecycle_event! {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub(crate) struct SessionDeleted {
+        pub(crate) session_id: Snowflake,
+        pub(crate) user_id: Snowflake,
+    }
+}
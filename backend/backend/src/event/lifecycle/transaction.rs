@@ -1,4 +1,5 @@
 use crate::lifecycle_event;
+use crate::wrapper::entity::transaction::recurring::RecurringTransaction;
 use crate::wrapper::entity::transaction::Transaction;
 
 lifecycle_event! {
@@ -22,3 +23,11 @@ lifecycle_event! {
         pub(crate) new_transaction: Transaction,
     }
 }
+
+lifecycle_event! {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub(crate) struct RecurringTransactionExecuted {
+        pub(crate) recurring_transaction: RecurringTransaction,
+        pub(crate) transaction: Transaction,
+    }
+}
@@ -6,12 +6,8 @@ macro_rules! lifecycle_event {
             $(pub(crate) $field:ident: $type:ty,)*
         }
     ) => {
-        paste::paste! {
-            #[allow(non_upper_case_globals)]
-            static [< $name EventBus >]: std::sync::OnceLock<$crate::event::EventBus<$name>> = std::sync::OnceLock::new();
-        }
-
         $(#[$meta])*
+        #[derive(serde::Serialize, serde::Deserialize)]
         pub(crate) struct $name {
             $(pub(crate) $field: $type,)*
         }
@@ -25,10 +21,8 @@ macro_rules! lifecycle_event {
         }
 
         impl $crate::event::GenericEvent for $name {
-            paste::paste! {
-                fn get_event_bus() -> &'static $crate::event::EventBus<Self> {
-                    [< $name EventBus >].get_or_init($crate::event::EventBus::new)
-                }
+            fn event_name() -> &'static str {
+                stringify!($name)
             }
         }
     };
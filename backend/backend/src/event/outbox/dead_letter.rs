@@ -0,0 +1,105 @@
+use sea_orm::{EntityName, EntityTrait, Set};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+use entity::utility::time::get_now;
+use entity::{event_delivery, event_outbox};
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::error::api::ApiError;
+use crate::api::pagination::PageSizeParam;
+use crate::database::entity::{count, delete, find_all_paginated, find_one_or_error, update};
+use crate::database::transaction::in_transaction;
+use crate::event::outbox::wake_dispatcher;
+use crate::wrapper::entity::audit::AuditLog;
+use crate::wrapper::entity::{TableName, WrapperEntity};
+
+/// An event a subscriber kept failing to handle. It is not retried until an admin requests it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct DeadLetter {
+    #[serde(rename = "id")]
+    pub(crate) snowflake: Snowflake,
+    pub(crate) event_id: Snowflake,
+    /// The name of the event, e.g. `TransactionCreation`.
+    pub(crate) event: String,
+    pub(crate) subscriber: String,
+    pub(crate) attempts: i32,
+    /// Why the last attempt failed.
+    pub(crate) error: Option<String>,
+    #[schema(value_type = Object)]
+    pub(crate) payload: Value,
+    #[serde(with = "time::serde::rfc3339")]
+    pub(crate) failed_at: OffsetDateTime,
+}
+
+impl DeadLetter {
+    /// Gives the subscriber all of its attempts again.
+    pub(crate) async fn retry(self) -> Result<(), ApiError> {
+        update(event_delivery::ActiveModel {
+            id: Set(self.snowflake.id),
+            attempts: Set(0),
+            next_attempt_at: Set(Some(get_now())),
+            failed_at: Set(None),
+            ..Default::default()
+        })
+        .await?;
+        wake_dispatcher();
+
+        Ok(())
+    }
+
+    /// Discards the event for this subscriber, it is never handled.
+    pub(crate) async fn delete(self) -> Result<(), ApiError> {
+        in_transaction(async move {
+            AuditLog::record_deletion(&self).await?;
+            delete(event_delivery::Entity::delete_by_id(self.snowflake.id)).await
+        })
+        .await
+    }
+
+    pub(crate) async fn find_by_id(id: Snowflake) -> Result<Self, ApiError> {
+        Self::from_delivery(find_one_or_error(event_delivery::Entity::find_failed_by_id(id)).await?).await
+    }
+
+    pub(crate) async fn find_all_paginated(page_size: &PageSizeParam) -> Result<Vec<Self>, ApiError> {
+        let mut dead_letters = Vec::new();
+        for delivery in find_all_paginated(event_delivery::Entity::find_all_failed(), page_size).await? {
+            dead_letters.push(Self::from_delivery(delivery).await?);
+        }
+
+        Ok(dead_letters)
+    }
+
+    pub(crate) async fn count_all() -> Result<u64, ApiError> {
+        count(event_delivery::Entity::find_all_failed()).await
+    }
+
+    async fn from_delivery(delivery: event_delivery::Model) -> Result<Self, ApiError> {
+        let event = find_one_or_error(event_outbox::Entity::find_by_id(delivery.event)).await?;
+
+        Ok(Self {
+            snowflake: Snowflake::from(delivery.id),
+            event_id: Snowflake::from(event.id),
+            event: event.event,
+            subscriber: delivery.subscriber,
+            attempts: delivery.attempts,
+            error: delivery.error,
+            payload: event.payload,
+            failed_at: delivery.failed_at.unwrap_or(event.created_at),
+        })
+    }
+}
+
+impl TableName for DeadLetter {
+    fn table_name() -> &'static str {
+        event_delivery::Entity.table_name()
+    }
+}
+
+impl WrapperEntity for DeadLetter {
+    fn get_id(&self) -> Snowflake {
+        self.snowflake
+    }
+}
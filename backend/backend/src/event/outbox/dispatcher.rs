@@ -0,0 +1,135 @@
+use sea_orm::{EntityTrait, Set};
+use time::Duration as TimeDuration;
+use tokio::select;
+use tokio::spawn;
+use tokio::time::{interval, sleep, Duration};
+use tracing::{error, warn};
+
+use entity::utility::time::get_now;
+use entity::{event_delivery, event_outbox};
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::error::api::ApiError;
+use crate::config::Config;
+use crate::database::entity::{delete, find_all, find_one_or_error, update, update_many};
+use crate::database::transaction::in_transaction;
+use crate::event::outbox::{find_subscriber, DISPATCHER_SIGNAL};
+use crate::scheduling::schedule_task_with_interval;
+
+const BATCH_SIZE: u64 = 100;
+const POLL_INTERVAL_SECONDS: u64 = 1;
+// a delivery whose dispatcher died while handling it is picked up again after this
+const LEASE_SECONDS: i64 = 300;
+const MAX_BACKOFF_EXPONENT: u32 = 16;
+const CLEAN_UP_INTERVAL_SECONDS: u64 = 60 * 60;
+
+pub(crate) fn start_dispatcher() {
+    spawn(async {
+        loop {
+            match dispatch_due().await {
+                // there may be more due deliveries than fit into one batch
+                Ok(handled) if handled as u64 == BATCH_SIZE => continue,
+                Ok(_) => {}
+                Err(e) => error!("Could not dispatch events: {}", e),
+            }
+
+            select! {
+                _ = DISPATCHER_SIGNAL.notified() => {}
+                _ = sleep(Duration::from_secs(POLL_INTERVAL_SECONDS)) => {}
+            }
+        }
+    });
+}
+
+async fn dispatch_due() -> Result<usize, ApiError> {
+    let deliveries = find_all(event_delivery::Entity::find_all_due(get_now(), BATCH_SIZE)).await?;
+    let handled = deliveries.len();
+    for delivery in deliveries {
+        let delivery_id = delivery.id;
+        if let Err(e) = dispatch(delivery).await {
+            error!("Could not dispatch event delivery {}: {}", delivery_id, e);
+        }
+    }
+
+    Ok(handled)
+}
+
+async fn dispatch(delivery: event_delivery::Model) -> Result<(), ApiError> {
+    let Some(next_attempt_at) = delivery.next_attempt_at else {
+        return Ok(());
+    };
+    let lease_until = get_now() + TimeDuration::seconds(LEASE_SECONDS);
+    let claimed =
+        update_many(event_delivery::Entity::claim(Snowflake::from(delivery.id), next_attempt_at, lease_until)).await?;
+    if claimed == 0 {
+        return Ok(());
+    }
+
+    let event = find_one_or_error(event_outbox::Entity::find_by_id(delivery.event)).await?;
+    let Some(subscriber) = find_subscriber(&event.event, &delivery.subscriber) else {
+        let error = format!("No subscriber {} is registered for {}.", delivery.subscriber, event.event);
+        return record_failure(delivery, error).await;
+    };
+
+    // the handler's changes and the delivery being marked as processed are committed together
+    let attempts = delivery.attempts + 1;
+    let result = in_transaction(async {
+        subscriber.handle(event.payload).await?;
+        update(event_delivery::ActiveModel {
+            id: Set(delivery.id),
+            attempts: Set(attempts),
+            error: Set(None),
+            next_attempt_at: Set(None),
+            processed_at: Set(Some(get_now())),
+            ..Default::default()
+        })
+        .await?;
+
+        Ok(())
+    })
+    .await;
+
+    match result {
+        Ok(()) => Ok(()),
+        Err(e) => record_failure(delivery, e.to_string()).await,
+    }
+}
+
+/// Schedules the next attempt with exponential backoff, or dead-letters the delivery once it ran out of attempts.
+async fn record_failure(delivery: event_delivery::Model, error: String) -> Result<(), ApiError> {
+    let config = &Config::get_config().event_outbox;
+    let attempts = delivery.attempts + 1;
+    let now = get_now();
+    let (next_attempt_at, failed_at) = if attempts < config.max_attempts as i32 {
+        let exponent = (attempts as u32 - 1).min(MAX_BACKOFF_EXPONENT);
+        (Some(now + TimeDuration::seconds((config.retry_base_seconds << exponent) as i64)), None)
+    } else {
+        warn!("Event delivery {} to {} was dead-lettered: {}", delivery.id, delivery.subscriber, error);
+        (None, Some(now))
+    };
+
+    update(event_delivery::ActiveModel {
+        id: Set(delivery.id),
+        attempts: Set(attempts),
+        error: Set(Some(error)),
+        next_attempt_at: Set(next_attempt_at),
+        failed_at: Set(failed_at),
+        ..Default::default()
+    })
+    .await?;
+
+    Ok(())
+}
+
+pub(crate) fn schedule_outbox_clean_up_task() {
+    let interval = interval(Duration::from_secs(CLEAN_UP_INTERVAL_SECONDS));
+    schedule_task_with_interval(interval, clean_up);
+}
+
+/// Deletes events all subscribers handled once they are older than the retention, dead letters are kept.
+async fn clean_up() {
+    let retention = TimeDuration::days(Config::get_config().event_outbox.retention_days);
+    if let Err(e) = delete(event_outbox::Entity::delete_all_handled_before(get_now() - retention)).await {
+        error!("Could not clean up the event outbox: {}", e);
+    }
+}
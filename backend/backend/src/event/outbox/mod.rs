@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, RwLock};
+
+use futures_util::future::BoxFuture;
+use sea_orm::Set;
+use serde::Serialize;
+use serde_json::Value;
+use time::Duration as TimeDuration;
+use tokio::sync::Notify;
+use tokio::time::Duration;
+
+use entity::utility::time::get_now;
+use entity::{event_delivery, event_outbox};
+
+use crate::api::error::api::ApiError;
+use crate::database::entity::insert;
+use crate::database::transaction::{after_commit, in_transaction};
+use crate::SNOWFLAKE_GENERATOR;
+
+pub(crate) mod dead_letter;
+pub(crate) mod dispatcher;
+
+type Handler = Box<dyn Fn(Value) -> BoxFuture<'static, Result<(), ApiError>> + Send + Sync>;
+type SubscriberMap = HashMap<&'static str, Vec<Arc<Subscriber>>>;
+
+pub(crate) struct Subscriber {
+    pub(crate) name: &'static str,
+    handler: Handler,
+}
+
+impl Subscriber {
+    pub(crate) async fn handle(&self, payload: Value) -> Result<(), ApiError> {
+        (self.handler)(payload).await
+    }
+}
+
+static SUBSCRIBERS: LazyLock<RwLock<SubscriberMap>> = LazyLock::new(Default::default);
+pub(crate) static DISPATCHER_SIGNAL: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+pub(crate) fn register_subscriber(event_name: &'static str, name: &'static str, handler: Handler) {
+    let mut subscribers = SUBSCRIBERS.write().expect("Subscriber lock is poisoned!");
+    let event_subscribers = subscribers.entry(event_name).or_default();
+    if event_subscribers.iter().any(|subscriber| subscriber.name == name) {
+        panic!("Subscriber {name} is already registered for {event_name}!");
+    }
+    event_subscribers.push(Arc::new(Subscriber {
+        name,
+        handler,
+    }));
+}
+
+pub(crate) fn find_subscriber(event_name: &str, name: &str) -> Option<Arc<Subscriber>> {
+    let subscribers = SUBSCRIBERS.read().expect("Subscriber lock is poisoned!");
+    subscribers.get(event_name)?.iter().find(|subscriber| subscriber.name == name).cloned()
+}
+
+fn subscriber_names(event_name: &str) -> Vec<&'static str> {
+    let subscribers = SUBSCRIBERS.read().expect("Subscriber lock is poisoned!");
+    subscribers
+        .get(event_name)
+        .map(|subscribers| subscribers.iter().map(|subscriber| subscriber.name).collect())
+        .unwrap_or_default()
+}
+
+/// Stores the event with one delivery per subscriber. The deliveries become due after the delay.
+pub(crate) async fn write_event<T: Serialize + Send>(
+    event_name: &'static str,
+    event: T,
+    delay: Option<Duration>,
+) -> Result<(), ApiError> {
+    let subscribers = subscriber_names(event_name);
+    if subscribers.is_empty() {
+        return Ok(());
+    }
+
+    let now = get_now();
+    let event_id = SNOWFLAKE_GENERATOR.next_id()?;
+    let next_attempt_at = now + TimeDuration::seconds(delay.map_or(0, |delay| delay.as_secs() as i64));
+    let payload = serde_json::to_value(&event)?;
+    in_transaction(async move {
+        insert(event_outbox::ActiveModel {
+            id: Set(event_id),
+            event: Set(event_name.to_string()),
+            payload: Set(payload),
+            created_at: Set(now),
+        })
+        .await?;
+        for subscriber in subscribers {
+            insert(event_delivery::ActiveModel {
+                id: Set(SNOWFLAKE_GENERATOR.next_id()?),
+                event: Set(event_id),
+                subscriber: Set(subscriber.to_string()),
+                attempts: Set(0),
+                error: Set(None),
+                next_attempt_at: Set(Some(next_attempt_at)),
+                processed_at: Set(None),
+                failed_at: Set(None),
+            })
+            .await?;
+        }
+
+        Ok(())
+    })
+    .await?;
+    after_commit(async { wake_dispatcher() });
+
+    Ok(())
+}
+
+/// Makes the dispatcher look for due deliveries right away instead of at its next poll.
+pub(crate) fn wake_dispatcher() {
+    DISPATCHER_SIGNAL.notify_one();
+}
@@ -0,0 +1,60 @@
+use futures_util::future::BoxFuture;
+use serde::Serialize;
+use tracing::info;
+
+use crate::api::error::api::ApiError;
+use crate::config::{Config, MailConfig};
+use crate::mail::transport::{FileTransport, LogTransport, SendmailTransport};
+use crate::MAIL_TRANSPORT;
+
+pub(crate) mod transport;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct Mail {
+    pub(crate) from: String,
+    pub(crate) to: String,
+    pub(crate) subject: String,
+    pub(crate) body: String,
+}
+
+impl Mail {
+    pub(crate) fn new(to: &str, subject: &str, body: String) -> Self {
+        Self {
+            from: Config::get_config().mail.from.clone(),
+            to: to.to_string(),
+            subject: subject.to_string(),
+            body,
+        }
+    }
+
+    /// Renders the mail as a plain text RFC 5322 message.
+    pub(crate) fn to_message(&self) -> String {
+        format!(
+            "From: {}\r\nTo: {}\r\nSubject: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}\r\n",
+            self.from, self.to, self.subject, self.body
+        )
+    }
+
+    pub(crate) async fn send(&self) -> Result<(), ApiError> {
+        get_mail_transport().send(self).await
+    }
+}
+
+/// Delivers mails, swapped through `MAIL_TRANSPORT` so tests can read mails from disk instead of a mailbox.
+pub(crate) trait MailTransport: Send + Sync {
+    fn send<'a>(&'a self, mail: &'a Mail) -> BoxFuture<'a, Result<(), ApiError>>;
+}
+
+pub(crate) fn get_mail_transport<'a>() -> &'a dyn MailTransport {
+    MAIL_TRANSPORT.get().expect("Could not get mail transport!").as_ref()
+}
+
+pub(crate) fn create_mail_transport(config: &MailConfig) -> Box<dyn MailTransport> {
+    info!("\t[*] Using '{}' mail transport...", config.transport);
+    match config.transport.as_str() {
+        "sendmail" => Box::new(SendmailTransport::new(config.sendmail_command.clone())),
+        "file" => Box::new(FileTransport::new(config.file_directory.clone().into())),
+        "log" => Box::new(LogTransport),
+        transport => panic!("Unknown mail transport '{}'! Use sendmail, file or log.", transport),
+    }
+}
@@ -0,0 +1,97 @@
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use futures_util::future::BoxFuture;
+use tokio::task::spawn_blocking;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::api::error::api::ApiError;
+use crate::mail::{Mail, MailTransport};
+
+/// Pipes the message into a sendmail compatible binary, which reads the recipients from the headers.
+pub(crate) struct SendmailTransport {
+    command: String,
+}
+
+impl SendmailTransport {
+    pub(crate) fn new(command: String) -> Self {
+        Self {
+            command,
+        }
+    }
+
+    fn deliver(command: &str, message: &str) -> Result<(), String> {
+        let mut child = Command::new(command)
+            .args(["-t", "-i"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+            .map_err(|e| e.to_string())?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(message.as_bytes()).map_err(|e| e.to_string())?;
+        }
+
+        let status = child.wait().map_err(|e| e.to_string())?;
+        match status.success() {
+            true => Ok(()),
+            false => Err(format!("{} exited with {}", command, status)),
+        }
+    }
+}
+
+impl MailTransport for SendmailTransport {
+    fn send<'a>(&'a self, mail: &'a Mail) -> BoxFuture<'a, Result<(), ApiError>> {
+        let command = self.command.clone();
+        let message = mail.to_message();
+        Box::pin(async move {
+            spawn_blocking(move || Self::deliver(&command, &message))
+                .await
+                .map_err(|e| ApiError::MailDeliveryFailed(&e.to_string()))?
+                .map_err(|e| ApiError::MailDeliveryFailed(&e))
+        })
+    }
+}
+
+/// Writes every mail as an `.eml` file into a directory, used as a stand-in for a real mail server.
+pub(crate) struct FileTransport {
+    directory: PathBuf,
+}
+
+impl FileTransport {
+    pub(crate) fn new(directory: PathBuf) -> Self {
+        Self {
+            directory,
+        }
+    }
+}
+
+impl MailTransport for FileTransport {
+    fn send<'a>(&'a self, mail: &'a Mail) -> BoxFuture<'a, Result<(), ApiError>> {
+        let directory = self.directory.clone();
+        let message = mail.to_message();
+        Box::pin(async move {
+            spawn_blocking(move || {
+                fs::create_dir_all(&directory)?;
+                fs::write(directory.join(format!("{}.eml", Uuid::new_v4())), message)
+            })
+            .await
+            .map_err(|e| ApiError::MailDeliveryFailed(&e.to_string()))?
+            .map_err(|e| ApiError::MailDeliveryFailed(&e.to_string()))
+        })
+    }
+}
+
+/// Only logs the mail, the default so a fresh instance works without a mail server.
+pub(crate) struct LogTransport;
+
+impl MailTransport for LogTransport {
+    fn send<'a>(&'a self, mail: &'a Mail) -> BoxFuture<'a, Result<(), ApiError>> {
+        Box::pin(async move {
+            info!("Mail to {} with subject '{}':\n{}", mail.to, mail.subject, mail.body);
+            Ok(())
+        })
+    }
+}
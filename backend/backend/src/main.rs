@@ -5,7 +5,8 @@ use std::time::Duration;
 
 use actix_cors::Cors;
 use actix_limitation::{Limiter, RateLimiter};
-use actix_web::middleware::{Compress, DefaultHeaders, NormalizePath, TrailingSlash};
+use actix_web::dev::Service;
+use actix_web::middleware::{from_fn, Compress, DefaultHeaders, NormalizePath, TrailingSlash};
 use actix_web::web::Data;
 use actix_web::{
     web::{self},
@@ -31,26 +32,46 @@ use migration::MigratorTrait;
 use utility::snowflake::generator::SnowflakeGenerator;
 
 use crate::api::error::api::ApiError;
+use crate::api::graphql::controller::graphql_controller;
+use crate::api::idempotency::{idempotency, IDEMPOTENCY_KEY_HEADER, IDEMPOTENCY_REPLAYED_HEADER};
 use crate::api::routes::account::controller::account_controller;
+use crate::api::routes::admin::controller::admin_controller;
+use crate::api::routes::audit::controller::audit_controller;
+use crate::api::routes::batch::controller::batch_controller;
 use crate::api::routes::budget::controller::budget_controller;
 use crate::api::routes::currency::controller::currency_controller;
+use crate::api::routes::expense::controller::expense_controller;
+use crate::api::routes::group::controller::group_controller;
 use crate::api::routes::openapi::controller::configure_openapi;
+use crate::api::routes::realtime::controller::realtime_controller;
 use crate::api::routes::session::controller::session_controller;
+use crate::api::routes::sync::controller::sync_controller;
+use crate::api::routes::token::controller::api_token_controller;
 use crate::api::routes::transaction::controller::transaction_controller;
+use crate::api::routes::trash::controller::trash_controller;
 use crate::api::routes::user::controller::user_controller;
+use crate::api::routes::webhook::controller::webhook_controller;
 use crate::api::status::controller::status_controller;
 use crate::config::{logger, Config};
 use crate::database::connection::{create_redis_client, establish_database_connection, get_database_connection};
 use crate::database::redis::clear_redis;
+use crate::event::outbox::dispatcher::{schedule_outbox_clean_up_task, start_dispatcher};
+use crate::mail::{create_mail_transport, MailTransport};
+use crate::util::auth::extract_bearer_token;
 use crate::util::panic::install_panic_hook;
+use crate::wrapper::entity::audit::context::Actor;
 use crate::wrapper::entity::session::Session;
 use crate::wrapper::entity::start_wrapper;
+use crate::wrapper::entity::trash::purge::schedule_purge_task;
+use crate::wrapper::entity::webhook::retry::schedule_retry_task;
 use crate::wrapper::permission::cleanup::schedule_clean_up_task;
 
 pub(crate) mod api;
 pub(crate) mod config;
 pub(crate) mod database;
 pub(crate) mod event;
+pub(crate) mod mail;
+pub(crate) mod oidc;
 pub(crate) mod scheduling;
 pub(crate) mod util;
 pub(crate) mod wrapper;
@@ -62,6 +83,7 @@ static GLOBAL: MiMalloc = MiMalloc;
 pub(crate) static DB: OnceLock<DatabaseConnection> = OnceLock::new();
 pub(crate) static REDIS: OnceLock<Client> = OnceLock::new();
 pub(crate) static CONFIG: OnceLock<Config> = OnceLock::new();
+pub(crate) static MAIL_TRANSPORT: OnceLock<Box<dyn MailTransport>> = OnceLock::new();
 pub(crate) static SNOWFLAKE_GENERATOR: LazyLock<SnowflakeGenerator> =
     LazyLock::new(|| SnowflakeGenerator::new_from_env().expect("Could not create snowflake generator!"));
 
@@ -82,7 +104,18 @@ pub(crate) static SNOWFLAKE_GENERATOR: LazyLock<SnowflakeGenerator> =
         (name = "Transaction", description = "Endpoints for transaction management."),
         (name = "Transaction-Template", description = "Endpoints for transaction template management."),
         (name = "Recurring-Transaction", description = "Endpoints for recurring transaction management."),
-        (name = "Budget", description = "Endpoints for budget management.")
+        (name = "Budget", description = "Endpoints for budget management."),
+        (name = "Group", description = "Endpoints for group and household management."),
+        (name = "Expense", description = "Endpoints for shared expenses and debts."),
+        (name = "Audit", description = "Endpoints for the audit log of all entity changes."),
+        (name = "Trash", description = "Endpoints for restoring deleted entities."),
+        (name = "Batch", description = "Endpoints for running many changes in one request."),
+        (name = "Sync", description = "Endpoints for syncing offline clients."),
+        (name = "Api-Token", description = "Endpoints for managing personal API tokens."),
+        (name = "Webhook", description = "Endpoints for webhooks that receive lifecycle events."),
+        (name = "Realtime", description = "Endpoints that push lifecycle events to connected clients."),
+        (name = "GraphQL", description = "Endpoint for querying and changing the entities with GraphQL."),
+        (name = "Admin", description = "Endpoints for administrating the instance, only usable by admins.")
     ),
     modifiers(& BearerTokenAddon)
 )]
@@ -124,15 +157,31 @@ async fn main() -> Result<()> {
     info!("[*] Migrating database...");
     Migrator::up(get_database_connection(), None).await.expect("Could not migrate database!");
 
+    info!("[*] Setting up mail transport...");
+    assert!(
+        MAIL_TRANSPORT.set(create_mail_transport(&Config::get_config().mail)).is_ok(),
+        "Could not set mail transport!"
+    );
+
     info!("[*] Loading sessions...");
     Session::init().await.expect("Could not load sessions!");
 
     info!("[*] Starting up event system...");
     event::init();
+    start_dispatcher();
+
+    info!("[*] Scheduling event outbox clean up task...");
+    schedule_outbox_clean_up_task();
 
     info!("[*] Scheduling clean up task...");
     schedule_clean_up_task();
 
+    info!("[*] Scheduling trash purge task...");
+    schedule_purge_task();
+
+    info!("[*] Scheduling webhook retry task...");
+    schedule_retry_task();
+
     info!("\t[*] Initializing rate limiter...");
     let limiter = Data::new(build_rate_limiter());
 
@@ -173,6 +222,7 @@ fn configure_api(cfg: &mut web::ServiceConfig) {
         web::scope("/api")
             .wrap(NormalizePath::new(TrailingSlash::Trim))
             .configure(configure_api_v1)
+            .configure(graphql_controller)
             .configure(status_controller),
     );
 }
@@ -180,12 +230,24 @@ fn configure_api(cfg: &mut web::ServiceConfig) {
 fn configure_api_v1(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/v1")
+            .wrap(from_fn(idempotency))
+            .wrap_fn(|req, srv| Actor::scope(extract_bearer_token(req.request()).ok(), srv.call(req)))
             .configure(user_controller)
             .configure(account_controller)
             .configure(currency_controller)
             .configure(transaction_controller)
             .configure(budget_controller)
-            .configure(session_controller),
+            .configure(group_controller)
+            .configure(expense_controller)
+            .configure(audit_controller)
+            .configure(trash_controller)
+            .configure(batch_controller)
+            .configure(sync_controller)
+            .configure(session_controller)
+            .configure(api_token_controller)
+            .configure(webhook_controller)
+            .configure(realtime_controller)
+            .configure(admin_controller),
     );
 }
 
@@ -193,7 +255,15 @@ fn build_cors() -> Cors {
     let cors_config = &Config::get_config().cors;
     let mut cors = Cors::default()
         .allowed_methods(vec!["GET", "POST", "PATCH", "DELETE"])
-        .allowed_headers(vec!["Authorization", "Content-Type", "Accept"])
+        .allowed_headers(vec![
+            "Authorization",
+            "Content-Type",
+            "Accept",
+            "If-Match",
+            "If-None-Match",
+            IDEMPOTENCY_KEY_HEADER,
+        ])
+        .expose_headers(vec!["ETag", IDEMPOTENCY_REPLAYED_HEADER])
         .max_age(3600);
 
     if cors_config.allow_any_origin {
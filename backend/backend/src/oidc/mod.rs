@@ -0,0 +1,144 @@
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use reqwest::{Client, Url};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::OnceCell;
+
+use entity::utility::time::get_now;
+use utility::jwt::{decode, JwkSet, Validation};
+
+use crate::api::error::api::ApiError;
+use crate::config::{Config, OidcConfig};
+
+// the clocks of the provider and ours are never perfectly in sync
+const CLOCK_LEEWAY_SECONDS: i64 = 60;
+const REQUEST_TIMEOUT_SECONDS: u64 = 10;
+
+static HTTP_CLIENT: LazyLock<Client> = LazyLock::new(|| {
+    Client::builder()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECONDS))
+        .build()
+        .expect("Could not build http client!")
+});
+static DISCOVERY: OnceCell<Discovery> = OnceCell::const_new();
+
+/// The parts of the provider metadata (OpenID Connect Discovery 1.0) we need.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Discovery {
+    pub(crate) issuer: String,
+    pub(crate) authorization_endpoint: String,
+    pub(crate) token_endpoint: String,
+    pub(crate) jwks_uri: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct IdTokenClaims {
+    pub(crate) iss: String,
+    pub(crate) sub: String,
+    pub(crate) email: Option<String>,
+    #[serde(default)]
+    pub(crate) email_verified: bool,
+    pub(crate) preferred_username: Option<String>,
+    pub(crate) name: Option<String>,
+}
+
+/// The identity provider configured in `OidcConfig`, talking to it through the authorization code flow with PKCE.
+pub(crate) struct OidcProvider;
+
+impl OidcProvider {
+    pub(crate) fn config<'a>() -> Result<&'a OidcConfig, ApiError> {
+        let config = &Config::get_config().oidc;
+        match config.issuer_url {
+            Some(_) => Ok(config),
+            None => Err(ApiError::OidcDisabled()),
+        }
+    }
+
+    pub(crate) fn code_challenge(code_verifier: &str) -> String {
+        URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+    }
+
+    pub(crate) async fn authorization_url(state: &str, nonce: &str, code_verifier: &str) -> Result<String, ApiError> {
+        let config = Self::config()?;
+        let discovery = Self::discovery().await?;
+        let code_challenge = Self::code_challenge(code_verifier);
+        let url = Url::parse_with_params(
+            &discovery.authorization_endpoint,
+            [
+                ("response_type", "code"),
+                ("client_id", config.client_id.as_str()),
+                ("redirect_uri", config.redirect_url.as_str()),
+                ("scope", config.scopes.as_str()),
+                ("state", state),
+                ("nonce", nonce),
+                ("code_challenge", code_challenge.as_str()),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .map_err(|e| ApiError::OidcProviderError(&e.to_string()))?;
+
+        Ok(url.to_string())
+    }
+
+    /// Redeems the authorization code and returns the claims of the validated ID token.
+    pub(crate) async fn exchange_code(code: &str, code_verifier: &str, nonce: &str) -> Result<IdTokenClaims, ApiError> {
+        let config = Self::config()?;
+        let discovery = Self::discovery().await?;
+        let response: TokenResponse = HTTP_CLIENT
+            .post(&discovery.token_endpoint)
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", config.redirect_url.as_str()),
+                ("client_id", config.client_id.as_str()),
+                ("client_secret", config.client_secret.as_str()),
+                ("code_verifier", code_verifier),
+            ])
+            .send()
+            .await?
+            .error_for_status()
+            .map_err(|_| ApiError::InvalidOidcLogin("The identity provider rejected the authorization code!"))?
+            .json()
+            .await?;
+
+        // keys are rotated by the provider, so they are fetched for every login instead of being cached
+        let keys: JwkSet = HTTP_CLIENT.get(&discovery.jwks_uri).send().await?.error_for_status()?.json().await?;
+        let validation = Validation {
+            issuer: discovery.issuer.clone(),
+            audience: config.client_id.clone(),
+            nonce: Some(nonce.to_string()),
+            leeway_seconds: CLOCK_LEEWAY_SECONDS,
+        };
+
+        Ok(decode(&response.id_token, &keys, &validation, get_now())?)
+    }
+
+    async fn discovery<'a>() -> Result<&'a Discovery, ApiError> {
+        DISCOVERY.get_or_try_init(Self::fetch_discovery).await
+    }
+
+    async fn fetch_discovery() -> Result<Discovery, ApiError> {
+        let issuer_url = Self::config()?.issuer_url.clone().unwrap_or_default();
+        let discovery: Discovery = HTTP_CLIENT
+            .get(format!("{}/.well-known/openid-configuration", issuer_url))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if discovery.issuer.trim_end_matches('/') != issuer_url {
+            return Err(ApiError::OidcProviderError("The discovered issuer does not match OIDC_ISSUER_URL"));
+        }
+
+        Ok(discovery)
+    }
+}
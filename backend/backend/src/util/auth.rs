@@ -1,6 +1,10 @@
 use actix_web::HttpRequest;
 
+use utility::snowflake::entity::Snowflake;
+
 use crate::api::error::api::ApiError;
+use crate::wrapper::entity::api_token::ApiToken;
+use crate::wrapper::entity::session::Session;
 
 pub(crate) fn extract_bearer_token(req: &HttpRequest) -> Result<String, ApiError> {
     req.headers()
@@ -16,3 +20,17 @@ pub(crate) fn extract_bearer_token(req: &HttpRequest) -> Result<String, ApiError
         })
         .ok_or(ApiError::NoTOkenProvided())
 }
+
+/// Resolves the user behind the bearer token. Sessions may use every route, API tokens only the routes their
+/// scopes cover.
+pub(crate) async fn authenticate(req: &HttpRequest) -> Result<Snowflake, ApiError> {
+    let token = extract_bearer_token(req)?;
+    if ApiToken::is_api_token(&token) {
+        return ApiToken::authenticate(&token, req.method(), req.path()).await;
+    }
+
+    let user_id = Session::find_user_id(token.clone()).await?;
+    Session::touch(&token).await?;
+
+    Ok(user_id)
+}
@@ -0,0 +1,33 @@
+use std::future::{ready, Ready};
+
+use actix_web::dev::Payload;
+use actix_web::http::header::USER_AGENT;
+use actix_web::{FromRequest, HttpRequest};
+
+use crate::api::error::api::ApiError;
+
+/// Who is on the other end of a request, as far as we can tell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ClientInfo {
+    pub(crate) ip: String,
+    pub(crate) user_agent: Option<String>,
+}
+
+impl ClientInfo {
+    // keyed like the rate limiter, so both agree on who a client is
+    pub(crate) fn extract(req: &HttpRequest) -> Self {
+        Self {
+            ip: req.peer_addr().map(|addr| addr.ip().to_string()).unwrap_or_else(|| "unknown".to_string()),
+            user_agent: req.headers().get(USER_AGENT).and_then(|header| header.to_str().ok()).map(str::to_string),
+        }
+    }
+}
+
+impl FromRequest for ClientInfo {
+    type Error = ApiError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(Ok(Self::extract(req)))
+    }
+}
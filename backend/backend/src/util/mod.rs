@@ -1,4 +1,5 @@
 pub(crate) mod auth;
+pub(crate) mod client;
 pub(crate) mod cron;
 pub(crate) mod panic;
 pub(crate) mod validation;
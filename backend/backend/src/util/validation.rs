@@ -57,6 +57,16 @@ pub(crate) fn validate_unique_username(username: &str) -> Result<(), ValidationE
     })
 }
 
+pub(crate) fn validate_unique_email(email: &str) -> Result<(), ValidationError> {
+    Handle::current().block_on(async {
+        match User::find_by_email(email).one(get_database_connection()).await {
+            Ok(Some(_)) => ValidationCode::EMAIL_NOT_UNIQUE.into(),
+            Err(_) => ValidationCode::INTERNAL_SERVER_ERROR.into(),
+            _ => Ok(()),
+        }
+    })
+}
+
 pub(crate) fn validate_iban(iban: &str) -> Result<(), ValidationError> {
     match iban.parse::<Iban>() {
         Ok(_) => Ok(()),
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+use validator::Validate;
+
+use utility::snowflake::entity::Snowflake;
+
+use crate::wrapper::entity::account::Account;
+use crate::wrapper::entity::budget::Budget;
+use crate::wrapper::entity::transaction::template::TransactionTemplate;
+use crate::wrapper::entity::transaction::Transaction;
+use crate::wrapper::entity::TableName;
+
+pub(crate) const MAX_OPERATIONS: u64 = 100;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum BatchMode {
+    /// All operations are committed together, or none of them if one fails.
+    #[default]
+    Atomic,
+    /// Every operation is committed on its own, failed operations are skipped.
+    BestEffort,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum BatchAction {
+    Create,
+    Update,
+    Delete,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum BatchEntity {
+    Account,
+    Budget,
+    Transaction,
+    TransactionTemplate,
+}
+
+impl BatchEntity {
+    pub(crate) fn table_name(&self) -> &'static str {
+        match self {
+            Self::Account => Account::table_name(),
+            Self::Budget => Budget::table_name(),
+            Self::Transaction => Transaction::table_name(),
+            Self::TransactionTemplate => TransactionTemplate::table_name(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate, ToSchema)]
+pub(crate) struct BatchDTO {
+    #[serde(default)]
+    pub(crate) mode: BatchMode,
+    #[validate(length(min = 1, max = MAX_OPERATIONS))]
+    pub(crate) operations: Vec<BatchOperation>,
+}
+
+/// One operation of a batch, `data` is the body the single-item route expects.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct BatchOperation {
+    pub(crate) action: BatchAction,
+    pub(crate) entity: BatchEntity,
+    /// The entity to update or delete.
+    pub(crate) id: Option<Snowflake>,
+    /// The value of the `If-Match` header of the single-item route.
+    pub(crate) if_match: Option<String>,
+    #[schema(value_type = Option<Object>)]
+    pub(crate) data: Option<Value>,
+}
+
+/// The outcome of one operation, `body` is what the single-item route would have responded with.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub(crate) struct BatchResult {
+    pub(crate) status: u16,
+    #[schema(value_type = Option<Object>)]
+    pub(crate) body: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub(crate) struct BatchResponse {
+    /// False if the atomic batch was rolled back.
+    pub(crate) committed: bool,
+    pub(crate) results: Vec<BatchResult>,
+}
@@ -0,0 +1,262 @@
+use actix_web::http::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use tracing::error;
+use validator::{Validate, ValidationErrors};
+
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::conditional::IfMatch;
+use crate::api::error::api::ApiError;
+use crate::api::error::validation::ValidationCode;
+use crate::database::transaction::in_transaction;
+use crate::wrapper::batch::dto::{
+    BatchAction, BatchDTO, BatchEntity, BatchMode, BatchOperation, BatchResponse, BatchResult,
+};
+use crate::wrapper::entity::account::Account;
+use crate::wrapper::entity::budget::Budget;
+use crate::wrapper::entity::transaction::dto::TransactionDTO;
+use crate::wrapper::entity::transaction::template::dto::TransactionTemplateDTO;
+use crate::wrapper::entity::transaction::template::TransactionTemplate;
+use crate::wrapper::entity::transaction::Transaction;
+use crate::wrapper::permission::{HasPermissionOrError, Permissions};
+use crate::wrapper::types::phantom::Identifiable;
+
+pub(crate) mod dto;
+
+pub(crate) type Outcome = (StatusCode, Option<Value>);
+
+/// Runs the operations in order with the checks of their single-item routes.
+pub(crate) async fn execute(user_id: Snowflake, batch: BatchDTO) -> BatchResponse {
+    match batch.mode {
+        BatchMode::Atomic => execute_atomic(user_id, batch.operations).await,
+        BatchMode::BestEffort => execute_best_effort(user_id, batch.operations).await,
+    }
+}
+
+/// Stops at the first failed operation and rolls back the ones before it, all other operations are reported as
+/// aborted.
+async fn execute_atomic(user_id: Snowflake, operations: Vec<BatchOperation>) -> BatchResponse {
+    let total = operations.len();
+    let mut outcomes = Vec::with_capacity(total);
+    let result = in_transaction(async {
+        for operation in operations {
+            outcomes.push(execute_operation(user_id, operation).await?);
+        }
+
+        Ok(())
+    })
+    .await;
+
+    let Err(e) = result else {
+        return BatchResponse {
+            committed: true,
+            results: outcomes.into_iter().map(|outcome| BatchResult::from(Ok(outcome))).collect(),
+        };
+    };
+    // the operations before the failed one were rolled back, the ones after it never ran
+    let failed = outcomes.len();
+    let mut results: Vec<BatchResult> = (0..failed).map(|_| BatchResult::from(Err(ApiError::BatchAborted()))).collect();
+    results.push(BatchResult::from(Err(e)));
+    results.extend((failed + 1..total).map(|_| BatchResult::from(Err(ApiError::BatchAborted()))));
+
+    BatchResponse {
+        committed: false,
+        results,
+    }
+}
+
+/// Commits every operation on its own, a failed operation does not affect the others.
+async fn execute_best_effort(user_id: Snowflake, operations: Vec<BatchOperation>) -> BatchResponse {
+    let mut results = Vec::with_capacity(operations.len());
+    for operation in operations {
+        results.push(BatchResult::from(in_transaction(execute_operation(user_id, operation)).await));
+    }
+
+    BatchResponse {
+        committed: true,
+        results,
+    }
+}
+
+pub(crate) async fn execute_operation(user_id: Snowflake, operation: BatchOperation) -> Result<Outcome, ApiError> {
+    let if_match = IfMatch::from_value(operation.if_match.as_deref());
+    let BatchOperation {
+        action,
+        entity,
+        id,
+        data,
+        ..
+    } = operation;
+
+    match entity {
+        BatchEntity::Account => execute_account(user_id, action, id, data, if_match).await,
+        BatchEntity::Budget => execute_budget(user_id, action, id, data, if_match).await,
+        BatchEntity::Transaction => execute_transaction(user_id, action, id, data, if_match).await,
+        BatchEntity::TransactionTemplate => execute_template(user_id, action, id, data).await,
+    }
+}
+
+async fn execute_account(
+    user_id: Snowflake,
+    action: BatchAction,
+    id: Option<Snowflake>,
+    data: Option<Value>,
+    if_match: IfMatch,
+) -> Result<Outcome, ApiError> {
+    match action {
+        BatchAction::Create => {
+            let account = Account::new(parse(data)?, user_id).await?;
+            respond(StatusCode::CREATED, &account)
+        }
+        BatchAction::Update => {
+            let account = Account::find_by_id(require_id(id)?).await?;
+            account.has_permission_or_error(user_id, Permissions::READ_WRITE).await?;
+            if_match.check(&account)?;
+            respond(StatusCode::OK, &account.update(parse(data)?).await?)
+        }
+        BatchAction::Delete => {
+            let account = Account::find_by_id(require_id(id)?).await?;
+            account.has_permission_or_error(user_id, Permissions::READ_DELETE).await?;
+            if_match.check(&account)?;
+            account.delete().await?;
+            Ok((StatusCode::NO_CONTENT, None))
+        }
+    }
+}
+
+async fn execute_budget(
+    user_id: Snowflake,
+    action: BatchAction,
+    id: Option<Snowflake>,
+    data: Option<Value>,
+    if_match: IfMatch,
+) -> Result<Outcome, ApiError> {
+    match action {
+        BatchAction::Create => {
+            let budget = Budget::new(user_id, parse(data)?).await?;
+            respond(StatusCode::CREATED, &budget)
+        }
+        BatchAction::Update => {
+            let budget = Budget::find_by_id(require_id(id)?).await?;
+            budget.has_permission_or_error(user_id, Permissions::READ_WRITE).await?;
+            if_match.check(&budget)?;
+            respond(StatusCode::OK, &budget.update(parse(data)?).await?)
+        }
+        BatchAction::Delete => {
+            let budget = Budget::find_by_id(require_id(id)?).await?;
+            budget.has_permission_or_error(user_id, Permissions::READ_DELETE).await?;
+            if_match.check(&budget)?;
+            budget.delete().await?;
+            Ok((StatusCode::NO_CONTENT, None))
+        }
+    }
+}
+
+async fn execute_transaction(
+    user_id: Snowflake,
+    action: BatchAction,
+    id: Option<Snowflake>,
+    data: Option<Value>,
+    if_match: IfMatch,
+) -> Result<Outcome, ApiError> {
+    match action {
+        BatchAction::Create => {
+            let dto: TransactionDTO = parse(data)?;
+            if !dto.check_permissions(user_id).await? {
+                return Err(ApiError::Unauthorized());
+            }
+            respond(StatusCode::CREATED, &Transaction::new(dto).await?)
+        }
+        BatchAction::Update => {
+            let transaction = Transaction::find_by_id(require_id(id)?).await?;
+            transaction.has_permission_or_error(user_id, Permissions::READ_WRITE).await?;
+            if_match.check(&transaction)?;
+            respond(StatusCode::OK, &transaction.update(parse(data)?).await?)
+        }
+        BatchAction::Delete => {
+            let transaction = Transaction::find_by_id(require_id(id)?).await?;
+            transaction.has_permission_or_error(user_id, Permissions::READ_DELETE).await?;
+            if_match.check(&transaction)?;
+            transaction.delete().await?;
+            Ok((StatusCode::NO_CONTENT, None))
+        }
+    }
+}
+
+// templates are not versioned, so there is no precondition to check
+async fn execute_template(
+    user_id: Snowflake,
+    action: BatchAction,
+    id: Option<Snowflake>,
+    data: Option<Value>,
+) -> Result<Outcome, ApiError> {
+    match action {
+        BatchAction::Create => {
+            let dto: TransactionTemplateDTO = parse(data)?;
+            if !dto.check_permissions(user_id).await? {
+                return Err(ApiError::Unauthorized());
+            }
+            respond(StatusCode::CREATED, &TransactionTemplate::new(dto, user_id).await?)
+        }
+        BatchAction::Update => {
+            let template = TransactionTemplate::find_by_id(require_id(id)?).await?;
+            template.has_permission_or_error(user_id, Permissions::READ_WRITE).await?;
+            respond(StatusCode::OK, &template.update(parse(data)?).await?)
+        }
+        BatchAction::Delete => {
+            let template = TransactionTemplate::find_by_id(require_id(id)?).await?;
+            template.has_permission_or_error(user_id, Permissions::READ_DELETE).await?;
+            template.delete().await?;
+            Ok((StatusCode::NO_CONTENT, None))
+        }
+    }
+}
+
+/// Reads the data like the body of the single-item route, including its validation.
+fn parse<T: DeserializeOwned + Validate>(data: Option<Value>) -> Result<T, ApiError> {
+    let Some(data) = data else {
+        return Err(missing("data", ValidationCode::BATCH_DATA_MISSING));
+    };
+    let dto: T = serde_json::from_value(data)?;
+    dto.validate()?;
+
+    Ok(dto)
+}
+
+fn require_id(id: Option<Snowflake>) -> Result<Snowflake, ApiError> {
+    id.ok_or_else(|| missing("id", ValidationCode::BATCH_ID_MISSING))
+}
+
+fn missing(field: &'static str, code: ValidationCode) -> ApiError {
+    let mut errors = ValidationErrors::new();
+    errors.add(field, code.into());
+
+    errors.into()
+}
+
+fn respond<T: Serialize>(status: StatusCode, entity: &T) -> Result<Outcome, ApiError> {
+    Ok((status, Some(serde_json::to_value(entity)?)))
+}
+
+impl From<Result<Outcome, ApiError>> for BatchResult {
+    fn from(result: Result<Outcome, ApiError>) -> Self {
+        match result {
+            Ok((status, body)) => Self {
+                status: status.as_u16(),
+                body,
+            },
+            Err(e) => {
+                if e.status_code == StatusCode::INTERNAL_SERVER_ERROR {
+                    error!("Internal server error in batch operation: {}", e);
+                }
+
+                Self {
+                    status: e.status_code.as_u16(),
+                    body: serde_json::to_value(&e).ok(),
+                }
+            }
+        }
+    }
+}
@@ -1,21 +1,18 @@
 use crate::api::error::api::ApiError;
 use crate::event::lifecycle::transaction::{TransactionCreation, TransactionDeletion, TransactionUpdate};
 use crate::event::GenericEvent;
-use crate::wrapper::entity::account::dto::AccountDTO;
 use crate::wrapper::entity::account::Account;
 
 async fn update_account_balance(account: Account, amount: i64) -> Result<(), ApiError> {
-    let dto = AccountDTO::from(&account);
-    let balance = account.balance + amount;
-    account.update_with_balance(dto, balance).await?;
+    account.change_balance(amount).await?;
 
     Ok(())
 }
 
 pub(crate) fn account_listener() {
-    TransactionCreation::subscribe(transaction_created);
-    TransactionUpdate::subscribe(transaction_updated);
-    TransactionDeletion::subscribe(transaction_deleted);
+    TransactionCreation::subscribe("account.transaction_created", transaction_created);
+    TransactionUpdate::subscribe("account.transaction_updated", transaction_updated);
+    TransactionDeletion::subscribe("account.transaction_deleted", transaction_deleted);
 }
 
 async fn transaction_created(event: TransactionCreation) -> Result<(), ApiError> {
@@ -1,21 +1,33 @@
-use sea_orm::{EntityName, EntityTrait, Set};
+use sea_orm::sea_query::LockType;
+use sea_orm::{EntityName, EntityTrait, NotSet, QuerySelect, Set};
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 use tracing::error;
 use utoipa::ToSchema;
 
 use entity::utility::time::get_now;
-use entity::{account, transaction};
+use entity::{account, transaction, transaction_template};
 use utility::snowflake::entity::Snowflake;
 
 use crate::api::error::api::ApiError;
+use crate::api::expansion::{Expandable, Related};
 use crate::api::pagination::PageSizeParam;
-use crate::database::entity::{count, delete, find_all, find_all_paginated, find_one_or_error, insert, update};
+use crate::database::entity::{
+    count, find_all, find_all_paginated, find_one_or_error, insert, update_many, update_one,
+};
+use crate::database::transaction::in_transaction;
+use crate::event::lifecycle::account::{AccountCreation, AccountDeletion, AccountUpdate};
+use crate::event::GenericEvent;
 use crate::wrapper::entity::account::dto::AccountDTO;
+use crate::wrapper::entity::audit::AuditLog;
 use crate::wrapper::entity::currency::Currency;
+use crate::wrapper::entity::transaction::recurring::RecurringTransaction;
+use crate::wrapper::entity::transaction::template::TransactionTemplate;
 use crate::wrapper::entity::transaction::Transaction;
-use crate::wrapper::entity::{TableName, WrapperEntity};
-use crate::wrapper::permission::{Permission, Permissions, PermissionsEntity};
+use crate::wrapper::entity::trash::TrashEntry;
+use crate::wrapper::entity::{TableName, Versioned, WrapperEntity};
+use crate::wrapper::permission::collaborator::Shareable;
+use crate::wrapper::permission::{Permission, PermissionByIds, Permissions, PermissionsEntity, Principal};
 use crate::wrapper::types::phantom::{Identifiable, Phantom};
 use crate::{permission_impl, SNOWFLAKE_GENERATOR};
 
@@ -35,67 +47,220 @@ pub(crate) struct Account {
     pub(crate) currency_id: Phantom<Currency>,
     #[serde(with = "time::serde::rfc3339")]
     pub(crate) created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub(crate) archived_at: Option<OffsetDateTime>,
+    /// Incremented by every update of the account itself, balance changes from transactions leave it as it is. It is
+    /// sent as the `ETag` together with the balance.
+    pub(crate) version: i32,
 }
 
 impl Account {
     pub(crate) async fn new(dto: AccountDTO, user_id: Snowflake) -> Result<Self, ApiError> {
-        let snowflake = SNOWFLAKE_GENERATOR.next_id()?;
-        let active_model = account::ActiveModel {
-            id: Set(snowflake),
-            name: Set(dto.name),
-            description: Set(dto.description),
-            iban: Set(dto.iban),
-            balance: Set(dto.original_balance),
-            original_balance: Set(dto.original_balance),
-            currency: Set(dto.currency_id.id),
-            created_at: Set(get_now()),
-        };
-        let model = insert(active_model).await?;
+        in_transaction(async move {
+            let snowflake = SNOWFLAKE_GENERATOR.next_id()?;
+            let active_model = account::ActiveModel {
+                id: Set(snowflake),
+                name: Set(dto.name),
+                description: Set(dto.description),
+                iban: Set(dto.iban),
+                balance: Set(dto.original_balance),
+                original_balance: Set(dto.original_balance),
+                currency: Set(dto.currency_id.id),
+                created_at: Set(get_now()),
+                archived_at: Set(None),
+                version: Set(0),
+            };
+            let model = insert(active_model).await?;
 
-        let account = Self::from(model);
-        account.add_permission(user_id, Permissions::all()).await?;
+            let account = Self::from(model);
+            AuditLog::record_creation(&account).await?;
+            account.add_permission(user_id, Permissions::all()).await?;
 
-        Ok(account)
+            AccountCreation::new(account.clone()).fire().await?;
+
+            Ok(account)
+        })
+        .await
     }
 
     pub(crate) async fn delete(self) -> Result<(), ApiError> {
-        delete(account::Entity::delete_by_id(self.snowflake.id)).await
+        in_transaction(async move {
+            // transactions go with the account, so the balances of other accounts stay untouched like on a hard delete
+            let transactions = find_all(transaction::Entity::find_all_by_account_id(self.snowflake)).await?;
+            let templates = find_all(transaction_template::Entity::find_all_by_account_id(self.snowflake)).await?;
+            let recurring_transactions = RecurringTransaction::find_all_by_account_id(self.snowflake).await?;
+
+            let children = transactions
+                .into_iter()
+                .map(|transaction| (Transaction::table_name(), Snowflake::from(transaction.id)))
+                .chain(
+                    templates
+                        .into_iter()
+                        .map(|template| (TransactionTemplate::table_name(), Snowflake::from(template.id))),
+                )
+                .chain(
+                    recurring_transactions
+                        .iter()
+                        .map(|recurring| (RecurringTransaction::table_name(), recurring.snowflake)),
+                )
+                .collect();
+            TrashEntry::trash(&self, children).await?;
+            AuditLog::record_deletion(&self).await?;
+
+            for recurring_transaction in recurring_transactions {
+                recurring_transaction.stop_after_commit();
+            }
+
+            AccountDeletion::new(self).fire().await?;
+
+            Ok(())
+        })
+        .await
     }
 
     pub(crate) async fn update(&self, dto: AccountDTO) -> Result<Self, ApiError> {
-        self.update_with_balance(dto, self.balance).await
+        in_transaction(async {
+            let active_model = account::ActiveModel {
+                id: Set(self.snowflake.id),
+                name: Set(dto.name),
+                description: Set(dto.description),
+                iban: Set(dto.iban),
+                balance: NotSet,
+                original_balance: Set(dto.original_balance),
+                currency: Set(dto.currency_id.id),
+                created_at: Set(self.created_at),
+                archived_at: Set(self.archived_at),
+                version: Set(self.version + 1),
+            };
+            let mut model = update_one(account::Entity::update_if_version(active_model, self.version)).await?;
+            // the balance moves along with the original balance, on top of what transactions did in the meantime
+            if dto.original_balance != self.original_balance {
+                model = Self::add_to_balance(self.snowflake, dto.original_balance - self.original_balance).await?;
+            }
+            let account = Self::from(model);
+            AuditLog::record_update(self, &account).await?;
+
+            AccountUpdate::new(self.clone(), account.clone()).fire().await?;
+
+            Ok(account)
+        })
+        .await
+    }
+
+    /// Books the amount of a transaction. The account itself is not edited, so its version stays the same and the
+    /// `If-Match` of a client that is editing it still applies.
+    pub(crate) async fn change_balance(&self, amount: i64) -> Result<Self, ApiError> {
+        in_transaction(async {
+            let account = Self::from(Self::add_to_balance(self.snowflake, amount).await?);
+            AuditLog::record_update(self, &account).await?;
+
+            AccountUpdate::new(self.clone(), account.clone()).fire().await?;
+
+            Ok(account)
+        })
+        .await
+    }
+
+    pub(crate) async fn archive(self) -> Result<Self, ApiError> {
+        in_transaction(async move {
+            // waits for and then blocks the transactions that checked the account is not archived
+            find_one_or_error(account::Entity::find_by_id(self.snowflake.id).lock(LockType::Update)).await?;
+            let recurring_transactions = RecurringTransaction::find_all_by_account_id(self.snowflake).await?;
+            if !recurring_transactions.is_empty() {
+                return Err(ApiError::AccountHasRecurringTransactions(&recurring_transactions));
+            }
+
+            self.set_archived_at(Some(get_now())).await
+        })
+        .await
     }
 
-    pub(crate) async fn update_with_balance(&self, dto: AccountDTO, balance: i64) -> Result<Self, ApiError> {
-        let balance = Self::calculate_new_balance(balance, dto.original_balance, self.original_balance);
-        let active_model = account::ActiveModel {
-            id: Set(self.snowflake.id),
-            name: Set(dto.name),
-            description: Set(dto.description),
-            iban: Set(dto.iban),
-            balance: Set(balance),
-            original_balance: Set(dto.original_balance),
-            currency: Set(dto.currency_id.id),
-            created_at: Set(self.created_at),
-        };
-        let model = update(active_model).await?;
-        let account = Self::from(model);
+    pub(crate) async fn unarchive(self) -> Result<Self, ApiError> {
+        self.set_archived_at(None).await
+    }
+
+    async fn set_archived_at(self, archived_at: Option<OffsetDateTime>) -> Result<Self, ApiError> {
+        in_transaction(async move {
+            let active_model = account::ActiveModel {
+                id: Set(self.snowflake.id),
+                name: Set(self.name.clone()),
+                description: Set(self.description.clone()),
+                iban: Set(self.iban.clone()),
+                balance: NotSet,
+                original_balance: Set(self.original_balance),
+                currency: Set(self.currency_id.get_id().id),
+                created_at: Set(self.created_at),
+                archived_at: Set(archived_at),
+                version: Set(self.version + 1),
+            };
+            let account = Self::from(update_one(account::Entity::update_if_version(active_model, self.version)).await?);
+            AuditLog::record_update(&self, &account).await?;
 
-        //AccountUpdate::new(self.clone(), account.clone()).fire();
+            AccountUpdate::new(self, account.clone()).fire().await?;
 
-        Ok(account)
+            Ok(account)
+        })
+        .await
+    }
+
+    pub(crate) fn is_archived(&self) -> bool {
+        self.archived_at.is_some()
+    }
+
+    // the key share lock makes a concurrent archive wait for the surrounding transaction, balance updates do not
+    pub(crate) async fn assert_not_archived(account: &Phantom<Self>) -> Result<(), ApiError> {
+        let account =
+            find_one_or_error(account::Entity::find_by_id(account.get_id().id).lock(LockType::KeyShare)).await?;
+        if Self::from(account).is_archived() {
+            return Err(ApiError::AccountArchived());
+        }
+
+        Ok(())
     }
 
-    fn calculate_new_balance(balance: i64, new_original_balance: i64, old_original_balance: i64) -> i64 {
-        balance + new_original_balance - old_original_balance
+    // accounts that are already referenced are skipped, so existing entries stay editable
+    pub(crate) async fn assert_accounts_not_archived(
+        accounts: [&Option<Phantom<Self>>; 2],
+        referenced: [&Option<Phantom<Self>>; 2],
+    ) -> Result<(), ApiError> {
+        for account in accounts.into_iter().flatten() {
+            let already_referenced =
+                referenced.into_iter().flatten().any(|referenced| referenced.get_id() == account.get_id());
+            if !already_referenced {
+                Self::assert_not_archived(account).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn add_to_balance(id: Snowflake, amount: i64) -> Result<account::Model, ApiError> {
+        update_many(account::Entity::add_to_balance(id, amount)).await?;
+
+        find_one_or_error(account::Entity::find_by_id(id.id)).await
     }
 
     pub(crate) async fn find_all_by_user(user_id: Snowflake) -> Result<Vec<Self>, ApiError> {
-        Ok(find_all(account::Entity::find_all_by_user_id(user_id)).await?.into_iter().map(Self::from).collect())
+        Ok(find_all(account::Entity::find_all_active_by_user_id(user_id)).await?.into_iter().map(Self::from).collect())
     }
 
     pub(crate) async fn count_all_by_user(user_id: Snowflake) -> Result<u64, ApiError> {
-        count(account::Entity::find_all_by_user_id(user_id)).await
+        count(account::Entity::find_all_active_by_user_id(user_id)).await
+    }
+
+    pub(crate) async fn find_all_archived_by_user_paginated(
+        user_id: Snowflake,
+        page_size: &PageSizeParam,
+    ) -> Result<Vec<Self>, ApiError> {
+        Ok(find_all_paginated(account::Entity::find_all_archived_by_user_id(user_id), page_size)
+            .await?
+            .into_iter()
+            .map(Self::from)
+            .collect())
+    }
+
+    pub(crate) async fn count_all_archived_by_user(user_id: Snowflake) -> Result<u64, ApiError> {
+        count(account::Entity::find_all_archived_by_user_id(user_id)).await
     }
 
     pub(crate) async fn find_transactions_by_account_id_paginated(
@@ -121,23 +286,84 @@ impl Account {
     ) -> Result<(), ApiError> {
         let permissions = PermissionsEntity::find_all_by_type_and_id(Self::table_name(), account_id).await?;
         for permission in permissions {
-            if let Err(err) = obj.add_permission(permission.user_id, permission.permissions).await {
+            let Some(principal) = permission.principal() else {
+                continue;
+            };
+            if let Err(err) = obj.add_permission(principal, permission.permissions).await {
                 error!("Failed to add permission to user: {}", err);
             }
         }
 
         Ok(())
     }
+
+    async fn sync_permissions_from_accounts(
+        obj: &impl Permission,
+        accounts: &[&Option<Phantom<Self>>],
+        principal: Principal,
+    ) -> Result<(), ApiError> {
+        let mut permissions = Permissions::empty();
+        for account in accounts.iter().copied().flatten() {
+            permissions |= Self::get_principal_permissions_by_id(account.get_id(), principal).await?;
+        }
+
+        obj.set_permission(principal, permissions).await
+    }
 }
 
 permission_impl!(Account);
 
+impl Expandable for Account {
+    const RELATIONS: &'static [&'static str] = &["currency"];
+
+    fn related(&mut self, name: &str) -> Option<Related<'_>> {
+        match name {
+            "currency" => Some(Related::Currency(&mut self.currency_id)),
+            _ => None,
+        }
+    }
+}
+
+impl Shareable for Account {
+    async fn propagate_permissions(&self, principal: Principal) -> Result<(), ApiError> {
+        let transactions = find_all(transaction::Entity::find_all_by_account_id(self.snowflake)).await?;
+        for transaction in transactions.into_iter().map(Transaction::from) {
+            let accounts = [&transaction.source_id, &transaction.destination_id];
+            Self::sync_permissions_from_accounts(&transaction, &accounts, principal).await?;
+        }
+
+        let templates = find_all(transaction_template::Entity::find_all_by_account_id(self.snowflake)).await?;
+        for template in templates.into_iter().map(TransactionTemplate::from) {
+            let accounts = [&template.source_id, &template.destination_id];
+            Self::sync_permissions_from_accounts(&template, &accounts, principal).await?;
+        }
+
+        for recurring_transaction in RecurringTransaction::find_all_by_account_id(self.snowflake).await? {
+            let template = recurring_transaction.template_id.fetch_inner().await?;
+            let accounts = [&template.source_id, &template.destination_id];
+            Self::sync_permissions_from_accounts(&recurring_transaction, &accounts, principal).await?;
+        }
+
+        Ok(())
+    }
+}
+
 impl WrapperEntity for Account {
     fn get_id(&self) -> Snowflake {
         self.snowflake
     }
 }
 
+impl Versioned for Account {
+    fn version(&self) -> i32 {
+        self.version
+    }
+
+    fn derived_state(&self) -> Option<i64> {
+        Some(self.balance)
+    }
+}
+
 impl TableName for Account {
     fn table_name() -> &'static str {
         account::Entity.table_name()
@@ -149,7 +375,7 @@ impl Identifiable for Account {
     where
         Self: Sized,
     {
-        find_one_or_error(account::Entity::find_by_id(id)).await.map(Self::from)
+        find_one_or_error(account::Entity::find_by_id_not_trashed(id)).await.map(Self::from)
     }
 }
 
@@ -164,6 +390,8 @@ impl From<account::Model> for Account {
             original_balance: value.original_balance,
             currency_id: Phantom::from(value.currency),
             created_at: value.created_at,
+            archived_at: value.archived_at,
+            version: value.version,
         }
     }
 }
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::wrapper::entity::api_token::scope::ApiTokenScope;
+use crate::wrapper::entity::api_token::ApiToken;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
+pub(crate) struct ApiTokenDTO {
+    #[validate(length(min = 1))]
+    pub(crate) name: String,
+    #[validate(length(min = 1))]
+    pub(crate) scopes: Vec<ApiTokenScope>,
+    /// The token never expires if this is not set.
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub(crate) expires_at: Option<OffsetDateTime>,
+}
+
+/// The only time the plain token is returned, it can not be retrieved again.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct CreatedApiToken {
+    pub(crate) token: String,
+    pub(crate) api_token: ApiToken,
+}
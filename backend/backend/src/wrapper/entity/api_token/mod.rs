@@ -0,0 +1,174 @@
+use actix_web::http::Method;
+use sea_orm::{EntityName, EntityTrait, Set};
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
+use utoipa::ToSchema;
+
+use entity::api_token;
+use entity::utility::hashing::{generate_salt, hash_token};
+use entity::utility::time::get_now;
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::error::api::ApiError;
+use crate::api::pagination::PageSizeParam;
+use crate::database::entity::{count, delete, find_all_paginated, find_one, find_one_or_error, insert, update};
+use crate::wrapper::entity::api_token::dto::{ApiTokenDTO, CreatedApiToken};
+use crate::wrapper::entity::api_token::scope::ApiTokenScope;
+use crate::wrapper::entity::user::User;
+use crate::wrapper::entity::{TableName, WrapperEntity};
+use crate::wrapper::types::phantom::Phantom;
+use crate::SNOWFLAKE_GENERATOR;
+
+pub(crate) mod dto;
+pub(crate) mod scope;
+
+pub(crate) const API_TOKEN_PREFIX: &str = "financrr_pat_";
+const TOKEN_LENGTH: usize = 40;
+// writing on every request would turn each read into a write
+const LAST_USED_PRECISION_MINUTES: i64 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct ApiToken {
+    #[serde(rename = "id")]
+    pub(crate) snowflake: Snowflake,
+    pub(crate) user_id: Phantom<User>,
+    pub(crate) name: String,
+    pub(crate) scopes: Vec<ApiTokenScope>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub(crate) expires_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub(crate) last_used_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub(crate) created_at: OffsetDateTime,
+}
+
+impl ApiToken {
+    pub(crate) async fn create(user_id: Snowflake, dto: ApiTokenDTO) -> Result<CreatedApiToken, ApiError> {
+        let token = format!("{}{}", API_TOKEN_PREFIX, generate_salt(TOKEN_LENGTH));
+        let active_model = api_token::ActiveModel {
+            id: Set(SNOWFLAKE_GENERATOR.next_id()?),
+            user: Set(user_id.id),
+            name: Set(dto.name),
+            token_hash: Set(hash_token(&token)),
+            scopes: Set(serde_json::to_value(&dto.scopes)?),
+            expires_at: Set(dto.expires_at),
+            last_used_at: Set(None),
+            created_at: Set(get_now()),
+        };
+        let api_token = Self::try_from(insert(active_model).await?)?;
+
+        Ok(CreatedApiToken {
+            token,
+            api_token,
+        })
+    }
+
+    pub(crate) fn is_api_token(token: &str) -> bool {
+        token.starts_with(API_TOKEN_PREFIX)
+    }
+
+    /// Resolves the owner of the token if it is still valid and its scopes cover the requested route.
+    pub(crate) async fn authenticate(token: &str, method: &Method, path: &str) -> Result<Snowflake, ApiError> {
+        let api_token = Self::find_by_token(token).await?.ok_or_else(ApiError::InvalidSession)?;
+        if api_token.expires_at.is_some_and(|expires_at| expires_at <= get_now()) {
+            return Err(ApiError::InvalidSession());
+        }
+
+        match ApiTokenScope::required_for(method, path) {
+            None => return Err(ApiError::SessionRequired()),
+            Some(scopes) if !scopes.iter().any(|scope| api_token.scopes.contains(scope)) => {
+                return Err(ApiError::MissingPermissions())
+            }
+            Some(_) => {}
+        }
+
+        let user_id = api_token.user_id.get_id();
+        api_token.touch().await?;
+
+        Ok(user_id)
+    }
+
+    async fn touch(self) -> Result<(), ApiError> {
+        let now = get_now();
+        if self
+            .last_used_at
+            .is_some_and(|last_used_at| now - last_used_at < Duration::minutes(LAST_USED_PRECISION_MINUTES))
+        {
+            return Ok(());
+        }
+
+        let mut active_model = self.to_active_model()?;
+        active_model.last_used_at = Set(Some(now));
+        update(active_model).await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn delete(self) -> Result<(), ApiError> {
+        delete(api_token::Entity::delete_by_id(self.snowflake.id)).await
+    }
+
+    pub(crate) async fn find_by_token(token: &str) -> Result<Option<Self>, ApiError> {
+        find_one(api_token::Entity::find_by_token(token)).await?.map(Self::try_from).transpose()
+    }
+
+    pub(crate) async fn find_by_id_and_user(id: Snowflake, user_id: Snowflake) -> Result<Self, ApiError> {
+        Self::try_from(find_one_or_error(api_token::Entity::find_by_id_and_user_id(id, user_id)).await?)
+    }
+
+    pub(crate) async fn find_all_by_user_paginated(
+        user_id: Snowflake,
+        page_size: &PageSizeParam,
+    ) -> Result<Vec<Self>, ApiError> {
+        find_all_paginated(api_token::Entity::find_by_user_id(user_id), page_size)
+            .await?
+            .into_iter()
+            .map(Self::try_from)
+            .collect()
+    }
+
+    pub(crate) async fn count_all_by_user(user_id: Snowflake) -> Result<u64, ApiError> {
+        count(api_token::Entity::find_by_user_id(user_id)).await
+    }
+
+    fn to_active_model(&self) -> Result<api_token::ActiveModel, ApiError> {
+        Ok(api_token::ActiveModel {
+            id: Set(self.snowflake.id),
+            user: Set(self.user_id.get_id().id),
+            name: Set(self.name.clone()),
+            token_hash: Default::default(),
+            scopes: Set(serde_json::to_value(&self.scopes)?),
+            expires_at: Set(self.expires_at),
+            last_used_at: Set(self.last_used_at),
+            created_at: Set(self.created_at),
+        })
+    }
+}
+
+impl TableName for ApiToken {
+    fn table_name() -> &'static str {
+        api_token::Entity.table_name()
+    }
+}
+
+impl WrapperEntity for ApiToken {
+    fn get_id(&self) -> Snowflake {
+        self.snowflake
+    }
+}
+
+impl TryFrom<api_token::Model> for ApiToken {
+    type Error = ApiError;
+
+    fn try_from(model: api_token::Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            snowflake: Snowflake::from(model.id),
+            user_id: Phantom::from(model.user),
+            name: model.name,
+            scopes: serde_json::from_value(model.scopes)?,
+            expires_at: model.expires_at,
+            last_used_at: model.last_used_at,
+            created_at: model.created_at,
+        })
+    }
+}
@@ -0,0 +1,79 @@
+use actix_web::http::Method;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+const API_V1_PREFIX: &str = "/api/v1/";
+/// The resource and last path segment of the routes that only return aggregated data.
+const REPORT_ROUTES: [(&str, &str); 2] = [("group", "balances"), ("expense", "debts")];
+
+/// What an API token may be used for. Every scope covers one resource, split into reading and writing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+pub(crate) enum ApiTokenScope {
+    #[serde(rename = "account:read")]
+    AccountRead,
+    #[serde(rename = "account:write")]
+    AccountWrite,
+    #[serde(rename = "transaction:read")]
+    TransactionRead,
+    #[serde(rename = "transaction:write")]
+    TransactionWrite,
+    #[serde(rename = "budget:read")]
+    BudgetRead,
+    #[serde(rename = "budget:write")]
+    BudgetWrite,
+    #[serde(rename = "currency:read")]
+    CurrencyRead,
+    #[serde(rename = "currency:write")]
+    CurrencyWrite,
+    #[serde(rename = "group:read")]
+    GroupRead,
+    #[serde(rename = "group:write")]
+    GroupWrite,
+    #[serde(rename = "audit:read")]
+    AuditRead,
+    #[serde(rename = "trash:read")]
+    TrashRead,
+    #[serde(rename = "trash:write")]
+    TrashWrite,
+    #[serde(rename = "webhook:read")]
+    WebhookRead,
+    #[serde(rename = "webhook:write")]
+    WebhookWrite,
+    /// The aggregated views, group balances and debts, without access to the expenses behind them.
+    #[serde(rename = "report:read")]
+    ReportRead,
+}
+
+impl ApiTokenScope {
+    /// The scopes that allow a route, any one of them is enough. They are derived from the resource the route belongs
+    /// to and whether it only reads.
+    /// Routes without scopes are session-only: session, user and token management, and the routes that span every
+    /// resource at once (batch, sync, realtime and GraphQL), since no single scope could limit them.
+    pub(crate) fn required_for(method: &Method, path: &str) -> Option<&'static [Self]> {
+        let mut segments = path.strip_prefix(API_V1_PREFIX)?.split('/');
+        let resource = segments.next()?;
+        let read = matches!(*method, Method::GET | Method::HEAD);
+        if read && segments.last().is_some_and(|last| REPORT_ROUTES.contains(&(resource, last))) {
+            return Some(&[Self::GroupRead, Self::ReportRead]);
+        }
+
+        match (resource, read) {
+            ("account", true) => Some(&[Self::AccountRead]),
+            ("account", false) => Some(&[Self::AccountWrite]),
+            ("transaction", true) => Some(&[Self::TransactionRead]),
+            ("transaction", false) => Some(&[Self::TransactionWrite]),
+            ("budget", true) => Some(&[Self::BudgetRead]),
+            ("budget", false) => Some(&[Self::BudgetWrite]),
+            ("currency", true) => Some(&[Self::CurrencyRead]),
+            ("currency", false) => Some(&[Self::CurrencyWrite]),
+            ("group" | "expense", true) => Some(&[Self::GroupRead]),
+            ("group" | "expense", false) => Some(&[Self::GroupWrite]),
+            ("audit", true) => Some(&[Self::AuditRead]),
+            ("trash", true) => Some(&[Self::TrashRead]),
+            ("trash", false) => Some(&[Self::TrashWrite]),
+            ("webhook", true) => Some(&[Self::WebhookRead]),
+            ("webhook", false) => Some(&[Self::WebhookWrite]),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use std::future::Future;
+
+use tokio::task::futures::TaskLocalFuture;
+use tokio::task_local;
+
+use entity::{api_token, session};
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::error::api::ApiError;
+use crate::database::entity::find_one;
+use crate::wrapper::entity::api_token::ApiToken;
+
+task_local! {
+    static REQUEST_TOKEN: Option<String>;
+}
+
+/// The user and session on whose behalf the current task is running, API tokens have no session.
+/// Background jobs and event listeners run without one and are recorded as system changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct Actor {
+    pub(crate) user_id: Option<Snowflake>,
+    pub(crate) session_id: Option<Snowflake>,
+}
+
+impl Actor {
+    pub(crate) fn scope<F: Future>(token: Option<String>, future: F) -> TaskLocalFuture<Option<String>, F> {
+        REQUEST_TOKEN.scope(token, future)
+    }
+
+    pub(crate) async fn current() -> Result<Self, ApiError> {
+        let token = REQUEST_TOKEN.try_with(Clone::clone).ok().flatten();
+        let Some(token) = token else {
+            return Ok(Self::default());
+        };
+
+        if ApiToken::is_api_token(&token) {
+            return Ok(find_one(api_token::Entity::find_by_token(&token))
+                .await?
+                .map(|api_token| Self {
+                    user_id: Some(Snowflake::from(api_token.user)),
+                    session_id: None,
+                })
+                .unwrap_or_default());
+        }
+
+        Ok(find_one(session::Entity::find_by_token(token))
+            .await?
+            .map(|session| Self {
+                user_id: Some(Snowflake::from(session.user)),
+                session_id: Some(Snowflake::from(session.id)),
+            })
+            .unwrap_or_default())
+    }
+}
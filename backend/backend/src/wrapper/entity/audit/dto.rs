@@ -0,0 +1,41 @@
+use sea_orm::{ColumnTrait, QueryFilter, QueryTrait, Select};
+use serde::Deserialize;
+use time::OffsetDateTime;
+use utoipa::IntoParams;
+
+use entity::audit_log;
+use utility::snowflake::entity::Snowflake;
+
+use crate::wrapper::entity::audit::AuditAction;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct AuditLogFilter {
+    /// Only return entries of this entity type, e.g. `transaction`.
+    pub(crate) entity_type: Option<String>,
+    /// Only return entries of this entity.
+    pub(crate) entity_id: Option<Snowflake>,
+    /// Only return entries caused by this user.
+    pub(crate) actor_id: Option<Snowflake>,
+    pub(crate) action: Option<AuditAction>,
+    /// Only return entries created at or after this point in time.
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub(crate) from: Option<OffsetDateTime>,
+    /// Only return entries created at or before this point in time.
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub(crate) to: Option<OffsetDateTime>,
+}
+
+impl AuditLogFilter {
+    pub(crate) fn apply(&self, select: Select<audit_log::Entity>) -> Select<audit_log::Entity> {
+        select
+            .apply_if(self.entity_type.clone(), |query, entity_type| {
+                query.filter(audit_log::Column::EntityType.eq(entity_type))
+            })
+            .apply_if(self.entity_id, |query, entity_id| query.filter(audit_log::Column::EntityId.eq(entity_id)))
+            .apply_if(self.actor_id, |query, actor_id| query.filter(audit_log::Column::Actor.eq(actor_id)))
+            .apply_if(self.action, |query, action| query.filter(audit_log::Column::Action.eq(action.as_str())))
+            .apply_if(self.from, |query, from| query.filter(audit_log::Column::CreatedAt.gte(from)))
+            .apply_if(self.to, |query, to| query.filter(audit_log::Column::CreatedAt.lte(to)))
+    }
+}
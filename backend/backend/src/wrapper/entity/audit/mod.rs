@@ -0,0 +1,258 @@
+use sea_orm::{EntityName, QuerySelect, Set};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+use entity::audit_log;
+use entity::error::EntityError;
+use entity::utility::time::get_now;
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::error::api::ApiError;
+use crate::api::pagination::PageSizeParam;
+use crate::database::entity::{count, find_all, find_all_paginated, insert};
+use crate::wrapper::entity::audit::context::Actor;
+use crate::wrapper::entity::audit::dto::AuditLogFilter;
+use crate::wrapper::entity::user::User;
+use crate::wrapper::entity::{TableName, WrapperEntity};
+use crate::wrapper::types::phantom::Phantom;
+use crate::SNOWFLAKE_GENERATOR;
+
+pub(crate) mod context;
+pub(crate) mod dto;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum AuditAction {
+    Create,
+    Update,
+    Delete,
+    Restore,
+    ResetPassword,
+    Unlock,
+    Revoke,
+}
+
+impl AuditAction {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Update => "update",
+            Self::Delete => "delete",
+            Self::Restore => "restore",
+            Self::ResetPassword => "reset_password",
+            Self::Unlock => "unlock",
+            Self::Revoke => "revoke",
+        }
+    }
+}
+
+impl TryFrom<&str> for AuditAction {
+    type Error = EntityError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "create" => Ok(Self::Create),
+            "update" => Ok(Self::Update),
+            "delete" => Ok(Self::Delete),
+            "restore" => Ok(Self::Restore),
+            "reset_password" => Ok(Self::ResetPassword),
+            "unlock" => Ok(Self::Unlock),
+            "revoke" => Ok(Self::Revoke),
+            _ => Err(EntityError::UnknownValue("audit action", value.to_string())),
+        }
+    }
+}
+
+/// An append-only record of a change to an entity.
+/// For updates `before` and `after` only contain the fields that changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct AuditLog {
+    #[serde(rename = "id")]
+    pub(crate) snowflake: Snowflake,
+    pub(crate) actor_id: Option<Phantom<User>>,
+    pub(crate) session_id: Option<Snowflake>,
+    pub(crate) entity_type: String,
+    pub(crate) entity_id: Snowflake,
+    pub(crate) action: AuditAction,
+    #[schema(value_type = Option<Object>)]
+    pub(crate) before: Option<Value>,
+    #[schema(value_type = Option<Object>)]
+    pub(crate) after: Option<Value>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub(crate) created_at: OffsetDateTime,
+}
+
+impl AuditLog {
+    pub(crate) async fn record_creation<T: WrapperEntity + Serialize>(entity: &T) -> Result<(), ApiError> {
+        Self::record(T::table_name(), entity.get_id(), AuditAction::Create, None, Some(serde_json::to_value(entity)?))
+            .await
+    }
+
+    pub(crate) async fn record_update<T: WrapperEntity + Serialize>(old: &T, new: &T) -> Result<(), ApiError> {
+        let (before, after) = diff(serde_json::to_value(old)?, serde_json::to_value(new)?);
+        if before.is_empty() && after.is_empty() {
+            return Ok(());
+        }
+
+        Self::record(
+            T::table_name(),
+            new.get_id(),
+            AuditAction::Update,
+            Some(Value::Object(before)),
+            Some(Value::Object(after)),
+        )
+        .await
+    }
+
+    pub(crate) async fn record_deletion<T: WrapperEntity + Serialize>(entity: &T) -> Result<(), ApiError> {
+        Self::record(T::table_name(), entity.get_id(), AuditAction::Delete, Some(serde_json::to_value(entity)?), None)
+            .await
+    }
+
+    pub(crate) async fn record_restoration(
+        entity_type: &str,
+        entity_id: Snowflake,
+        snapshot: Option<Value>,
+    ) -> Result<(), ApiError> {
+        Self::record(entity_type, entity_id, AuditAction::Restore, None, snapshot).await
+    }
+
+    /// Records an action on an entity that happened along with its parent, e.g. the transactions of a deleted account.
+    pub(crate) async fn record_cascade(
+        entity_type: &str,
+        entity_id: Snowflake,
+        action: AuditAction,
+    ) -> Result<(), ApiError> {
+        Self::record(entity_type, entity_id, action, None, None).await
+    }
+
+    /// Records that the user can no longer read the entity, so it is removed from their synced copy.
+    pub(crate) async fn record_revocation(
+        entity_type: &str,
+        entity_id: Snowflake,
+        user_id: Snowflake,
+    ) -> Result<(), ApiError> {
+        Self::record(entity_type, entity_id, AuditAction::Revoke, Some(json!({ "user_id": user_id })), None).await
+    }
+
+    /// Records an action on an entity that leaves no trace in its serialized fields, e.g. a password reset.
+    pub(crate) async fn record_action<T: WrapperEntity>(entity: &T, action: AuditAction) -> Result<(), ApiError> {
+        Self::record(T::table_name(), entity.get_id(), action, None, None).await
+    }
+
+    async fn record(
+        entity_type: &str,
+        entity_id: Snowflake,
+        action: AuditAction,
+        before: Option<Value>,
+        after: Option<Value>,
+    ) -> Result<(), ApiError> {
+        let actor = Actor::current().await?;
+        let active_model = audit_log::ActiveModel {
+            id: Set(SNOWFLAKE_GENERATOR.next_id()?),
+            actor: Set(actor.user_id.map(|user_id| user_id.id)),
+            session: Set(actor.session_id.map(|session_id| session_id.id)),
+            entity_type: Set(entity_type.to_string()),
+            entity_id: Set(entity_id.id),
+            action: Set(action.as_str().to_string()),
+            before: Set(before),
+            after: Set(after),
+            created_at: Set(get_now()),
+        };
+        insert(active_model).await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn find_all_paginated(
+        filter: &AuditLogFilter,
+        page_size: &PageSizeParam,
+    ) -> Result<Vec<Self>, ApiError> {
+        find_all_paginated(filter.apply(audit_log::Entity::find_all()), page_size)
+            .await?
+            .into_iter()
+            .map(Self::try_from)
+            .collect()
+    }
+
+    pub(crate) async fn count_all(filter: &AuditLogFilter) -> Result<u64, ApiError> {
+        count(filter.apply(audit_log::Entity::find_all())).await
+    }
+
+    pub(crate) async fn find_all_by_user_paginated(
+        user_id: Snowflake,
+        filter: &AuditLogFilter,
+        page_size: &PageSizeParam,
+    ) -> Result<Vec<Self>, ApiError> {
+        find_all_paginated(filter.apply(audit_log::Entity::find_all_by_user_id(user_id)), page_size)
+            .await?
+            .into_iter()
+            .map(Self::try_from)
+            .collect()
+    }
+
+    pub(crate) async fn count_all_by_user(user_id: Snowflake, filter: &AuditLogFilter) -> Result<u64, ApiError> {
+        count(filter.apply(audit_log::Entity::find_all_by_user_id(user_id))).await
+    }
+
+    /// The oldest changes between the two ids to entities of the given types the user can read.
+    pub(crate) async fn find_changes_by_user(
+        user_id: Snowflake,
+        after: Snowflake,
+        before: Snowflake,
+        entity_types: Vec<&str>,
+        limit: u64,
+    ) -> Result<Vec<Self>, ApiError> {
+        let changes = audit_log::Entity::find_changes_by_user_id(user_id, after, before, entity_types).limit(limit);
+
+        find_all(changes).await?.into_iter().map(Self::try_from).collect()
+    }
+}
+
+// keeps only the top-level fields whose values differ
+fn diff(before: Value, after: Value) -> (Map<String, Value>, Map<String, Value>) {
+    let (Value::Object(mut before), Value::Object(mut after)) = (before, after) else {
+        return (Map::new(), Map::new());
+    };
+
+    let unchanged: Vec<String> =
+        before.iter().filter(|(key, value)| after.get(*key) == Some(*value)).map(|(key, _)| key.clone()).collect();
+    for key in unchanged {
+        before.remove(&key);
+        after.remove(&key);
+    }
+
+    (before, after)
+}
+
+impl TableName for AuditLog {
+    fn table_name() -> &'static str {
+        audit_log::Entity.table_name()
+    }
+}
+
+impl WrapperEntity for AuditLog {
+    fn get_id(&self) -> Snowflake {
+        self.snowflake
+    }
+}
+
+impl TryFrom<audit_log::Model> for AuditLog {
+    type Error = ApiError;
+
+    fn try_from(model: audit_log::Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            snowflake: Snowflake::from(model.id),
+            actor_id: Phantom::from_option(model.actor),
+            session_id: model.session.map(Snowflake::from),
+            entity_type: model.entity_type,
+            entity_id: Snowflake::from(model.entity_id),
+            action: AuditAction::try_from(model.action.as_str())?,
+            before: model.before,
+            after: model.after,
+            created_at: model.created_at,
+        })
+    }
+}
@@ -13,9 +13,9 @@ async fn update_budget_amount(budget: Budget, amount: i64) -> Result<(), ApiErro
 }
 
 pub(crate) fn budget_listener() {
-    TransactionCreation::subscribe(transaction_created);
-    TransactionUpdate::subscribe(transaction_updated);
-    TransactionDeletion::subscribe(transaction_deleted);
+    TransactionCreation::subscribe("budget.transaction_created", transaction_created);
+    TransactionUpdate::subscribe("budget.transaction_updated", transaction_updated);
+    TransactionDeletion::subscribe("budget.transaction_deleted", transaction_deleted);
 }
 
 async fn transaction_created(event: TransactionCreation) -> Result<(), ApiError> {
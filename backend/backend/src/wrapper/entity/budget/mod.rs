@@ -1,19 +1,29 @@
 use sea_orm::ActiveValue::Set;
-use sea_orm::{EntityName, EntityTrait};
+use sea_orm::EntityName;
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 use utoipa::ToSchema;
 
-use entity::{budget, transaction};
+use entity::{budget, transaction, transaction_template};
 use utility::snowflake::entity::Snowflake;
 
 use crate::api::error::api::ApiError;
+use crate::api::expansion::{Expandable, Related};
 use crate::api::pagination::PageSizeParam;
-use crate::database::entity::{count, delete, find_all_paginated, find_one_or_error, insert, update};
+use crate::database::entity::{count, find_all, find_all_paginated, find_one_or_error, insert, update_one};
+use crate::database::transaction::in_transaction;
+use crate::event::lifecycle::budget::{BudgetCreation, BudgetDeletion, BudgetUpdate};
+use crate::event::lifecycle::transaction::TransactionDeletion;
+use crate::event::GenericEvent;
+use crate::wrapper::entity::audit::AuditLog;
 use crate::wrapper::entity::budget::dto::BudgetDTO;
+use crate::wrapper::entity::transaction::recurring::RecurringTransaction;
+use crate::wrapper::entity::transaction::template::TransactionTemplate;
 use crate::wrapper::entity::transaction::Transaction;
+use crate::wrapper::entity::trash::TrashEntry;
 use crate::wrapper::entity::user::User;
-use crate::wrapper::entity::{TableName, WrapperEntity};
+use crate::wrapper::entity::{TableName, Versioned, WrapperEntity};
+use crate::wrapper::permission::collaborator::Shareable;
 use crate::wrapper::permission::{Permission, Permissions};
 use crate::wrapper::types::phantom::{Identifiable, Phantom};
 use crate::{permission_impl, SNOWFLAKE_GENERATOR};
@@ -32,29 +42,38 @@ pub(crate) struct Budget {
     pub(crate) description: Option<String>,
     #[serde(with = "time::serde::rfc3339")]
     pub(crate) created_at: OffsetDateTime,
+    /// Incremented by every update and sent as the `ETag`.
+    pub(crate) version: i32,
 }
 
 impl Budget {
     pub(crate) async fn new(user_id: Snowflake, dto: BudgetDTO) -> Result<Self, ApiError> {
-        let snowflake = SNOWFLAKE_GENERATOR.next_id()?;
-        let model = budget::ActiveModel {
-            id: Set(snowflake),
-            user: Set(user_id.id),
-            amount: Set(dto.amount),
-            name: Set(dto.name),
-            description: Set(dto.description),
-            created_at: Set(dto.created_at),
-        };
-
-        let model = insert(model).await?;
-        let budget = Self::from(model);
-        budget.add_permission(user_id, Permissions::all()).await?;
-
-        Ok(budget)
+        in_transaction(async move {
+            let snowflake = SNOWFLAKE_GENERATOR.next_id()?;
+            let model = budget::ActiveModel {
+                id: Set(snowflake),
+                user: Set(user_id.id),
+                amount: Set(dto.amount),
+                name: Set(dto.name),
+                description: Set(dto.description),
+                created_at: Set(dto.created_at),
+                version: Set(0),
+            };
+
+            let model = insert(model).await?;
+            let budget = Self::from(model);
+            AuditLog::record_creation(&budget).await?;
+            budget.add_permission(user_id, Permissions::all()).await?;
+
+            BudgetCreation::new(budget.clone()).fire().await?;
+
+            Ok(budget)
+        })
+        .await
     }
 
     pub(crate) async fn exists(id: Snowflake) -> Result<bool, ApiError> {
-        count(budget::Entity::find_by_id(id)).await.map(|count| count > 0)
+        count(budget::Entity::find_by_id_not_trashed(id)).await.map(|count| count > 0)
     }
 
     pub(crate) async fn find_all_by_user_paginated(
@@ -73,20 +92,67 @@ impl Budget {
     }
 
     pub(crate) async fn delete(self) -> Result<(), ApiError> {
-        delete(budget::Entity::delete_by_id(self.snowflake)).await
+        in_transaction(async move {
+            let transactions = find_all(transaction::Entity::find_all_by_budget_id(self.snowflake)).await?;
+            let templates = find_all(transaction_template::Entity::find_all_by_budget_id(self.snowflake)).await?;
+            let mut recurring_transactions = Vec::new();
+            for template in &templates {
+                recurring_transactions
+                    .extend(RecurringTransaction::find_all_by_template_id(Snowflake::from(template.id)).await?);
+            }
+
+            let transactions: Vec<Transaction> = transactions.into_iter().map(Transaction::from).collect();
+            let children = transactions
+                .iter()
+                .map(|transaction| (Transaction::table_name(), transaction.snowflake))
+                .chain(
+                    templates
+                        .into_iter()
+                        .map(|template| (TransactionTemplate::table_name(), Snowflake::from(template.id))),
+                )
+                .chain(
+                    recurring_transactions
+                        .iter()
+                        .map(|recurring| (RecurringTransaction::table_name(), recurring.snowflake)),
+                )
+                .collect();
+            TrashEntry::trash(&self, children).await?;
+            AuditLog::record_deletion(&self).await?;
+
+            for transaction in transactions {
+                TransactionDeletion::new(transaction).fire().await?;
+            }
+            for recurring_transaction in recurring_transactions {
+                recurring_transaction.stop_after_commit();
+            }
+
+            BudgetDeletion::new(self).fire().await?;
+
+            Ok(())
+        })
+        .await
     }
 
     pub(crate) async fn update(self, dto: BudgetDTO) -> Result<Self, ApiError> {
-        let model = budget::ActiveModel {
-            id: Set(self.snowflake.id),
-            user: Set(self.user_id.get_id().id),
-            amount: Set(dto.amount),
-            name: Set(dto.name),
-            description: Set(dto.description),
-            created_at: Set(dto.created_at),
-        };
-
-        Ok(update(model).await?.into())
+        in_transaction(async move {
+            let model = budget::ActiveModel {
+                id: Set(self.snowflake.id),
+                user: Set(self.user_id.get_id().id),
+                amount: Set(dto.amount),
+                name: Set(dto.name),
+                description: Set(dto.description),
+                created_at: Set(dto.created_at),
+                version: Set(self.version + 1),
+            };
+
+            let budget = Self::from(update_one(budget::Entity::update_if_version(model, self.version)).await?);
+            AuditLog::record_update(&self, &budget).await?;
+
+            BudgetUpdate::new(self, budget.clone()).fire().await?;
+
+            Ok(budget)
+        })
+        .await
     }
 
     pub(crate) async fn find_related_transactions_paginated(
@@ -109,9 +175,20 @@ impl Budget {
 
 permission_impl!(Budget);
 
+// the owner is not embedded, users are not readable by everyone the budget is shared with
+impl Expandable for Budget {
+    const RELATIONS: &'static [&'static str] = &[];
+
+    fn related(&mut self, _name: &str) -> Option<Related<'_>> {
+        None
+    }
+}
+
+impl Shareable for Budget {}
+
 impl Identifiable for Budget {
     async fn find_by_id(id: Snowflake) -> Result<Self, ApiError> {
-        Ok(Self::from(find_one_or_error(budget::Entity::find_by_id(id)).await?))
+        Ok(Self::from(find_one_or_error(budget::Entity::find_by_id_not_trashed(id)).await?))
     }
 }
 
@@ -127,6 +204,12 @@ impl WrapperEntity for Budget {
     }
 }
 
+impl Versioned for Budget {
+    fn version(&self) -> i32 {
+        self.version
+    }
+}
+
 impl From<budget::Model> for Budget {
     fn from(model: budget::Model) -> Self {
         Self {
@@ -136,6 +219,7 @@ impl From<budget::Model> for Budget {
             name: model.name,
             description: model.description,
             created_at: model.created_at,
+            version: model.version,
         }
     }
 }
@@ -13,6 +13,7 @@ use utility::snowflake::entity::Snowflake;
 use crate::api::error::api::ApiError;
 use crate::api::pagination::PageSizeParam;
 use crate::database::entity::{count, delete, find_all_paginated, find_one_or_error, insert, update};
+use crate::wrapper::entity::audit::AuditLog;
 use crate::wrapper::entity::currency::dto::CurrencyDTO;
 use crate::wrapper::entity::user::User;
 use crate::wrapper::entity::{TableName, WrapperEntity};
@@ -50,6 +51,7 @@ impl Currency {
         };
         let model = insert(currency).await?;
         let currency = Self::from(model);
+        AuditLog::record_creation(&currency).await?;
         currency.add_permission(user_id, Permissions::all()).await?;
 
         Ok(currency)
@@ -57,6 +59,7 @@ impl Currency {
 
     pub(crate) async fn delete(self) -> Result<(), ApiError> {
         delete(currency::Entity::delete_by_id(self.snowflake.id)).await?;
+        AuditLog::record_deletion(&self).await?;
 
         Ok(())
     }
@@ -93,7 +96,7 @@ impl Currency {
     }
 
     pub(crate) async fn update(self, update_dto: CurrencyDTO) -> Result<Self, ApiError> {
-        let user_option = self.user.map(|user| user.get_id());
+        let user_option = self.user.as_ref().map(|user| user.get_id());
         let active_model = currency::ActiveModel {
             id: Set(self.snowflake.id),
             name: Set(update_dto.name),
@@ -102,9 +105,10 @@ impl Currency {
             decimal_places: Set(update_dto.decimal_places),
             user: Set(user_option.map(|user_id| user_id.id)),
         };
-        let model = update(active_model).await?;
+        let currency = Self::from(update(active_model).await?);
+        AuditLog::record_update(&self, &currency).await?;
 
-        Ok(Self::from(model))
+        Ok(currency)
     }
 }
 
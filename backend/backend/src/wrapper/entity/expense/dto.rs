@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::wrapper::entity::account::Account;
+use crate::wrapper::entity::expense::split::SplitType;
+use crate::wrapper::entity::group::Group;
+use crate::wrapper::entity::transaction::Transaction;
+use crate::wrapper::entity::user::User;
+use crate::wrapper::types::phantom::Phantom;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
+pub(crate) struct ExpenseDTO {
+    pub(crate) transaction_id: Phantom<Transaction>,
+    pub(crate) group_id: Phantom<Group>,
+    pub(crate) split_type: SplitType,
+    #[validate(length(min = 1), nested)]
+    pub(crate) shares: Vec<ExpenseShareDTO>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
+pub(crate) struct ExpenseShareDTO {
+    pub(crate) user_id: Phantom<User>,
+    #[validate(range(min = 0))]
+    pub(crate) value: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
+pub(crate) struct SettlementDTO {
+    pub(crate) payee_id: Phantom<User>,
+    #[validate(range(min = 1))]
+    pub(crate) amount: i64,
+    pub(crate) source_id: Phantom<Account>,
+    pub(crate) destination_id: Phantom<Account>,
+}
@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use entity::{expense, expense_share, settlement};
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::error::api::ApiError;
+use crate::database::entity::find_all;
+use crate::wrapper::entity::currency::Currency;
+use crate::wrapper::entity::group::member::GroupMember;
+use crate::wrapper::entity::group::Group;
+use crate::wrapper::entity::user::User;
+use crate::wrapper::types::phantom::Phantom;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct Balance {
+    pub(crate) user_id: Phantom<User>,
+    pub(crate) currency_id: Phantom<Currency>,
+    pub(crate) amount: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct Debt {
+    pub(crate) debtor_id: Phantom<User>,
+    pub(crate) creditor_id: Phantom<User>,
+    pub(crate) currency_id: Phantom<Currency>,
+    pub(crate) amount: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct GroupBalances {
+    pub(crate) balances: Vec<Balance>,
+    pub(crate) debts: Vec<Debt>,
+}
+
+// a positive amount means that the counterparty owes you money
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct CounterpartyDebt {
+    pub(crate) user_id: Phantom<User>,
+    pub(crate) group_id: Phantom<Group>,
+    pub(crate) currency_id: Phantom<Currency>,
+    pub(crate) amount: i64,
+}
+
+struct LedgerEntry {
+    debtor: i64,
+    creditor: i64,
+    currency: i64,
+    amount: i64,
+}
+
+pub(crate) struct Ledger {
+    group_id: Snowflake,
+    entries: Vec<LedgerEntry>,
+}
+
+impl Ledger {
+    pub(crate) async fn load(group_id: Snowflake) -> Result<Self, ApiError> {
+        let expenses: HashMap<i64, expense::Model> = find_all(expense::Entity::find_all_by_group_id(group_id))
+            .await?
+            .into_iter()
+            .map(|expense| (expense.id, expense))
+            .collect();
+        let shares = find_all(expense_share::Entity::find_all_by_group_id(group_id)).await?;
+        let settlements = find_all(settlement::Entity::find_all_by_group_id(group_id)).await?;
+
+        let mut entries = Vec::with_capacity(shares.len() + settlements.len());
+        for share in shares {
+            if let Some(expense) = expenses.get(&share.expense) {
+                if share.user != expense.payer {
+                    entries.push(LedgerEntry {
+                        debtor: share.user,
+                        creditor: expense.payer,
+                        currency: expense.currency,
+                        amount: share.amount,
+                    });
+                }
+            }
+        }
+        // a settle-up payment is booked as a debt in the opposite direction
+        for settlement in settlements {
+            entries.push(LedgerEntry {
+                debtor: settlement.payee,
+                creditor: settlement.payer,
+                currency: settlement.currency,
+                amount: settlement.amount,
+            });
+        }
+
+        Ok(Self {
+            group_id,
+            entries,
+        })
+    }
+
+    pub(crate) async fn find_counterparty_debts_of_user(user_id: Snowflake) -> Result<Vec<CounterpartyDebt>, ApiError> {
+        let mut debts = Vec::new();
+        for membership in GroupMember::find_all_by_user_id(user_id).await? {
+            let ledger = Self::load(membership.group_id.get_id()).await?;
+            debts.extend(ledger.counterparty_debts(user_id));
+        }
+
+        Ok(debts)
+    }
+
+    pub(crate) fn group_balances(&self) -> GroupBalances {
+        GroupBalances {
+            balances: self.balances(),
+            debts: self.simplified_debts(),
+        }
+    }
+
+    pub(crate) fn balances(&self) -> Vec<Balance> {
+        let mut balances: HashMap<(i64, i64), i64> = HashMap::new();
+        for entry in &self.entries {
+            *balances.entry((entry.creditor, entry.currency)).or_default() += entry.amount;
+            *balances.entry((entry.debtor, entry.currency)).or_default() -= entry.amount;
+        }
+
+        let mut balances: Vec<Balance> = balances
+            .into_iter()
+            .filter(|(_, amount)| *amount != 0)
+            .map(|((user, currency), amount)| Balance {
+                user_id: Phantom::from(user),
+                currency_id: Phantom::from(currency),
+                amount,
+            })
+            .collect();
+        balances.sort_by_key(|balance| (balance.currency_id.get_id().id, -balance.amount, balance.user_id.get_id().id));
+
+        balances
+    }
+
+    // greedily matches the largest creditor with the largest debtor which needs at most n - 1 payments
+    pub(crate) fn simplified_debts(&self) -> Vec<Debt> {
+        let mut by_currency: HashMap<i64, Vec<(i64, i64)>> = HashMap::new();
+        for balance in self.balances() {
+            by_currency
+                .entry(balance.currency_id.get_id().id)
+                .or_default()
+                .push((balance.user_id.get_id().id, balance.amount));
+        }
+
+        let mut currencies: Vec<i64> = by_currency.keys().copied().collect();
+        currencies.sort_unstable();
+
+        let mut debts = Vec::new();
+        for currency in currencies {
+            let balances = by_currency.remove(&currency).unwrap_or_default();
+            let mut creditors: Vec<(i64, i64)> = balances.iter().copied().filter(|(_, amount)| *amount > 0).collect();
+            let mut debtors: Vec<(i64, i64)> =
+                balances.iter().map(|(user, amount)| (*user, -amount)).filter(|(_, amount)| *amount > 0).collect();
+            creditors.sort_by_key(|(user, amount)| (-amount, *user));
+            debtors.sort_by_key(|(user, amount)| (-amount, *user));
+
+            let (mut creditor_index, mut debtor_index) = (0, 0);
+            while let (Some(creditor), Some(debtor)) =
+                (creditors.get_mut(creditor_index), debtors.get_mut(debtor_index))
+            {
+                let amount = creditor.1.min(debtor.1);
+                debts.push(Debt {
+                    debtor_id: Phantom::from(debtor.0),
+                    creditor_id: Phantom::from(creditor.0),
+                    currency_id: Phantom::from(currency),
+                    amount,
+                });
+
+                creditor.1 -= amount;
+                debtor.1 -= amount;
+                if creditor.1 == 0 {
+                    creditor_index += 1;
+                }
+                if debtor.1 == 0 {
+                    debtor_index += 1;
+                }
+            }
+        }
+
+        debts
+    }
+
+    pub(crate) fn counterparty_debts(&self, user_id: Snowflake) -> Vec<CounterpartyDebt> {
+        let mut debts: HashMap<(i64, i64), i64> = HashMap::new();
+        for entry in &self.entries {
+            if entry.creditor == user_id.id {
+                *debts.entry((entry.debtor, entry.currency)).or_default() += entry.amount;
+            } else if entry.debtor == user_id.id {
+                *debts.entry((entry.creditor, entry.currency)).or_default() -= entry.amount;
+            }
+        }
+
+        let mut debts: Vec<CounterpartyDebt> = debts
+            .into_iter()
+            .filter(|(_, amount)| *amount != 0)
+            .map(|((user, currency), amount)| CounterpartyDebt {
+                user_id: Phantom::from(user),
+                group_id: Phantom::from(self.group_id),
+                currency_id: Phantom::from(currency),
+                amount,
+            })
+            .collect();
+        debts.sort_by_key(|debt| (debt.user_id.get_id().id, debt.currency_id.get_id().id));
+
+        debts
+    }
+}
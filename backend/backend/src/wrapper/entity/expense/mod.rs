@@ -0,0 +1,177 @@
+use std::collections::HashSet;
+
+use sea_orm::{EntityName, EntityTrait, QuerySelect, Set};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+use entity::utility::time::get_now;
+use entity::{expense, expense_share, transaction};
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::error::api::ApiError;
+use crate::api::pagination::PageSizeParam;
+use crate::database::entity::{count, delete, find_all, find_all_paginated, find_one_or_error, insert};
+use crate::database::transaction::in_transaction;
+use crate::wrapper::entity::currency::Currency;
+use crate::wrapper::entity::expense::dto::ExpenseDTO;
+use crate::wrapper::entity::expense::split::SplitType;
+use crate::wrapper::entity::group::member::GroupMember;
+use crate::wrapper::entity::group::Group;
+use crate::wrapper::entity::transaction::Transaction;
+use crate::wrapper::entity::user::User;
+use crate::wrapper::entity::{TableName, WrapperEntity};
+use crate::wrapper::types::phantom::{Identifiable, Phantom};
+use crate::SNOWFLAKE_GENERATOR;
+
+pub(crate) mod dto;
+pub(crate) mod ledger;
+pub(crate) mod settlement;
+pub(crate) mod split;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct Expense {
+    #[serde(rename = "id")]
+    pub(crate) snowflake: Snowflake,
+    pub(crate) transaction_id: Phantom<Transaction>,
+    pub(crate) group_id: Phantom<Group>,
+    pub(crate) payer_id: Phantom<User>,
+    pub(crate) amount: i64,
+    pub(crate) currency_id: Phantom<Currency>,
+    pub(crate) split_type: SplitType,
+    pub(crate) shares: Vec<ExpenseShare>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub(crate) created_at: OffsetDateTime,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct ExpenseShare {
+    pub(crate) user_id: Phantom<User>,
+    pub(crate) value: i64,
+    pub(crate) amount: i64,
+}
+
+impl Expense {
+    pub(crate) async fn new(payer_id: Snowflake, dto: ExpenseDTO) -> Result<Self, ApiError> {
+        let transaction = dto.transaction_id.fetch_inner().await?;
+
+        in_transaction(async move {
+            // a concurrent split of the same transaction waits for this one and then sees it
+            find_one_or_error(transaction::Entity::find_by_id(transaction.snowflake.id).lock_exclusive()).await?;
+            if count(expense::Entity::find_by_transaction_id(transaction.snowflake)).await? > 0 {
+                return Err(ApiError::InvalidExpense("The transaction has already been split!"));
+            }
+
+            let members: HashSet<Snowflake> = GroupMember::find_all_by_group_id(dto.group_id.get_id())
+                .await?
+                .into_iter()
+                .map(|member| member.user_id.get_id())
+                .collect();
+            let mut participants = HashSet::new();
+            for share in &dto.shares {
+                if !members.contains(&share.user_id.get_id()) {
+                    return Err(ApiError::InvalidExpense("Expenses can only be split between members of the group!"));
+                }
+                if !participants.insert(share.user_id.get_id()) {
+                    return Err(ApiError::InvalidExpense("Every user can only be part of a split once!"));
+                }
+            }
+
+            let values: Vec<i64> = dto.shares.iter().map(|share| share.value).collect();
+            let amounts = dto.split_type.split(transaction.amount, &values)?;
+
+            let snowflake = SNOWFLAKE_GENERATOR.next_id()?;
+            let model = expense::ActiveModel {
+                id: Set(snowflake),
+                transaction: Set(transaction.snowflake.id),
+                group: Set(dto.group_id.get_id().id),
+                payer: Set(payer_id.id),
+                amount: Set(transaction.amount),
+                currency: Set(transaction.currency_id.get_id().id),
+                split_type: Set(dto.split_type.as_str().to_string()),
+                created_at: Set(get_now()),
+            };
+            let model = insert(model).await?;
+
+            let mut shares = Vec::with_capacity(dto.shares.len());
+            for (share, amount) in dto.shares.into_iter().zip(amounts) {
+                let share_model = expense_share::ActiveModel {
+                    expense: Set(snowflake),
+                    user: Set(share.user_id.get_id().id),
+                    value: Set(share.value),
+                    amount: Set(amount),
+                };
+                shares.push(insert(share_model).await?);
+            }
+
+            Self::from_models(model, shares)
+        })
+        .await
+    }
+
+    pub(crate) async fn delete(self) -> Result<(), ApiError> {
+        delete(expense::Entity::delete_by_id(self.snowflake.id)).await
+    }
+
+    pub(crate) async fn find_all_by_group_paginated(
+        group_id: Snowflake,
+        page_size: &PageSizeParam,
+    ) -> Result<Vec<Self>, ApiError> {
+        let models = find_all_paginated(expense::Entity::find_all_by_group_id(group_id), page_size).await?;
+        let mut expenses = Vec::with_capacity(models.len());
+        for model in models {
+            expenses.push(Self::with_shares(model).await?);
+        }
+
+        Ok(expenses)
+    }
+
+    pub(crate) async fn count_all_by_group(group_id: Snowflake) -> Result<u64, ApiError> {
+        count(expense::Entity::find_all_by_group_id(group_id)).await
+    }
+
+    async fn with_shares(model: expense::Model) -> Result<Self, ApiError> {
+        let shares = find_all(expense_share::Entity::find_all_by_expense_id(Snowflake::from(model.id))).await?;
+
+        Self::from_models(model, shares)
+    }
+
+    fn from_models(model: expense::Model, shares: Vec<expense_share::Model>) -> Result<Self, ApiError> {
+        Ok(Self {
+            snowflake: Snowflake::from(model.id),
+            transaction_id: Phantom::from(model.transaction),
+            group_id: Phantom::from(model.group),
+            payer_id: Phantom::from(model.payer),
+            amount: model.amount,
+            currency_id: Phantom::from(model.currency),
+            split_type: SplitType::try_from(model.split_type.as_str())?,
+            shares: shares
+                .into_iter()
+                .map(|share| ExpenseShare {
+                    user_id: Phantom::from(share.user),
+                    value: share.value,
+                    amount: share.amount,
+                })
+                .collect(),
+            created_at: model.created_at,
+        })
+    }
+}
+
+impl Identifiable for Expense {
+    async fn find_by_id(id: Snowflake) -> Result<Self, ApiError> {
+        Self::with_shares(find_one_or_error(expense::Entity::find_by_id(id)).await?).await
+    }
+}
+
+impl TableName for Expense {
+    fn table_name() -> &'static str {
+        expense::Entity.table_name()
+    }
+}
+
+impl WrapperEntity for Expense {
+    fn get_id(&self) -> Snowflake {
+        self.snowflake
+    }
+}
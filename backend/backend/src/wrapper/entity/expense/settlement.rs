@@ -0,0 +1,101 @@
+use sea_orm::Set;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+use entity::settlement;
+use entity::utility::time::get_now;
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::error::api::ApiError;
+use crate::database::entity::insert;
+use crate::database::transaction::in_transaction;
+use crate::wrapper::entity::account::Account;
+use crate::wrapper::entity::currency::Currency;
+use crate::wrapper::entity::expense::dto::SettlementDTO;
+use crate::wrapper::entity::group::Group;
+use crate::wrapper::entity::transaction::dto::TransactionDTO;
+use crate::wrapper::entity::transaction::Transaction;
+use crate::wrapper::entity::user::User;
+use crate::wrapper::permission::{HasPermissionOrError, PermissionByIds, Permissions};
+use crate::wrapper::types::phantom::Phantom;
+use crate::SNOWFLAKE_GENERATOR;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct Settlement {
+    #[serde(rename = "id")]
+    pub(crate) snowflake: Snowflake,
+    pub(crate) group_id: Phantom<Group>,
+    pub(crate) payer_id: Phantom<User>,
+    pub(crate) payee_id: Phantom<User>,
+    pub(crate) amount: i64,
+    pub(crate) currency_id: Phantom<Currency>,
+    pub(crate) transaction_id: Phantom<Transaction>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub(crate) created_at: OffsetDateTime,
+}
+
+impl Settlement {
+    // the payment is booked as a real transfer from the payer's to the payee's account
+    pub(crate) async fn new(group: &Group, payer_id: Snowflake, dto: SettlementDTO) -> Result<Self, ApiError> {
+        in_transaction(async move {
+            let payee_id = dto.payee_id.get_id();
+            if payee_id == payer_id {
+                return Err(ApiError::InvalidExpense("You can not settle up with yourself!"));
+            }
+            group.membership_or_error(payee_id).await?;
+
+            dto.source_id.has_permission_or_error(payer_id, Permissions::READ_WRITE).await?;
+            if !Account::has_permission_by_id(dto.destination_id.get_id(), payee_id, Permissions::READ_WRITE).await? {
+                return Err(ApiError::InvalidExpense("The destination account has to belong to the payee!"));
+            }
+
+            let source = dto.source_id.fetch_inner().await?;
+            let destination = dto.destination_id.fetch_inner().await?;
+            if source.currency_id.get_id() != destination.currency_id.get_id() {
+                return Err(ApiError::InvalidExpense("Both accounts need to use the same currency!"));
+            }
+
+            let transaction = Transaction::new(TransactionDTO {
+                source_id: Some(dto.source_id),
+                destination_id: Some(dto.destination_id),
+                amount: dto.amount,
+                currency_id: source.currency_id.clone(),
+                name: format!("Settle-up in {}", group.name),
+                description: None,
+                budget_id: None,
+                executed_at: get_now(),
+            })
+            .await?;
+
+            let model = settlement::ActiveModel {
+                id: Set(SNOWFLAKE_GENERATOR.next_id()?),
+                group: Set(group.snowflake.id),
+                payer: Set(payer_id.id),
+                payee: Set(payee_id.id),
+                amount: Set(dto.amount),
+                currency: Set(source.currency_id.get_id().id),
+                transaction: Set(transaction.snowflake.id),
+                created_at: Set(get_now()),
+            };
+
+            Ok(Self::from(insert(model).await?))
+        })
+        .await
+    }
+}
+
+impl From<settlement::Model> for Settlement {
+    fn from(value: settlement::Model) -> Self {
+        Self {
+            snowflake: Snowflake::from(value.id),
+            group_id: Phantom::from(value.group),
+            payer_id: Phantom::from(value.payer),
+            payee_id: Phantom::from(value.payee),
+            amount: value.amount,
+            currency_id: Phantom::from(value.currency),
+            transaction_id: Phantom::from(value.transaction),
+            created_at: value.created_at,
+        }
+    }
+}
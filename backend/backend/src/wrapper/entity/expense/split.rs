@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use entity::error::EntityError;
+
+use crate::api::error::api::ApiError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SplitType {
+    Shares,
+    Percentages,
+    Exact,
+}
+
+impl SplitType {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Shares => "shares",
+            Self::Percentages => "percentages",
+            Self::Exact => "exact",
+        }
+    }
+
+    pub(crate) fn split(&self, amount: i64, values: &[i64]) -> Result<Vec<i64>, ApiError> {
+        let total: i64 = values.iter().sum();
+        match self {
+            Self::Exact if total != amount => {
+                Err(ApiError::InvalidExpense("The exact amounts must add up to the amount!"))
+            }
+            Self::Exact => Ok(values.to_vec()),
+            Self::Percentages if total != 100 => Err(ApiError::InvalidExpense("The percentages must add up to 100!")),
+            Self::Shares if total <= 0 => Err(ApiError::InvalidExpense("At least one share is required!")),
+            Self::Percentages | Self::Shares => Ok(split_weighted(amount, values, total)),
+        }
+    }
+}
+
+impl TryFrom<&str> for SplitType {
+    type Error = EntityError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "shares" => Ok(Self::Shares),
+            "percentages" => Ok(Self::Percentages),
+            "exact" => Ok(Self::Exact),
+            _ => Err(EntityError::UnknownValue("split type", value.to_string())),
+        }
+    }
+}
+
+// uses the largest remainder method so that the parts always add up to the amount
+fn split_weighted(amount: i64, weights: &[i64], total: i64) -> Vec<i64> {
+    let amount = amount as i128;
+    let total = total as i128;
+    let mut parts: Vec<i64> = weights.iter().map(|weight| (amount * *weight as i128 / total) as i64).collect();
+
+    let mut remainders: Vec<(usize, i128)> =
+        weights.iter().enumerate().map(|(index, weight)| (index, amount * *weight as i128 % total)).collect();
+    remainders.sort_by(|(a_index, a), (b_index, b)| b.cmp(a).then(a_index.cmp(b_index)));
+
+    let distributed: i64 = parts.iter().sum();
+    let leftover = amount as i64 - distributed;
+    for (index, _) in remainders.into_iter().take(leftover.max(0) as usize) {
+        parts[index] += 1;
+    }
+
+    parts
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::wrapper::entity::group::member::GroupRole;
+use crate::wrapper::entity::group::Group;
+use crate::wrapper::permission::collaborator::validation::validate_permissions_contain_read;
+use crate::wrapper::permission::Permissions;
+use crate::wrapper::types::phantom::Phantom;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
+pub(crate) struct GroupDTO {
+    #[validate(length(min = 1, max = 255))]
+    pub(crate) name: String,
+    #[validate(length(min = 0, max = 255))]
+    pub(crate) description: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
+pub(crate) struct GroupMemberDTO {
+    #[validate(length(min = 1))]
+    pub(crate) username: String,
+    pub(crate) role: GroupRole,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
+pub(crate) struct GroupGrantDTO {
+    pub(crate) group_id: Phantom<Group>,
+    #[validate(custom(function = "validate_permissions_contain_read"))]
+    pub(crate) permissions: Permissions,
+}
@@ -0,0 +1,112 @@
+use sea_orm::{EntityTrait, Set};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+use entity::error::EntityError;
+use entity::group_member;
+use entity::utility::time::get_now;
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::error::api::ApiError;
+use crate::database::entity::{delete, find_all, find_one, insert, update};
+use crate::wrapper::entity::group::Group;
+use crate::wrapper::entity::user::User;
+use crate::wrapper::permission::Permissions;
+use crate::wrapper::types::phantom::Phantom;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum GroupRole {
+    Owner,
+    Member,
+    Viewer,
+}
+
+impl GroupRole {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Owner => "owner",
+            Self::Member => "member",
+            Self::Viewer => "viewer",
+        }
+    }
+
+    // viewers only ever get read access to what has been granted to their group
+    pub(crate) fn restrict(&self, permissions: Permissions) -> Permissions {
+        match self {
+            Self::Owner | Self::Member => permissions,
+            Self::Viewer => permissions & Permissions::READ,
+        }
+    }
+}
+
+impl TryFrom<&str> for GroupRole {
+    type Error = EntityError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "owner" => Ok(Self::Owner),
+            "member" => Ok(Self::Member),
+            "viewer" => Ok(Self::Viewer),
+            _ => Err(EntityError::UnknownValue("group role", value.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct GroupMember {
+    pub(crate) group_id: Phantom<Group>,
+    pub(crate) user_id: Phantom<User>,
+    pub(crate) role: GroupRole,
+    #[serde(with = "time::serde::rfc3339")]
+    pub(crate) joined_at: OffsetDateTime,
+}
+
+impl GroupMember {
+    pub(crate) async fn find(group_id: Snowflake, user_id: Snowflake) -> Result<Option<Self>, ApiError> {
+        find_one(group_member::Entity::find_membership(group_id, user_id)).await?.map(Self::try_from).transpose()
+    }
+
+    pub(crate) async fn find_all_by_group_id(group_id: Snowflake) -> Result<Vec<Self>, ApiError> {
+        find_all(group_member::Entity::find_all_by_group_id(group_id)).await?.into_iter().map(Self::try_from).collect()
+    }
+
+    pub(crate) async fn find_all_by_user_id(user_id: Snowflake) -> Result<Vec<Self>, ApiError> {
+        find_all(group_member::Entity::find_all_by_user_id(user_id)).await?.into_iter().map(Self::try_from).collect()
+    }
+
+    pub(crate) async fn save(group_id: Snowflake, user_id: Snowflake, role: GroupRole) -> Result<Self, ApiError> {
+        let existing = Self::find(group_id, user_id).await?;
+        let active_model = group_member::ActiveModel {
+            group_id: Set(group_id.id),
+            user_id: Set(user_id.id),
+            role: Set(role.as_str().to_string()),
+            joined_at: Set(existing.as_ref().map_or_else(get_now, |member| member.joined_at)),
+        };
+
+        let model = match existing {
+            Some(_) => update(active_model).await?,
+            None => insert(active_model).await?,
+        };
+
+        Self::try_from(model)
+    }
+
+    pub(crate) async fn delete(self) -> Result<(), ApiError> {
+        delete(group_member::Entity::delete_by_id((self.group_id.get_id().id, self.user_id.get_id().id))).await
+    }
+}
+
+impl TryFrom<group_member::Model> for GroupMember {
+    type Error = ApiError;
+
+    fn try_from(value: group_member::Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            group_id: Phantom::from(value.group_id),
+            user_id: Phantom::from(value.user_id),
+            role: GroupRole::try_from(value.role.as_str())?,
+            joined_at: value.joined_at,
+        })
+    }
+}
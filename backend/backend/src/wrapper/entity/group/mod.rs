@@ -0,0 +1,191 @@
+use sea_orm::{EntityName, EntityTrait, Set};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+use entity::group;
+use entity::permissions;
+use entity::utility::time::get_now;
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::error::api::ApiError;
+use crate::api::pagination::PageSizeParam;
+use crate::database::entity::{count, delete, find_all, find_all_paginated, find_one_or_error, insert, update};
+use crate::database::transaction::in_transaction;
+use crate::wrapper::entity::audit::AuditLog;
+use crate::wrapper::entity::group::dto::{GroupDTO, GroupMemberDTO};
+use crate::wrapper::entity::group::member::{GroupMember, GroupRole};
+use crate::wrapper::entity::user::User;
+use crate::wrapper::entity::{TableName, WrapperEntity};
+use crate::wrapper::permission::Permissions;
+use crate::wrapper::types::phantom::Identifiable;
+use crate::SNOWFLAKE_GENERATOR;
+
+pub(crate) mod dto;
+pub(crate) mod member;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct Group {
+    #[serde(rename = "id")]
+    pub(crate) snowflake: Snowflake,
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub(crate) created_at: OffsetDateTime,
+}
+
+impl Group {
+    pub(crate) async fn new(user_id: Snowflake, dto: GroupDTO) -> Result<Self, ApiError> {
+        let snowflake = SNOWFLAKE_GENERATOR.next_id()?;
+        let model = group::ActiveModel {
+            id: Set(snowflake),
+            name: Set(dto.name),
+            description: Set(dto.description),
+            created_at: Set(get_now()),
+        };
+
+        let group = Self::from(insert(model).await?);
+        GroupMember::save(group.snowflake, user_id, GroupRole::Owner).await?;
+
+        Ok(group)
+    }
+
+    pub(crate) async fn find_all_by_user_paginated(
+        user_id: Snowflake,
+        page_size: &PageSizeParam,
+    ) -> Result<Vec<Self>, ApiError> {
+        Ok(find_all_paginated(group::Entity::find_all_by_user_id(user_id), page_size)
+            .await?
+            .into_iter()
+            .map(Self::from)
+            .collect())
+    }
+
+    pub(crate) async fn count_all_by_user(user_id: Snowflake) -> Result<u64, ApiError> {
+        count(group::Entity::find_all_by_user_id(user_id)).await
+    }
+
+    pub(crate) async fn update(self, dto: GroupDTO) -> Result<Self, ApiError> {
+        let model = group::ActiveModel {
+            id: Set(self.snowflake.id),
+            name: Set(dto.name),
+            description: Set(dto.description),
+            created_at: Set(self.created_at),
+        };
+
+        Ok(Self::from(update(model).await?))
+    }
+
+    pub(crate) async fn delete(self) -> Result<(), ApiError> {
+        in_transaction(async move {
+            let members: Vec<Snowflake> =
+                self.find_members().await?.into_iter().map(|member| member.user_id.get_id()).collect();
+            self.record_revocations(&members).await?;
+
+            delete(group::Entity::delete_by_id(self.snowflake.id)).await
+        })
+        .await
+    }
+
+    pub(crate) async fn find_all_by_ids(ids: Vec<Snowflake>) -> Result<Vec<Self>, ApiError> {
+        let ids = ids.into_iter().map(|id| id.id).collect();
+
+        Ok(find_all(group::Entity::find_all_by_ids(ids)).await?.into_iter().map(Self::from).collect())
+    }
+
+    pub(crate) async fn find_members(&self) -> Result<Vec<GroupMember>, ApiError> {
+        GroupMember::find_all_by_group_id(self.snowflake).await
+    }
+
+    // non-members must not learn about the existence of the group
+    pub(crate) async fn membership_or_error(&self, user_id: Snowflake) -> Result<GroupMember, ApiError> {
+        GroupMember::find(self.snowflake, user_id).await?.ok_or_else(|| ApiError::ResourceNotFound(Self::table_name()))
+    }
+
+    pub(crate) async fn has_role_or_error(&self, user_id: Snowflake, roles: &[GroupRole]) -> Result<(), ApiError> {
+        let membership = self.membership_or_error(user_id).await?;
+        if !roles.contains(&membership.role) {
+            return Err(ApiError::MissingPermissions());
+        }
+
+        Ok(())
+    }
+
+    pub(crate) async fn set_member(&self, dto: GroupMemberDTO) -> Result<GroupMember, ApiError> {
+        let user = User::find_by_username(&dto.username).await?;
+        if dto.role != GroupRole::Owner {
+            self.assert_owner_remains(user.snowflake).await?;
+        }
+
+        GroupMember::save(self.snowflake, user.snowflake, dto.role).await
+    }
+
+    pub(crate) async fn remove_member(&self, user_id: Snowflake) -> Result<(), ApiError> {
+        in_transaction(async {
+            let membership = self.membership_or_error(user_id).await?;
+            self.assert_owner_remains(user_id).await?;
+            self.record_revocations(&[user_id]).await?;
+
+            membership.delete().await
+        })
+        .await
+    }
+
+    // the users lose everything that was granted to the group
+    async fn record_revocations(&self, user_ids: &[Snowflake]) -> Result<(), ApiError> {
+        let grants = find_all(permissions::Entity::find_all_by_group_id(self.snowflake)).await?;
+        for grant in grants {
+            if !Permissions::from(grant.clone()).contains(Permissions::READ) {
+                continue;
+            }
+            for user_id in user_ids {
+                AuditLog::record_revocation(&grant.entity_type, Snowflake::from(grant.entity_id), *user_id).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn assert_owner_remains(&self, user_id: Snowflake) -> Result<(), ApiError> {
+        let other_owners = self
+            .find_members()
+            .await?
+            .into_iter()
+            .filter(|member| member.user_id.get_id() != user_id && member.role == GroupRole::Owner)
+            .count();
+        if other_owners == 0 {
+            return Err(ApiError::LastOwner());
+        }
+
+        Ok(())
+    }
+}
+
+impl Identifiable for Group {
+    async fn find_by_id(id: Snowflake) -> Result<Self, ApiError> {
+        find_one_or_error(group::Entity::find_by_id(id)).await.map(Self::from)
+    }
+}
+
+impl TableName for Group {
+    fn table_name() -> &'static str {
+        group::Entity.table_name()
+    }
+}
+
+impl WrapperEntity for Group {
+    fn get_id(&self) -> Snowflake {
+        self.snowflake
+    }
+}
+
+impl From<group::Model> for Group {
+    fn from(value: group::Model) -> Self {
+        Self {
+            snowflake: Snowflake::from(value.id),
+            name: value.name,
+            description: value.description,
+            created_at: value.created_at,
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::wrapper::entity::invitation::Invitation;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
+pub(crate) struct InvitationDTO {
+    /// The code can be used any number of times if this is not set.
+    #[validate(range(min = 1))]
+    pub(crate) max_uses: Option<i32>,
+    /// The code never expires if this is not set.
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub(crate) expires_at: Option<OffsetDateTime>,
+}
+
+/// The only time the plain code is returned, it can not be retrieved again.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct CreatedInvitation {
+    pub(crate) code: String,
+    pub(crate) invitation: Invitation,
+}
@@ -0,0 +1,121 @@
+use sea_orm::{EntityName, EntityTrait, Set};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+use entity::invitation;
+use entity::utility::hashing::{generate_salt, hash_token};
+use entity::utility::time::get_now;
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::error::api::ApiError;
+use crate::api::pagination::PageSizeParam;
+use crate::database::entity::{count, delete, find_all_paginated, find_one_or_error, insert, update_many};
+use crate::database::transaction::in_transaction;
+use crate::wrapper::entity::audit::AuditLog;
+use crate::wrapper::entity::invitation::dto::{CreatedInvitation, InvitationDTO};
+use crate::wrapper::entity::user::User;
+use crate::wrapper::entity::{TableName, WrapperEntity};
+use crate::wrapper::types::phantom::{Identifiable, Phantom};
+use crate::SNOWFLAKE_GENERATOR;
+
+pub(crate) mod dto;
+
+const CODE_LENGTH: usize = 24;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct Invitation {
+    #[serde(rename = "id")]
+    pub(crate) snowflake: Snowflake,
+    pub(crate) created_by: Option<Phantom<User>>,
+    pub(crate) max_uses: Option<i32>,
+    pub(crate) uses: i32,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub(crate) expires_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub(crate) created_at: OffsetDateTime,
+}
+
+impl Invitation {
+    pub(crate) async fn create(created_by: Snowflake, dto: InvitationDTO) -> Result<CreatedInvitation, ApiError> {
+        let code = generate_salt(CODE_LENGTH);
+        let active_model = invitation::ActiveModel {
+            id: Set(SNOWFLAKE_GENERATOR.next_id()?),
+            code_hash: Set(hash_token(&code)),
+            created_by: Set(Some(created_by.id)),
+            max_uses: Set(dto.max_uses),
+            uses: Set(0),
+            expires_at: Set(dto.expires_at),
+            created_at: Set(get_now()),
+        };
+        in_transaction(async move {
+            let invitation = Self::from(insert(active_model).await?);
+            AuditLog::record_creation(&invitation).await?;
+
+            Ok(CreatedInvitation {
+                code,
+                invitation,
+            })
+        })
+        .await
+    }
+
+    /// Uses the code once, fails if it does not exist, expired or is used up.
+    pub(crate) async fn consume(code: &str) -> Result<(), ApiError> {
+        if update_many(invitation::Entity::consume(code, get_now())).await? == 0 {
+            return Err(ApiError::InvalidInvitationCode());
+        }
+
+        Ok(())
+    }
+
+    pub(crate) async fn delete(self) -> Result<(), ApiError> {
+        in_transaction(async move {
+            AuditLog::record_deletion(&self).await?;
+            delete(invitation::Entity::delete_by_id(self.snowflake.id)).await
+        })
+        .await
+    }
+
+    pub(crate) async fn find_all_paginated(page_size: &PageSizeParam) -> Result<Vec<Self>, ApiError> {
+        Ok(find_all_paginated(invitation::Entity::find_all(), page_size).await?.into_iter().map(Self::from).collect())
+    }
+
+    pub(crate) async fn count_all() -> Result<u64, ApiError> {
+        count(invitation::Entity::find_all()).await
+    }
+}
+
+impl Identifiable for Invitation {
+    async fn find_by_id(id: Snowflake) -> Result<Self, ApiError>
+    where
+        Self: Sized,
+    {
+        find_one_or_error(invitation::Entity::find_by_id(id)).await.map(Self::from)
+    }
+}
+
+impl TableName for Invitation {
+    fn table_name() -> &'static str {
+        invitation::Entity.table_name()
+    }
+}
+
+impl WrapperEntity for Invitation {
+    fn get_id(&self) -> Snowflake {
+        self.snowflake
+    }
+}
+
+impl From<invitation::Model> for Invitation {
+    fn from(value: invitation::Model) -> Self {
+        Self {
+            snowflake: Snowflake::from(value.id),
+            created_by: Phantom::from_option(value.created_by),
+            max_uses: value.max_uses,
+            uses: value.uses,
+            expires_at: value.expires_at,
+            created_at: value.created_at,
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::api::error::api::ApiError;
+use crate::config::Config;
+use crate::database::redis::{del, expire, incr, set_ex, ttl};
+use crate::util::client::ClientInfo;
+use crate::wrapper::entity::login_attempt::LoginAttempt;
+use crate::wrapper::entity::user::dto::Credentials;
+use crate::wrapper::entity::user::User;
+
+const USER_SCOPE: &str = "user";
+const IP_SCOPE: &str = "ip";
+// keeps the doubling from overflowing, the result is capped by the config anyway
+const MAX_BACKOFF_EXPONENT: u64 = 32;
+
+/// Failed logins per username and IP. Once the failures pass a threshold every further attempt has to wait
+/// exponentially longer, too many failures on a username lock it until it expires or an admin unlocks it.
+pub(crate) struct LoginLockout;
+
+impl LoginLockout {
+    /// Authenticates the credentials unless the username or IP is throttled and records a failed attempt. A successful
+    /// one is only registered once the login is complete, see [`LoginLockout::register_success`].
+    pub(crate) async fn authenticate(credentials: &Credentials, client: &ClientInfo) -> Result<User, ApiError> {
+        let username = credentials.username.as_str();
+        // checked before the password, so a locked account can not be guessed any further
+        if let Err(error) = Self::check(username, &client.ip).await {
+            LoginAttempt::record_failure(username, client).await?;
+            return Err(error);
+        }
+
+        let user = User::authenticate(credentials).await;
+        if user.is_err() {
+            Self::register_failure(username, client).await?;
+        }
+
+        user
+    }
+
+    /// Clears the failures of the username and records the login. Users with two-factor authentication only get here
+    /// after answering the challenge, so a correct password alone does not reset the lockout.
+    pub(crate) async fn register_success(user: &User, client: &ClientInfo) -> Result<(), ApiError> {
+        Self::reset(&user.username).await?;
+        LoginAttempt::record(user.snowflake, client, true).await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn check(username: &str, ip: &str) -> Result<(), ApiError> {
+        if let Some(seconds) = ttl(lockout_key(username)).await? {
+            return Err(ApiError::AccountLocked(seconds));
+        }
+        for key in [backoff_key(USER_SCOPE, username), backoff_key(IP_SCOPE, ip)] {
+            if let Some(seconds) = ttl(key).await? {
+                return Err(ApiError::TooManyLoginAttempts(seconds));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Counts a failed login against the username and IP and records it, whether the password or the second factor
+    /// was wrong.
+    pub(crate) async fn register_failure(username: &str, client: &ClientInfo) -> Result<(), ApiError> {
+        let config = &Config::get_config().login_lockout;
+
+        let user_failures = Self::count_failure(USER_SCOPE, username).await?;
+        if user_failures >= config.lockout_threshold {
+            set_ex(lockout_key(username), user_failures.to_string(), config.lockout_seconds).await?;
+        } else {
+            Self::back_off(USER_SCOPE, username, user_failures, config.backoff_threshold).await?;
+        }
+
+        // everyone behind an IP shares it, so it only backs off once it failed as often as would lock a user
+        let ip_failures = Self::count_failure(IP_SCOPE, &client.ip).await?;
+        Self::back_off(IP_SCOPE, &client.ip, ip_failures, config.lockout_threshold).await?;
+
+        LoginAttempt::record_failure(username, client).await
+    }
+
+    pub(crate) async fn reset(username: &str) -> Result<(), ApiError> {
+        del(failures_key(USER_SCOPE, username)).await?;
+        del(backoff_key(USER_SCOPE, username)).await
+    }
+
+    pub(crate) async fn unlock(username: &str) -> Result<(), ApiError> {
+        Self::reset(username).await?;
+        del(lockout_key(username)).await
+    }
+
+    async fn count_failure(scope: &str, value: &str) -> Result<u64, ApiError> {
+        let key = failures_key(scope, value);
+        let failures = incr(key.clone()).await?;
+        expire(key, Config::get_config().login_lockout.failure_window_seconds as i64).await?;
+
+        Ok(failures)
+    }
+
+    async fn back_off(scope: &str, value: &str, failures: u64, threshold: u64) -> Result<(), ApiError> {
+        if failures < threshold {
+            return Ok(());
+        }
+
+        let exponent = (failures - threshold + 1).min(MAX_BACKOFF_EXPONENT) as u32;
+        let seconds = 2u64.pow(exponent).min(Config::get_config().login_lockout.max_backoff_seconds);
+        if seconds == 0 {
+            return Ok(());
+        }
+
+        set_ex(backoff_key(scope, value), failures.to_string(), seconds).await
+    }
+}
+
+fn failures_key(scope: &str, value: &str) -> String {
+    format!("login_failures:{}:{}", scope, value)
+}
+
+fn backoff_key(scope: &str, value: &str) -> String {
+    format!("login_backoff:{}:{}", scope, value)
+}
+
+fn lockout_key(username: &str) -> String {
+    format!("login_lockout:{}", username)
+}
@@ -0,0 +1,96 @@
+use sea_orm::{EntityName, Set};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+use entity::login_attempt;
+use entity::prelude::User as DbUser;
+use entity::utility::time::get_now;
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::error::api::ApiError;
+use crate::api::pagination::PageSizeParam;
+use crate::database::entity::{count, find_all_paginated, find_one, insert};
+use crate::util::client::ClientInfo;
+use crate::wrapper::entity::user::User;
+use crate::wrapper::entity::{TableName, WrapperEntity};
+use crate::wrapper::types::phantom::Phantom;
+use crate::SNOWFLAKE_GENERATOR;
+
+pub(crate) mod lockout;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct LoginAttempt {
+    #[serde(rename = "id")]
+    pub(crate) snowflake: Snowflake,
+    pub(crate) user_id: Phantom<User>,
+    pub(crate) ip: String,
+    pub(crate) user_agent: Option<String>,
+    pub(crate) successful: bool,
+    #[serde(with = "time::serde::rfc3339")]
+    pub(crate) created_at: OffsetDateTime,
+}
+
+impl LoginAttempt {
+    pub(crate) async fn record(user_id: Snowflake, client: &ClientInfo, successful: bool) -> Result<Self, ApiError> {
+        let active_model = login_attempt::ActiveModel {
+            id: Set(SNOWFLAKE_GENERATOR.next_id()?),
+            user: Set(user_id.id),
+            ip: Set(client.ip.clone()),
+            user_agent: Set(client.user_agent.clone()),
+            successful: Set(successful),
+            created_at: Set(get_now()),
+        };
+
+        insert(active_model).await.map(Self::from)
+    }
+
+    // attempts on unknown usernames are not recorded, there is nobody who could review them
+    pub(crate) async fn record_failure(username: &str, client: &ClientInfo) -> Result<(), ApiError> {
+        if let Some(user) = find_one(DbUser::find_by_username(username)).await? {
+            Self::record(Snowflake::from(user.id), client, false).await?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) async fn find_all_by_user_paginated(
+        user_id: Snowflake,
+        page_size: &PageSizeParam,
+    ) -> Result<Vec<Self>, ApiError> {
+        Ok(find_all_paginated(login_attempt::Entity::find_all_by_user_id(user_id), page_size)
+            .await?
+            .into_iter()
+            .map(Self::from)
+            .collect())
+    }
+
+    pub(crate) async fn count_all_by_user(user_id: Snowflake) -> Result<u64, ApiError> {
+        count(login_attempt::Entity::find_all_by_user_id(user_id)).await
+    }
+}
+
+impl TableName for LoginAttempt {
+    fn table_name() -> &'static str {
+        login_attempt::Entity.table_name()
+    }
+}
+
+impl WrapperEntity for LoginAttempt {
+    fn get_id(&self) -> Snowflake {
+        self.snowflake
+    }
+}
+
+impl From<login_attempt::Model> for LoginAttempt {
+    fn from(model: login_attempt::Model) -> Self {
+        Self {
+            snowflake: Snowflake::from(model.id),
+            user_id: Phantom::from(model.user),
+            ip: model.ip,
+            user_agent: model.user_agent,
+            successful: model.successful,
+            created_at: model.created_at,
+        }
+    }
+}
@@ -5,11 +5,19 @@ use utility::snowflake::entity::Snowflake;
 use crate::wrapper::entity::transaction::recurring::RecurringTransaction;
 
 pub(crate) mod account;
+pub(crate) mod api_token;
+pub(crate) mod audit;
 pub(crate) mod budget;
 pub(crate) mod currency;
+pub(crate) mod expense;
+pub(crate) mod group;
+pub(crate) mod invitation;
+pub(crate) mod login_attempt;
 pub(crate) mod session;
 pub(crate) mod transaction;
+pub(crate) mod trash;
 pub(crate) mod user;
+pub(crate) mod webhook;
 
 pub(crate) async fn start_wrapper() {
     info!("Initialize recurring transaction...");
@@ -20,6 +28,17 @@ pub(crate) trait WrapperEntity: TableName {
     fn get_id(&self) -> Snowflake;
 }
 
+/// Entities whose version is incremented by every update, it is sent as their `ETag`.
+pub(crate) trait Versioned {
+    fn version(&self) -> i32;
+
+    /// State that changes without an update of the entity, like the balance of an account. It is part of the `ETag`
+    /// so cached copies are refreshed, but is not compared against `If-Match`.
+    fn derived_state(&self) -> Option<i64> {
+        None
+    }
+}
+
 pub(crate) trait TableName {
     fn table_name() -> &'static str;
 }
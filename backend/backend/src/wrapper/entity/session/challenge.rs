@@ -0,0 +1,170 @@
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use entity::utility::time::get_now;
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::error::api::ApiError;
+use crate::config::Config;
+use crate::database::redis::{del, get, set_ex};
+use crate::util::client::ClientInfo;
+use crate::wrapper::entity::login_attempt::lockout::LoginLockout;
+use crate::wrapper::entity::login_attempt::LoginAttempt;
+use crate::wrapper::entity::session::Session;
+use crate::wrapper::entity::user::dto::Credentials;
+use crate::wrapper::entity::user::totp::UserTotp;
+use crate::wrapper::entity::user::User;
+use crate::wrapper::types::phantom::Identifiable;
+
+const MAX_ATTEMPTS: u8 = 5;
+
+/// Returned instead of a session when the user has two-factor authentication enabled.
+/// The session is created once the challenge is answered with a one-time password.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct TotpChallenge {
+    pub(crate) challenge_token: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub(crate) expires_at: OffsetDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PendingLogin {
+    user_id: Snowflake,
+    credentials: Credentials,
+    attempts: u8,
+    #[serde(with = "time::serde::rfc3339")]
+    expires_at: OffsetDateTime,
+}
+
+impl PendingLogin {
+    fn is_expired(&self, now: OffsetDateTime) -> bool {
+        self.expires_at <= now
+    }
+
+    /// Counts a wrong code and returns the seconds the challenge can still be answered, `None` once it is used up.
+    fn register_wrong_code(&mut self, now: OffsetDateTime) -> Option<u64> {
+        self.attempts += 1;
+        let remaining = (self.expires_at - now).whole_seconds();
+        if self.attempts >= MAX_ATTEMPTS || remaining <= 0 {
+            return None;
+        }
+
+        Some(remaining as u64)
+    }
+}
+
+impl TotpChallenge {
+    pub(crate) async fn new(user: &User, mut credentials: Credentials) -> Result<Self, ApiError> {
+        credentials.password.clear();
+        let lifetime = Config::get_config().totp.challenge_lifetime_seconds;
+        let pending = PendingLogin {
+            user_id: user.snowflake,
+            credentials,
+            attempts: 0,
+            expires_at: get_now() + Duration::seconds(lifetime as i64),
+        };
+        let challenge_token = Uuid::new_v4().to_string();
+        set_ex(Self::key(&challenge_token), serde_json::to_string(&pending)?, lifetime).await?;
+
+        Ok(Self {
+            challenge_token,
+            expires_at: pending.expires_at,
+        })
+    }
+
+    // the challenge is dropped after too many wrong codes, so the user has to log in again
+    pub(crate) async fn complete(
+        challenge_token: &str,
+        code: &str,
+        now: OffsetDateTime,
+        client: &ClientInfo,
+    ) -> Result<Session, ApiError> {
+        let key = Self::key(challenge_token);
+        let mut pending = match get::<Option<String>>(key.clone()).await? {
+            Some(pending) => serde_json::from_str::<PendingLogin>(&pending)?,
+            None => return Err(ApiError::InvalidTotpChallenge()),
+        };
+        if pending.is_expired(now) {
+            del(key).await?;
+            return Err(ApiError::InvalidTotpChallenge());
+        }
+        let mut totp =
+            UserTotp::find_confirmed_by_user(pending.user_id).await?.ok_or_else(ApiError::InvalidTotpChallenge)?;
+        let user = User::find_by_id(pending.user_id).await?;
+
+        // codes are guessed just like passwords, so they count against the same lockout
+        if let Err(error) = LoginLockout::check(&user.username, &client.ip).await {
+            LoginAttempt::record(user.snowflake, client, false).await?;
+            return Err(error);
+        }
+        if !totp.verify(code, now).await? {
+            LoginLockout::register_failure(&user.username, client).await?;
+            match pending.register_wrong_code(now) {
+                Some(remaining) => set_ex(key, serde_json::to_string(&pending)?, remaining).await?,
+                None => del(key).await?,
+            }
+
+            return Err(ApiError::InvalidTotpCode());
+        }
+
+        del(key).await?;
+        LoginLockout::register_success(&user, client).await?;
+        Session::new(user, pending.credentials, client).await
+    }
+
+    fn key(challenge_token: &str) -> String {
+        format!("totp_challenge:{}", challenge_token)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::all)]
+mod tests {
+    use super::*;
+
+    fn at(timestamp: i64) -> OffsetDateTime {
+        OffsetDateTime::from_unix_timestamp(timestamp).expect("Failed to create OffsetDateTime")
+    }
+
+    fn pending(expires_at: OffsetDateTime) -> PendingLogin {
+        PendingLogin {
+            user_id: Snowflake::from(1),
+            credentials: Credentials {
+                username: "totp_user".to_string(),
+                password: String::new(),
+                name: "totp_session".to_string(),
+                description: None,
+                platform: None,
+            },
+            attempts: 0,
+            expires_at,
+        }
+    }
+
+    #[test]
+    fn test_expires_at_lifetime() {
+        let pending = pending(at(1_000));
+        assert!(!pending.is_expired(at(999)));
+        assert!(pending.is_expired(at(1_000)));
+        assert!(pending.is_expired(at(1_001)));
+    }
+
+    #[test]
+    fn test_wrong_codes_use_up_challenge() {
+        let mut pending = pending(at(1_000));
+        for attempt in 1..MAX_ATTEMPTS {
+            assert_eq!(pending.register_wrong_code(at(700)), Some(300));
+            assert_eq!(pending.attempts, attempt);
+        }
+        assert_eq!(pending.register_wrong_code(at(700)), None);
+    }
+
+    #[test]
+    fn test_wrong_code_keeps_remaining_lifetime() {
+        let mut pending = pending(at(1_000));
+        assert_eq!(pending.register_wrong_code(at(990)), Some(10));
+        assert_eq!(pending.register_wrong_code(at(1_000)), None);
+    }
+}
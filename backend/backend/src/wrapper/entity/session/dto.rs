@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 use utoipa::ToSchema;
+use validator::Validate;
 
 use crate::wrapper::entity::session::Session;
 use crate::wrapper::entity::user::User;
@@ -12,6 +13,10 @@ pub(crate) struct PublicSession {
     pub(crate) name: String,
     pub(crate) description: Option<String>,
     pub(crate) platform: Option<String>,
+    pub(crate) ip: String,
+    pub(crate) user_agent: Option<String>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub(crate) last_used_at: Option<OffsetDateTime>,
     #[serde(with = "time::serde::rfc3339")]
     pub(crate) expires_at: OffsetDateTime,
     #[serde(with = "time::serde::rfc3339")]
@@ -27,9 +32,35 @@ impl From<Session> for PublicSession {
             name: value.name,
             description: value.description,
             platform: value.platform,
+            ip: value.ip,
+            user_agent: value.user_agent,
+            last_used_at: value.last_used_at,
             expires_at: value.expires_at,
             created_at: value.created_at,
             user: value.user,
         }
     }
 }
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
+pub(crate) struct TotpLogin {
+    #[validate(length(min = 1))]
+    pub(crate) challenge_token: String,
+    /// A one-time password or a recovery code.
+    #[validate(length(min = 1))]
+    pub(crate) code: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
+pub(crate) struct OidcCallback {
+    #[validate(length(min = 1))]
+    pub(crate) code: String,
+    #[validate(length(min = 1))]
+    pub(crate) state: String,
+    #[validate(length(min = 1))]
+    pub(crate) name: String,
+    #[validate(length(min = 1))]
+    pub(crate) description: Option<String>,
+    #[validate(length(min = 1))]
+    pub(crate) platform: Option<String>,
+}
@@ -20,9 +20,15 @@ use utility::snowflake::entity::Snowflake;
 use crate::api::error::api::ApiError;
 use crate::api::pagination::PageSizeParam;
 use crate::config::Config;
-use crate::database::entity::{count, delete, find_all_paginated, find_one_or_error, insert, update};
-use crate::database::redis::{del, get, set_ex, zadd};
+use crate::database::entity::{
+    count, delete, find_all_paginated, find_one, find_one_or_error, insert, update, update_many,
+};
+use crate::database::redis::{del, get, set_ex, set_nx_ex, zadd};
+use crate::database::transaction::in_transaction;
+use crate::event::lifecycle::session::{SessionCreated, SessionDeleted};
+use crate::event::GenericEvent;
 use crate::util::auth::extract_bearer_token;
+use crate::util::client::ClientInfo;
 use crate::wrapper::entity::user::dto::Credentials;
 use crate::wrapper::entity::user::User;
 use crate::wrapper::entity::{TableName, WrapperEntity};
@@ -31,7 +37,12 @@ use crate::wrapper::types::phantom::Identifiable;
 use crate::wrapper::util::handle_async_result_vec;
 use crate::{permission_impl, SNOWFLAKE_GENERATOR};
 
+pub(crate) mod challenge;
 pub(crate) mod dto;
+pub(crate) mod oidc;
+
+// writing on every request would turn each read into a write
+const LAST_USED_PRECISION_SECONDS: u64 = 60;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub(crate) struct Session {
@@ -41,6 +52,10 @@ pub(crate) struct Session {
     pub(crate) name: String,
     pub(crate) description: Option<String>,
     pub(crate) platform: Option<String>,
+    pub(crate) ip: String,
+    pub(crate) user_agent: Option<String>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub(crate) last_used_at: Option<OffsetDateTime>,
     #[serde(with = "time::serde::rfc3339")]
     pub(crate) expires_at: OffsetDateTime,
     #[serde(with = "time::serde::rfc3339")]
@@ -49,7 +64,11 @@ pub(crate) struct Session {
 }
 
 impl Session {
-    pub(crate) async fn new(user: User, credentials: Credentials) -> Result<Self, ApiError> {
+    pub(crate) async fn new(user: User, credentials: Credentials, client: &ClientInfo) -> Result<Self, ApiError> {
+        // every way to sign in ends here, so disabled users are turned away after their credentials checked out
+        if user.is_disabled() {
+            return Err(ApiError::UserDisabled());
+        }
         let session_token = Self::generate_session_key();
 
         if Self::reached_session_limit(user.snowflake).await? {
@@ -57,6 +76,7 @@ impl Session {
         }
 
         let snowflake = SNOWFLAKE_GENERATOR.next_id()?;
+        let now = get_now();
         // insert into database
         let session = session::ActiveModel {
             id: Set(snowflake),
@@ -65,18 +85,26 @@ impl Session {
             description: Set(credentials.description),
             platform: Set(credentials.platform),
             user: Set(user.snowflake.id),
-            created_at: Set(get_now()),
+            ip: Set(client.ip.clone()),
+            user_agent: Set(client.user_agent.clone()),
+            last_used_at: Set(Some(now)),
+            expires_at: Set(Self::expires_at_from(now)),
+            created_at: Set(now),
         };
-        let model = insert(session).await?;
+        let session = in_transaction(async {
+            let session = Self::from_model(insert(session).await?).await?;
 
-        let session = Self::from_model(model).await?;
+            //grant permissions to user
+            session.add_permission(user.snowflake, Permissions::all()).await?;
+            SessionCreated::new(session.snowflake, user.snowflake).fire().await?;
+
+            Ok(session)
+        })
+        .await?;
 
         // insert into redis
         session.insert_into_redis().await?;
 
-        //grant permissions to user
-        session.add_permission(user.snowflake, Permissions::all()).await?;
-
         Ok(session)
     }
 
@@ -87,24 +115,38 @@ impl Session {
         Ok(())
     }
 
+    // only moves the expiry, the session keeps its creation time
     pub(crate) async fn renew(mut self) -> Result<Self, ApiError> {
-        self.expires_at = get_now().add(TimeDuration::hours(Config::get_config().session.lifetime_hours as i64));
+        self.expires_at = Self::expires_at_from(get_now());
         self.insert_into_redis().await?;
 
         let active_model = session::ActiveModel {
             id: Set(self.snowflake.id),
-            token: Set(self.token.to_owned()),
-            name: Set(self.name.clone()),
-            description: Set(self.description.clone()),
-            platform: Set(self.platform.clone()),
-            user: Set(self.user.snowflake.id),
-            created_at: Set(get_now()),
+            expires_at: Set(self.expires_at),
+            ..Default::default()
         };
         let model = update(active_model).await?;
 
         Self::from_model(model).await
     }
 
+    /// Refreshes `last_used_at`, at most once per `LAST_USED_PRECISION_SECONDS` for each session.
+    pub(crate) async fn touch(token: &str) -> Result<(), ApiError> {
+        let touched =
+            set_nx_ex(format!("session_touched:{}", token), String::new(), LAST_USED_PRECISION_SECONDS).await?;
+        if !touched {
+            return Ok(());
+        }
+
+        update_many(session::Entity::update_last_used_at(token.to_string(), get_now())).await?;
+
+        Ok(())
+    }
+
+    fn expires_at_from(now: OffsetDateTime) -> OffsetDateTime {
+        now.add(TimeDuration::hours(Config::get_config().session.lifetime_hours as i64))
+    }
+
     pub(crate) async fn find_user_id(token: String) -> Result<Snowflake, ApiError> {
         let user_id = Self::find_user_id_from_redis(token.to_owned()).await?;
 
@@ -127,24 +169,35 @@ impl Session {
         if let Err(e) = del(self.token.to_owned()).await {
             error!("Could not delete session {}: {}", self.token, e);
         }
-        delete(session::Entity::delete_by_id(self.snowflake)).await?;
+        in_transaction(async {
+            delete(session::Entity::delete_by_id(self.snowflake)).await?;
 
-        Ok(())
+            SessionDeleted::new(self.snowflake, self.user.snowflake).fire().await
+        })
+        .await
     }
 
     pub(crate) async fn delete_by_token(session_token: &String) -> Result<(), ApiError> {
         if let Err(e) = del(session_token.clone()).await {
             error!("Could not delete session {}: {}", session_token, e);
         }
-        delete(session::Entity::delete_by_token(session_token.clone())).await?;
+        in_transaction(async {
+            let session = find_one(session::Entity::find_by_token(session_token.clone())).await?;
+            delete(session::Entity::delete_by_token(session_token.clone())).await?;
+            if let Some(session) = session {
+                SessionDeleted::new(Snowflake::from(session.id), Snowflake::from(session.user)).fire().await?;
+            }
 
-        Ok(())
+            Ok(())
+        })
+        .await
     }
 
-    pub(crate) async fn delete_all_with_user(user_id: Snowflake) -> Result<(), ApiError> {
+    /// Deletes every session of the user, except the one passed in `except`.
+    pub(crate) async fn delete_all_with_user(user_id: Snowflake, except: Option<Snowflake>) -> Result<(), ApiError> {
         let sessions = Self::find_all_by_user(user_id).await?;
 
-        for session in sessions {
+        for session in sessions.into_iter().filter(|session| Some(session.snowflake) != except) {
             session.delete().await?;
         }
 
@@ -269,9 +322,12 @@ impl Session {
             name: model.name,
             description: model.description,
             platform: model.platform,
+            ip: model.ip,
+            user_agent: model.user_agent,
+            last_used_at: model.last_used_at,
             token: model.token,
             user,
-            expires_at: model.created_at.add(TimeDuration::hours(Config::get_config().session.lifetime_hours as i64)),
+            expires_at: model.expires_at,
             created_at: model.created_at,
         })
     }
@@ -0,0 +1,152 @@
+use sea_orm::Set;
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
+use tracing::info;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use entity::prelude::User as DbUser;
+use entity::user_identity;
+use entity::utility::hashing::generate_salt;
+use entity::utility::time::get_now;
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::error::api::ApiError;
+use crate::database::entity::{count, find_one, insert};
+use crate::database::redis::{del, get, set_ex};
+use crate::oidc::{IdTokenClaims, OidcProvider};
+use crate::util::client::ClientInfo;
+use crate::wrapper::entity::login_attempt::LoginAttempt;
+use crate::wrapper::entity::session::dto::OidcCallback;
+use crate::wrapper::entity::session::Session;
+use crate::wrapper::entity::user::dto::{Credentials, UserCreation};
+use crate::wrapper::entity::user::User;
+use crate::wrapper::types::phantom::Identifiable;
+
+const LOGIN_LIFETIME_SECONDS: u64 = 600;
+const CODE_VERIFIER_LENGTH: usize = 64;
+const NONCE_LENGTH: usize = 32;
+// provisioned users sign in through the provider, the password only exists to satisfy the schema
+const PROVISIONED_PASSWORD_LENGTH: usize = 64;
+const USERNAME_SUFFIX_LENGTH: usize = 6;
+
+/// Where to send the user to sign in at the identity provider. The provider redirects back to `OIDC_REDIRECT_URL`
+/// with a code and the state, which are passed on to the callback to create the session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct OidcAuthorization {
+    pub(crate) authorization_url: String,
+    pub(crate) state: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub(crate) expires_at: OffsetDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PendingOidcLogin {
+    nonce: String,
+    code_verifier: String,
+}
+
+impl OidcAuthorization {
+    pub(crate) async fn new() -> Result<Self, ApiError> {
+        let state = Uuid::new_v4().to_string();
+        let pending = PendingOidcLogin {
+            nonce: generate_salt(NONCE_LENGTH),
+            code_verifier: generate_salt(CODE_VERIFIER_LENGTH),
+        };
+        let authorization_url = OidcProvider::authorization_url(&state, &pending.nonce, &pending.code_verifier).await?;
+        set_ex(Self::key(&state), serde_json::to_string(&pending)?, LOGIN_LIFETIME_SECONDS).await?;
+
+        Ok(Self {
+            authorization_url,
+            state,
+            expires_at: get_now() + Duration::seconds(LOGIN_LIFETIME_SECONDS as i64),
+        })
+    }
+
+    // the state is single-use, a replayed callback finds nothing
+    pub(crate) async fn complete(callback: OidcCallback, client: &ClientInfo) -> Result<Session, ApiError> {
+        let key = Self::key(&callback.state);
+        let pending = match get::<Option<String>>(key.clone()).await? {
+            Some(pending) => serde_json::from_str::<PendingOidcLogin>(&pending)?,
+            None => return Err(ApiError::InvalidOidcLogin("Invalid or expired state!")),
+        };
+        del(key).await?;
+
+        let claims = OidcProvider::exchange_code(&callback.code, &pending.code_verifier, &pending.nonce).await?;
+        let user = Self::find_or_link_user(&claims).await?;
+        LoginAttempt::record(user.snowflake, client, true).await?;
+
+        let credentials = Credentials {
+            username: user.username.clone(),
+            password: String::new(),
+            name: callback.name,
+            description: callback.description,
+            platform: callback.platform,
+        };
+
+        Session::new(user, credentials, client).await
+    }
+
+    async fn find_or_link_user(claims: &IdTokenClaims) -> Result<User, ApiError> {
+        if let Some(identity) =
+            find_one(user_identity::Entity::find_by_issuer_and_subject(&claims.iss, &claims.sub)).await?
+        {
+            return User::find_by_id(Snowflake::from(identity.user)).await;
+        }
+
+        let config = OidcProvider::config()?;
+        // an unverified email could be set to anyone's address at the provider
+        let verified_email = claims.email.as_deref().filter(|_| claims.email_verified);
+        let existing = match verified_email {
+            Some(email) if config.link_by_email => find_one(DbUser::find_by_email(email)).await?.map(User::from),
+            _ => None,
+        };
+
+        let user = match existing {
+            Some(user) => user,
+            None if config.auto_provision => Self::provision_user(claims, verified_email).await?,
+            None => return Err(ApiError::OidcAccountNotLinked()),
+        };
+
+        info!("Linking identity {} of {} to user {}.", claims.sub, claims.iss, user.snowflake);
+        insert(user_identity::ActiveModel {
+            issuer: Set(claims.iss.clone()),
+            subject: Set(claims.sub.clone()),
+            user: Set(user.snowflake.id),
+            created_at: Set(get_now()),
+        })
+        .await?;
+
+        Ok(user)
+    }
+
+    async fn provision_user(claims: &IdTokenClaims, verified_email: Option<&str>) -> Result<User, ApiError> {
+        let username = claims
+            .preferred_username
+            .clone()
+            .or_else(|| verified_email.and_then(|email| email.split('@').next()).map(str::to_string))
+            .unwrap_or_else(|| format!("sso_{}", claims.sub));
+        let username = match count(DbUser::find_by_username(&username)).await? {
+            0 => username,
+            _ => format!("{}_{}", username, generate_salt(USERNAME_SUFFIX_LENGTH).to_lowercase()),
+        };
+        let email = match verified_email {
+            Some(email) if count(DbUser::find_by_email(email)).await? == 0 => Some(email.to_string()),
+            _ => None,
+        };
+
+        // provisioning is configured on its own and does not depend on the registration mode
+        User::create(UserCreation {
+            username,
+            email,
+            display_name: claims.name.clone(),
+            password: generate_salt(PROVISIONED_PASSWORD_LENGTH),
+            is_admin: false,
+        })
+        .await
+    }
+
+    fn key(state: &str) -> String {
+        format!("oidc_login:{}", state)
+    }
+}
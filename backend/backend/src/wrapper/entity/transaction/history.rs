@@ -0,0 +1,133 @@
+use sea_orm::{EntityName, Set};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+use entity::transaction_version;
+use entity::utility::time::get_now;
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::error::api::ApiError;
+use crate::api::pagination::PageSizeParam;
+use crate::database::entity::{count, find_all_paginated, find_one, find_one_or_error, insert};
+use crate::wrapper::entity::account::Account;
+use crate::wrapper::entity::audit::context::Actor;
+use crate::wrapper::entity::budget::Budget;
+use crate::wrapper::entity::currency::Currency;
+use crate::wrapper::entity::transaction::dto::TransactionDTO;
+use crate::wrapper::entity::transaction::Transaction;
+use crate::wrapper::entity::user::User;
+use crate::wrapper::entity::TableName;
+use crate::wrapper::types::phantom::Phantom;
+
+/// A snapshot of a transaction as it was after a creation, update or restore. The revision counts the snapshots of
+/// the transaction, it is unrelated to the transaction's `version`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct TransactionVersion {
+    pub(crate) transaction_id: Phantom<Transaction>,
+    pub(crate) revision: i32,
+    pub(crate) source_id: Option<Phantom<Account>>,
+    pub(crate) destination_id: Option<Phantom<Account>>,
+    pub(crate) amount: i64,
+    pub(crate) currency_id: Phantom<Currency>,
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    pub(crate) budget_id: Option<Phantom<Budget>>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub(crate) executed_at: OffsetDateTime,
+    pub(crate) actor_id: Option<Phantom<User>>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub(crate) created_at: OffsetDateTime,
+}
+
+impl TransactionVersion {
+    /// Has to be called after the transaction row was written in the same database transaction, its row lock keeps
+    /// concurrent changes from taking the same revision.
+    pub(crate) async fn record(transaction: &Transaction) -> Result<Self, ApiError> {
+        let latest = find_one(transaction_version::Entity::find_all_by_transaction_id(transaction.snowflake)).await?;
+        let revision = latest.map_or(1, |latest| latest.revision + 1);
+
+        let active_model = transaction_version::ActiveModel {
+            transaction: Set(transaction.snowflake.id),
+            revision: Set(revision),
+            source: Set(transaction.source_id.as_ref().map(|source| source.get_id().id)),
+            destination: Set(transaction.destination_id.as_ref().map(|destination| destination.get_id().id)),
+            amount: Set(transaction.amount),
+            currency: Set(transaction.currency_id.get_id().id),
+            name: Set(transaction.name.clone()),
+            description: Set(transaction.description.clone()),
+            budget: Set(transaction.budget_id.as_ref().map(|budget| budget.get_id().id)),
+            executed_at: Set(transaction.executed_at),
+            actor: Set(Actor::current().await?.user_id.map(|user_id| user_id.id)),
+            created_at: Set(get_now()),
+        };
+
+        Ok(Self::from(insert(active_model).await?))
+    }
+
+    // transactions created before the history existed get their previous state as first revision
+    pub(crate) async fn record_initial(transaction: &Transaction) -> Result<(), ApiError> {
+        if count(transaction_version::Entity::find_all_by_transaction_id(transaction.snowflake)).await? == 0 {
+            Self::record(transaction).await?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) async fn find(transaction_id: Snowflake, revision: i32) -> Result<Self, ApiError> {
+        find_one_or_error(transaction_version::Entity::find_revision(transaction_id, revision)).await.map(Self::from)
+    }
+
+    pub(crate) async fn find_all_by_transaction_paginated(
+        transaction_id: Snowflake,
+        page_size: &PageSizeParam,
+    ) -> Result<Vec<Self>, ApiError> {
+        Ok(find_all_paginated(transaction_version::Entity::find_all_by_transaction_id(transaction_id), page_size)
+            .await?
+            .into_iter()
+            .map(Self::from)
+            .collect())
+    }
+
+    pub(crate) async fn count_all_by_transaction(transaction_id: Snowflake) -> Result<u64, ApiError> {
+        count(transaction_version::Entity::find_all_by_transaction_id(transaction_id)).await
+    }
+
+    pub(crate) fn to_dto(&self) -> TransactionDTO {
+        TransactionDTO {
+            source_id: self.source_id.clone(),
+            destination_id: self.destination_id.clone(),
+            amount: self.amount,
+            currency_id: self.currency_id.clone(),
+            name: self.name.clone(),
+            description: self.description.clone(),
+            budget_id: self.budget_id.clone(),
+            executed_at: self.executed_at,
+        }
+    }
+}
+
+impl TableName for TransactionVersion {
+    fn table_name() -> &'static str {
+        transaction_version::Entity.table_name()
+    }
+}
+
+impl From<transaction_version::Model> for TransactionVersion {
+    fn from(model: transaction_version::Model) -> Self {
+        Self {
+            transaction_id: Phantom::from(model.transaction),
+            revision: model.revision,
+            source_id: Phantom::from_option(model.source),
+            destination_id: Phantom::from_option(model.destination),
+            amount: model.amount,
+            currency_id: Phantom::from(model.currency),
+            name: model.name,
+            description: model.description,
+            budget_id: Phantom::from_option(model.budget),
+            executed_at: model.executed_at,
+            actor_id: Phantom::from_option(model.actor),
+            created_at: model.created_at,
+        }
+    }
+}
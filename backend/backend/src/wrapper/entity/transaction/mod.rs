@@ -1,5 +1,5 @@
 use sea_orm::ActiveValue::Set;
-use sea_orm::{EntityName, EntityTrait};
+use sea_orm::EntityName;
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 use tokio::time::Duration;
@@ -11,19 +11,25 @@ use entity::utility::time::get_now;
 use utility::snowflake::entity::Snowflake;
 
 use crate::api::error::api::ApiError;
+use crate::api::expansion::{Expandable, Related};
 use crate::api::pagination::PageSizeParam;
-use crate::database::entity::{count, delete, find_all_paginated, find_one_or_error, insert, update};
+use crate::database::entity::{count, find_all_paginated, find_one_or_error, insert, update_one};
+use crate::database::transaction::in_transaction;
 use crate::event::lifecycle::transaction::{TransactionCreation, TransactionDeletion, TransactionUpdate};
 use crate::event::GenericEvent;
 use crate::wrapper::entity::account::Account;
+use crate::wrapper::entity::audit::AuditLog;
 use crate::wrapper::entity::budget::Budget;
 use crate::wrapper::entity::currency::Currency;
 use crate::wrapper::entity::transaction::dto::TransactionDTO;
-use crate::wrapper::entity::{TableName, WrapperEntity};
+use crate::wrapper::entity::transaction::history::TransactionVersion;
+use crate::wrapper::entity::trash::TrashEntry;
+use crate::wrapper::entity::{TableName, Versioned, WrapperEntity};
 use crate::wrapper::types::phantom::{Identifiable, Phantom};
 use crate::{permission_impl, SNOWFLAKE_GENERATOR};
 
 pub(crate) mod dto;
+pub(crate) mod history;
 pub(crate) mod recurring;
 pub(crate) mod template;
 
@@ -42,73 +48,101 @@ pub(crate) struct Transaction {
     pub(crate) created_at: OffsetDateTime,
     #[serde(with = "time::serde::rfc3339")]
     pub(crate) executed_at: OffsetDateTime,
+    /// Incremented by every update and sent as the `ETag`.
+    pub(crate) version: i32,
 }
 
 impl Transaction {
     pub(crate) async fn new(dto: TransactionDTO) -> Result<Self, ApiError> {
-        let snowflake = SNOWFLAKE_GENERATOR.next_id()?;
-        let active_model = transaction::ActiveModel {
-            id: Set(snowflake),
-            source: Set(dto.source_id.as_ref().map(|source| source.get_id().id)),
-            destination: Set(dto.destination_id.as_ref().map(|destination| destination.get_id().id)),
-            amount: Set(dto.amount),
-            currency: Set(dto.currency_id.get_id().id),
-            name: Set(dto.name),
-            description: Set(dto.description),
-            budget: Set(dto.budget_id.map(|budget| budget.get_id().id)),
-            executed_at: Set(dto.executed_at),
-            created_at: Set(get_now()),
-        };
-        let model = insert(active_model).await?;
-
-        let transaction = Self::from(model);
-
-        //grant permission
-        if let Some(source) = dto.source_id.as_ref() {
-            Account::assign_permissions_from_account(&transaction, source.get_id()).await?;
-        }
-        if let Some(destination) = dto.destination_id.as_ref() {
-            Account::assign_permissions_from_account(&transaction, destination.get_id()).await?;
-        }
-
-        // check if execute_at is in the future
-        if transaction.executed_at > get_now() {
-            let delay = transaction.executed_at - get_now();
-            let delay = Duration::new(delay.whole_seconds() as u64, 0);
-            TransactionCreation::fire_scheduled(TransactionCreation::new(transaction.clone()), delay);
-        } else {
-            TransactionCreation::fire(TransactionCreation::new(transaction.clone()));
-        }
-
-        Ok(transaction)
+        in_transaction(async move {
+            Account::assert_accounts_not_archived([&dto.source_id, &dto.destination_id], [&None, &None]).await?;
+
+            let snowflake = SNOWFLAKE_GENERATOR.next_id()?;
+            let active_model = transaction::ActiveModel {
+                id: Set(snowflake),
+                source: Set(dto.source_id.as_ref().map(|source| source.get_id().id)),
+                destination: Set(dto.destination_id.as_ref().map(|destination| destination.get_id().id)),
+                amount: Set(dto.amount),
+                currency: Set(dto.currency_id.get_id().id),
+                name: Set(dto.name),
+                description: Set(dto.description),
+                budget: Set(dto.budget_id.map(|budget| budget.get_id().id)),
+                executed_at: Set(dto.executed_at),
+                created_at: Set(get_now()),
+                version: Set(0),
+            };
+            let model = insert(active_model).await?;
+
+            let transaction = Self::from(model);
+            AuditLog::record_creation(&transaction).await?;
+            TransactionVersion::record(&transaction).await?;
+
+            //grant permission
+            if let Some(source) = dto.source_id.as_ref() {
+                Account::assign_permissions_from_account(&transaction, source.get_id()).await?;
+            }
+            if let Some(destination) = dto.destination_id.as_ref() {
+                Account::assign_permissions_from_account(&transaction, destination.get_id()).await?;
+            }
+
+            // check if execute_at is in the future
+            if transaction.executed_at > get_now() {
+                let delay = transaction.executed_at - get_now();
+                let delay = Duration::new(delay.whole_seconds() as u64, 0);
+                TransactionCreation::new(transaction.clone()).fire_scheduled(delay).await?;
+            } else {
+                TransactionCreation::new(transaction.clone()).fire().await?;
+            }
+
+            Ok(transaction)
+        })
+        .await
     }
 
     pub(crate) async fn update(self, updated_dto: TransactionDTO) -> Result<Self, ApiError> {
-        let active_model = transaction::ActiveModel {
-            id: Set(self.snowflake.id),
-            source: Set(updated_dto.source_id.map(|source| source.get_id().id)),
-            destination: Set(updated_dto.destination_id.map(|destination| destination.get_id().id)),
-            amount: Set(updated_dto.amount),
-            currency: Set(updated_dto.currency_id.get_id().id),
-            name: Set(updated_dto.name),
-            description: Set(updated_dto.description),
-            budget: Set(updated_dto.budget_id.map(|budget| budget.get_id().id)),
-            created_at: Set(self.created_at),
-            executed_at: Set(updated_dto.executed_at),
-        };
-        let transaction = Self::from(update(active_model).await?);
-
-        TransactionUpdate::fire(TransactionUpdate::new(self.clone(), transaction.clone()));
-
-        Ok(transaction)
+        in_transaction(async move {
+            Account::assert_accounts_not_archived(
+                [&updated_dto.source_id, &updated_dto.destination_id],
+                [&self.source_id, &self.destination_id],
+            )
+            .await?;
+
+            let active_model = transaction::ActiveModel {
+                id: Set(self.snowflake.id),
+                source: Set(updated_dto.source_id.map(|source| source.get_id().id)),
+                destination: Set(updated_dto.destination_id.map(|destination| destination.get_id().id)),
+                amount: Set(updated_dto.amount),
+                currency: Set(updated_dto.currency_id.get_id().id),
+                name: Set(updated_dto.name),
+                description: Set(updated_dto.description),
+                budget: Set(updated_dto.budget_id.map(|budget| budget.get_id().id)),
+                created_at: Set(self.created_at),
+                executed_at: Set(updated_dto.executed_at),
+                version: Set(self.version + 1),
+            };
+            let transaction =
+                Self::from(update_one(transaction::Entity::update_if_version(active_model, self.version)).await?);
+            AuditLog::record_update(&self, &transaction).await?;
+            TransactionVersion::record_initial(&self).await?;
+            TransactionVersion::record(&transaction).await?;
+
+            TransactionUpdate::new(self.clone(), transaction.clone()).fire().await?;
+
+            Ok(transaction)
+        })
+        .await
     }
 
     pub(crate) async fn delete(self) -> Result<(), ApiError> {
-        delete(transaction::Entity::delete_by_id(self.snowflake)).await?;
+        in_transaction(async move {
+            TrashEntry::trash(&self, Vec::new()).await?;
+            AuditLog::record_deletion(&self).await?;
 
-        TransactionDeletion::fire(TransactionDeletion::new(self.clone()));
+            TransactionDeletion::new(self.clone()).fire().await?;
 
-        Ok(())
+            Ok(())
+        })
+        .await
     }
 
     pub(crate) async fn find_all_by_user_paginated(
@@ -129,6 +163,20 @@ impl Transaction {
 
 permission_impl!(Transaction);
 
+impl Expandable for Transaction {
+    const RELATIONS: &'static [&'static str] = &["source", "destination", "currency", "budget"];
+
+    fn related(&mut self, name: &str) -> Option<Related<'_>> {
+        match name {
+            "source" => self.source_id.as_mut().map(Related::Account),
+            "destination" => self.destination_id.as_mut().map(Related::Account),
+            "currency" => Some(Related::Currency(&mut self.currency_id)),
+            "budget" => self.budget_id.as_mut().map(Related::Budget),
+            _ => None,
+        }
+    }
+}
+
 impl TableName for Transaction {
     fn table_name() -> &'static str {
         transaction::Entity.table_name()
@@ -141,9 +189,15 @@ impl WrapperEntity for Transaction {
     }
 }
 
+impl Versioned for Transaction {
+    fn version(&self) -> i32 {
+        self.version
+    }
+}
+
 impl Identifiable for Transaction {
     async fn find_by_id(id: Snowflake) -> Result<Self, ApiError> {
-        find_one_or_error(transaction::Entity::find_by_id(id)).await.map(Self::from)
+        find_one_or_error(transaction::Entity::find_by_id_not_trashed(id)).await.map(Self::from)
     }
 }
 
@@ -160,6 +214,7 @@ impl From<Model> for Transaction {
             budget_id: Phantom::from_option(value.budget),
             created_at: value.created_at,
             executed_at: value.executed_at,
+            version: value.version,
         }
     }
 }
@@ -6,7 +6,7 @@ use deschuler::scheduler::job::Job;
 use deschuler::scheduler::tokio_scheduler::config::TokioSchedulerConfig;
 use deschuler::scheduler::tokio_scheduler::TokioScheduler;
 use deschuler::scheduler::Scheduler;
-use sea_orm::{EntityName, EntityTrait, Set};
+use sea_orm::{EntityName, Set};
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 use tokio::sync::RwLock;
@@ -21,15 +21,21 @@ use utility::datetime::{convert_chrono_to_time, convert_time_to_chrono};
 use utility::snowflake::entity::Snowflake;
 
 use crate::api::error::api::ApiError;
+use crate::api::expansion::{Expandable, Related};
 use crate::api::pagination::PageSizeParam;
-use crate::database::entity::{count, delete, find_all_paginated, find_one_or_error, insert, update};
+use crate::database::entity::{count, find_all, find_all_paginated, find_one_or_error, insert, update};
+use crate::database::transaction::{after_commit, in_transaction};
+use crate::event::lifecycle::transaction::RecurringTransactionExecuted;
+use crate::event::GenericEvent;
 use crate::util::cron::get_cron_builder_config_default;
 use crate::wrapper::entity::account::Account;
+use crate::wrapper::entity::audit::AuditLog;
 use crate::wrapper::entity::transaction::dto::TransactionDTO;
 use crate::wrapper::entity::transaction::recurring::dto::RecurringTransactionDTO;
 use crate::wrapper::entity::transaction::recurring::recurring_rule::RecurringRule;
 use crate::wrapper::entity::transaction::template::TransactionTemplate;
 use crate::wrapper::entity::transaction::Transaction;
+use crate::wrapper::entity::trash::TrashEntry;
 use crate::wrapper::entity::{TableName, WrapperEntity};
 use crate::wrapper::processor::db_iterator;
 use crate::wrapper::processor::db_iterator::{CountAllFn, FindAllPaginatedFn, JobFn};
@@ -119,57 +125,71 @@ impl RecurringTransaction {
         let recurring_rule = RecurringRule::from(dto.recurring_rule);
         recurring_rule.to_cron()?; // doing this to check if the cron is valid
 
-        let snowflake = SNOWFLAKE_GENERATOR.next_id()?;
-        let active_model = recurring_transaction::ActiveModel {
-            id: Set(snowflake),
-            template: Set(dto.template_id.get_id().id),
-            recurring_rule: Set(recurring_rule.to_json_value()?),
-            last_executed_at: Set(None),
-            created_at: Set(get_now()),
-        };
-
-        let model = insert(active_model).await?;
-        let transaction = Self::from(model);
-
-        let template = dto.template_id.fetch_inner().await?;
-        //grant permission
-        if let Some(source) = template.source_id.as_ref() {
-            Account::assign_permissions_from_account(&transaction, source.get_id()).await?;
-        }
-        if let Some(destination) = template.destination_id.as_ref() {
-            Account::assign_permissions_from_account(&transaction, destination.get_id()).await?;
-        }
+        let transaction = in_transaction(async move {
+            let template = dto.template_id.fetch_inner().await?;
+            Account::assert_accounts_not_archived([&template.source_id, &template.destination_id], [&None, &None])
+                .await?;
+
+            let snowflake = SNOWFLAKE_GENERATOR.next_id()?;
+            let active_model = recurring_transaction::ActiveModel {
+                id: Set(snowflake),
+                template: Set(dto.template_id.get_id().id),
+                recurring_rule: Set(recurring_rule.to_json_value()?),
+                last_executed_at: Set(None),
+                created_at: Set(get_now()),
+            };
+
+            let model = insert(active_model).await?;
+            let transaction = Self::from(model);
+            AuditLog::record_creation(&transaction).await?;
+
+            //grant permission
+            if let Some(source) = template.source_id.as_ref() {
+                Account::assign_permissions_from_account(&transaction, source.get_id()).await?;
+            }
+            if let Some(destination) = template.destination_id.as_ref() {
+                Account::assign_permissions_from_account(&transaction, destination.get_id()).await?;
+            }
+
+            Ok(transaction)
+        })
+        .await?;
 
-        //starting the recurring transaction
+        //starting the recurring transaction once it is committed
         transaction.start_recurring_transaction().await?;
 
         Ok(transaction)
     }
 
     pub(crate) async fn delete(self) -> Result<(), ApiError> {
-        delete(recurring_transaction::Entity::delete_by_id(self.snowflake)).await?;
-
-        let map = get_jobs();
-        let map = map.write().await;
-        if let Some(job) = map.get(&self.snowflake) {
-            job.interrupt();
-        }
-
-        Ok(())
+        in_transaction(async move {
+            TrashEntry::trash(&self, Vec::new()).await?;
+            AuditLog::record_deletion(&self).await?;
+            self.stop_after_commit();
+
+            Ok(())
+        })
+        .await
     }
 
     pub(crate) async fn update(&self, dto: RecurringTransactionDTO) -> Result<Self, ApiError> {
         let recurring_rule = RecurringRule::from(dto.recurring_rule);
         recurring_rule.to_cron()?; // doing this to check if the cron is valid
-        let active_model = recurring_transaction::ActiveModel {
-            id: Set(self.snowflake.id),
-            template: Set(dto.template_id.get_id().id),
-            recurring_rule: Set(recurring_rule.to_json_value()?),
-            last_executed_at: Set(self.last_executed_at),
-            created_at: Set(self.created_at),
-        };
-        let model = update(active_model).await?;
-        let transaction = Self::from(model);
+        let transaction = in_transaction(async {
+            let active_model = recurring_transaction::ActiveModel {
+                id: Set(self.snowflake.id),
+                template: Set(dto.template_id.get_id().id),
+                recurring_rule: Set(recurring_rule.to_json_value()?),
+                last_executed_at: Set(self.last_executed_at),
+                created_at: Set(self.created_at),
+            };
+            let model = update(active_model).await?;
+            let transaction = Self::from(model);
+            AuditLog::record_update(self, &transaction).await?;
+
+            Ok(transaction)
+        })
+        .await?;
 
         self.stop_recurring_transaction().await?;
         transaction.start_recurring_transaction().await?;
@@ -177,7 +197,7 @@ impl RecurringTransaction {
         Ok(transaction)
     }
 
-    async fn start_recurring_transaction(&self) -> Result<(), ApiError> {
+    pub(crate) async fn start_recurring_transaction(&self) -> Result<(), ApiError> {
         let binding = get_recurring_transaction_scheduler();
         let cron = self.recurring_rule.to_cron()?;
 
@@ -200,7 +220,7 @@ impl RecurringTransaction {
         Ok(())
     }
 
-    async fn stop_recurring_transaction(&self) -> Result<(), ApiError> {
+    pub(crate) async fn stop_recurring_transaction(&self) -> Result<(), ApiError> {
         let binding = get_jobs();
         let mut jobs = binding.write().await;
         if let Some(job) = jobs.remove(&self.snowflake) {
@@ -210,28 +230,44 @@ impl RecurringTransaction {
         Ok(())
     }
 
+    /// Stops the job once the current transaction is committed, so it keeps running if the deletion is rolled back.
+    pub(crate) fn stop_after_commit(self) {
+        after_commit(async move {
+            if let Err(err) = self.stop_recurring_transaction().await {
+                error!("Could not stop recurring transaction job. Error: {:?}", err);
+            }
+        });
+    }
+
     async fn handle_job(&self, now: OffsetDateTime) {
-        if let Err(err) = self.recurring_transaction_job_task(now).await {
+        if let Err(err) = self.execute(now).await {
             error!("Could not execute recurring transaction job. Error: {:?}", err);
-        } else if let Err(err) = self.update_last_run(now).await {
-            error!("Could not update last run. Error: {:?}", err);
         }
     }
 
-    async fn recurring_transaction_job_task(&self, now: OffsetDateTime) -> Result<(), ApiError> {
+    // the transaction, the last run and the event are committed together, so a failed run is not half recorded
+    async fn execute(&self, now: OffsetDateTime) -> Result<(), ApiError> {
+        in_transaction(async {
+            let transaction = self.recurring_transaction_job_task(now).await?;
+            let recurring_transaction = self.update_last_run(now).await?;
+
+            RecurringTransactionExecuted::new(recurring_transaction, transaction).fire().await
+        })
+        .await
+    }
+
+    async fn recurring_transaction_job_task(&self, now: OffsetDateTime) -> Result<Transaction, ApiError> {
         let template = Arc::new(self.template_id.fetch_inner().await?);
         let dto = TransactionDTO::from_template(template, now).await?;
-        Transaction::new(dto).await?;
 
-        Ok(())
+        Transaction::new(dto).await
     }
 
-    async fn update_last_run(&self, last_run: OffsetDateTime) -> Result<(), ApiError> {
+    async fn update_last_run(&self, last_run: OffsetDateTime) -> Result<Self, ApiError> {
         let mut active_model = self.to_active_model();
         active_model.last_executed_at = Set(Some(last_run));
-        update(active_model).await?;
 
-        Ok(())
+        Ok(Self::from(update(active_model).await?))
     }
 
     fn to_active_model(&self) -> recurring_transaction::ActiveModel {
@@ -260,15 +296,31 @@ impl RecurringTransaction {
     }
 
     pub(crate) async fn find_by_id(id: Snowflake) -> Result<Self, ApiError> {
-        find_one_or_error(recurring_transaction::Entity::find_by_id(id)).await.map(Self::from)
+        find_one_or_error(recurring_transaction::Entity::find_by_id_not_trashed(id)).await.map(Self::from)
+    }
+
+    pub(crate) async fn find_all_by_account_id(account_id: Snowflake) -> Result<Vec<Self>, ApiError> {
+        Ok(find_all(recurring_transaction::Entity::find_all_by_account_id(account_id))
+            .await?
+            .into_iter()
+            .map(Self::from)
+            .collect())
+    }
+
+    pub(crate) async fn find_all_by_template_id(template_id: Snowflake) -> Result<Vec<Self>, ApiError> {
+        Ok(find_all(recurring_transaction::Entity::find_all_by_template_id(template_id))
+            .await?
+            .into_iter()
+            .map(Self::from)
+            .collect())
     }
 
     pub(crate) async fn count_all() -> Result<u64, ApiError> {
-        count(recurring_transaction::Entity::find()).await
+        count(recurring_transaction::Entity::find_not_trashed()).await
     }
 
     pub(crate) async fn find_all_paginated(page_size: PageSizeParam) -> Result<Vec<Self>, ApiError> {
-        Ok(find_all_paginated(recurring_transaction::Entity::find(), &page_size)
+        Ok(find_all_paginated(recurring_transaction::Entity::find_not_trashed(), &page_size)
             .await?
             .into_iter()
             .map(Self::from)
@@ -278,6 +330,17 @@ impl RecurringTransaction {
 
 permission_impl!(RecurringTransaction);
 
+impl Expandable for RecurringTransaction {
+    const RELATIONS: &'static [&'static str] = &["template"];
+
+    fn related(&mut self, name: &str) -> Option<Related<'_>> {
+        match name {
+            "template" => Some(Related::TransactionTemplate(&mut self.template_id)),
+            _ => None,
+        }
+    }
+}
+
 impl From<Model> for RecurringTransaction {
     fn from(value: Model) -> Self {
         let recurring_rule: RecurringRule =
@@ -295,7 +358,7 @@ impl From<Model> for RecurringTransaction {
 
 impl Identifiable for RecurringTransaction {
     async fn find_by_id(id: Snowflake) -> Result<Self, ApiError> {
-        find_one_or_error(recurring_transaction::Entity::find_by_id(id)).await.map(Self::from)
+        find_one_or_error(recurring_transaction::Entity::find_by_id_not_trashed(id)).await.map(Self::from)
     }
 }
 
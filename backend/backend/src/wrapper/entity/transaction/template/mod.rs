@@ -1,4 +1,4 @@
-use sea_orm::{EntityName, EntityTrait, Set};
+use sea_orm::{EntityName, Set};
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 use utoipa::ToSchema;
@@ -9,11 +9,16 @@ use entity::utility::time::get_now;
 use utility::snowflake::entity::Snowflake;
 
 use crate::api::error::api::ApiError;
+use crate::api::expansion::{Expandable, Related};
 use crate::api::pagination::PageSizeParam;
-use crate::database::entity::{count, delete, find_all_paginated, find_one_or_error, insert, update};
+use crate::database::entity::{count, find_all_paginated, find_one_or_error, insert, update};
+use crate::database::transaction::in_transaction;
 use crate::wrapper::entity::account::Account;
+use crate::wrapper::entity::audit::AuditLog;
 use crate::wrapper::entity::budget::Budget;
 use crate::wrapper::entity::currency::Currency;
+use crate::wrapper::entity::transaction::recurring::RecurringTransaction;
+use crate::wrapper::entity::trash::TrashEntry;
 use crate::wrapper::entity::{TableName, WrapperEntity};
 use crate::wrapper::permission::{Permission, Permissions};
 use crate::wrapper::types::phantom::{Identifiable, Phantom};
@@ -38,25 +43,31 @@ pub(crate) struct TransactionTemplate {
 
 impl TransactionTemplate {
     pub(crate) async fn new(dto: TransactionTemplateDTO, user_id: Snowflake) -> Result<Self, ApiError> {
-        let snowflake = SNOWFLAKE_GENERATOR.next_id()?;
-        let active_model = transaction_template::ActiveModel {
-            id: Set(snowflake),
-            source: Set(dto.source_id.map(|source| source.get_id().id)),
-            destination: Set(dto.destination_id.map(|destination| destination.get_id().id)),
-            amount: Set(dto.amount),
-            currency: Set(dto.currency_id.get_id().id),
-            name: Set(dto.name),
-            description: Set(dto.description),
-            budget: Set(dto.budget_id.map(|budget| budget.get_id().id)),
-            created_at: Set(get_now()),
-        };
-        let model = insert(active_model).await?;
-        let template = Self::from(model);
-
-        //grant permission
-        template.add_permission(user_id, Permissions::all()).await?;
-
-        Ok(template)
+        in_transaction(async move {
+            Account::assert_accounts_not_archived([&dto.source_id, &dto.destination_id], [&None, &None]).await?;
+
+            let snowflake = SNOWFLAKE_GENERATOR.next_id()?;
+            let active_model = transaction_template::ActiveModel {
+                id: Set(snowflake),
+                source: Set(dto.source_id.map(|source| source.get_id().id)),
+                destination: Set(dto.destination_id.map(|destination| destination.get_id().id)),
+                amount: Set(dto.amount),
+                currency: Set(dto.currency_id.get_id().id),
+                name: Set(dto.name),
+                description: Set(dto.description),
+                budget: Set(dto.budget_id.map(|budget| budget.get_id().id)),
+                created_at: Set(get_now()),
+            };
+            let model = insert(active_model).await?;
+            let template = Self::from(model);
+            AuditLog::record_creation(&template).await?;
+
+            //grant permission
+            template.add_permission(user_id, Permissions::all()).await?;
+
+            Ok(template)
+        })
+        .await
     }
 
     pub(crate) async fn count_all_by_user_id(user_id: Snowflake) -> Result<u64, ApiError> {
@@ -75,32 +86,70 @@ impl TransactionTemplate {
     }
 
     pub(crate) async fn update(self, updated_dto: TransactionTemplateDTO) -> Result<Self, ApiError> {
-        let active_model = transaction_template::ActiveModel {
-            id: Set(self.snowflake.id),
-            source: Set(updated_dto.source_id.map(|source| source.get_id().id)),
-            destination: Set(updated_dto.destination_id.map(|destination| destination.get_id().id)),
-            amount: Set(updated_dto.amount),
-            currency: Set(updated_dto.currency_id.get_id().id),
-            name: Set(updated_dto.name),
-            description: Set(updated_dto.description),
-            budget: Set(updated_dto.budget_id.map(|budget| budget.get_id().id)),
-            created_at: Set(get_now()),
-        };
-        let model = update(active_model).await?;
-        let template = Self::from(model);
-
-        Ok(template)
+        in_transaction(async move {
+            Account::assert_accounts_not_archived(
+                [&updated_dto.source_id, &updated_dto.destination_id],
+                [&self.source_id, &self.destination_id],
+            )
+            .await?;
+
+            let active_model = transaction_template::ActiveModel {
+                id: Set(self.snowflake.id),
+                source: Set(updated_dto.source_id.map(|source| source.get_id().id)),
+                destination: Set(updated_dto.destination_id.map(|destination| destination.get_id().id)),
+                amount: Set(updated_dto.amount),
+                currency: Set(updated_dto.currency_id.get_id().id),
+                name: Set(updated_dto.name),
+                description: Set(updated_dto.description),
+                budget: Set(updated_dto.budget_id.map(|budget| budget.get_id().id)),
+                created_at: Set(get_now()),
+            };
+            let model = update(active_model).await?;
+            let template = Self::from(model);
+            AuditLog::record_update(&self, &template).await?;
+
+            Ok(template)
+        })
+        .await
     }
 
     pub(crate) async fn delete(self) -> Result<(), ApiError> {
-        delete(transaction_template::Entity::delete_by_id(self.snowflake)).await?;
-
-        Ok(())
+        in_transaction(async move {
+            let recurring_transactions = RecurringTransaction::find_all_by_template_id(self.snowflake).await?;
+
+            let children = recurring_transactions
+                .iter()
+                .map(|recurring| (RecurringTransaction::table_name(), recurring.snowflake))
+                .collect();
+            TrashEntry::trash(&self, children).await?;
+            AuditLog::record_deletion(&self).await?;
+
+            for recurring_transaction in recurring_transactions {
+                recurring_transaction.stop_after_commit();
+            }
+
+            Ok(())
+        })
+        .await
     }
 }
 
 permission_impl!(TransactionTemplate);
 
+impl Expandable for TransactionTemplate {
+    const RELATIONS: &'static [&'static str] = &["source", "destination", "currency", "budget"];
+
+    fn related(&mut self, name: &str) -> Option<Related<'_>> {
+        match name {
+            "source" => self.source_id.as_mut().map(Related::Account),
+            "destination" => self.destination_id.as_mut().map(Related::Account),
+            "currency" => Some(Related::Currency(&mut self.currency_id)),
+            "budget" => self.budget_id.as_mut().map(Related::Budget),
+            _ => None,
+        }
+    }
+}
+
 impl From<transaction_template::Model> for TransactionTemplate {
     fn from(model: transaction_template::Model) -> Self {
         Self {
@@ -119,7 +168,7 @@ impl From<transaction_template::Model> for TransactionTemplate {
 
 impl Identifiable for TransactionTemplate {
     async fn find_by_id(id: Snowflake) -> Result<Self, ApiError> {
-        find_one_or_error(transaction_template::Entity::find_by_id(id)).await.map(Self::from)
+        find_one_or_error(transaction_template::Entity::find_by_id_not_trashed(id)).await.map(Self::from)
     }
 }
 
@@ -0,0 +1,192 @@
+use sea_orm::{ColumnTrait, EntityName, EntityTrait, QueryFilter, Set};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use time::{Duration, OffsetDateTime};
+use utoipa::ToSchema;
+
+use entity::trash;
+use entity::utility::table::delete_entity;
+use entity::utility::time::get_now;
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::error::api::ApiError;
+use crate::api::pagination::PageSizeParam;
+use crate::config::Config;
+use crate::database::connection::get_database_connection;
+use crate::database::entity::{count, delete, find_all, find_all_paginated, find_one_or_error, insert};
+use crate::database::transaction::in_transaction;
+use crate::event::lifecycle::transaction::TransactionCreation;
+use crate::event::GenericEvent;
+use crate::wrapper::entity::account::Account;
+use crate::wrapper::entity::audit::context::Actor;
+use crate::wrapper::entity::audit::{AuditAction, AuditLog};
+use crate::wrapper::entity::transaction::recurring::RecurringTransaction;
+use crate::wrapper::entity::transaction::Transaction;
+use crate::wrapper::entity::user::User;
+use crate::wrapper::entity::{TableName, WrapperEntity};
+use crate::wrapper::permission::{get_user_permissions, has_permission_or_error_raw, Permissions};
+use crate::wrapper::types::phantom::{Identifiable, Phantom};
+
+pub(crate) mod purge;
+
+/// A soft-deleted entity that can be restored until `restorable_until`.
+/// Entities that were deleted along with it (e.g. the transactions of an account) are restored with it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct TrashEntry {
+    pub(crate) entity_type: String,
+    pub(crate) entity_id: Snowflake,
+    pub(crate) deleted_by: Option<Phantom<User>>,
+    #[schema(value_type = Option<Object>)]
+    pub(crate) snapshot: Option<Value>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub(crate) deleted_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub(crate) restorable_until: OffsetDateTime,
+}
+
+impl TrashEntry {
+    pub(crate) async fn trash<T: WrapperEntity + Serialize>(
+        entity: &T,
+        children: Vec<(&'static str, Snowflake)>,
+    ) -> Result<(), ApiError> {
+        let actor = Actor::current().await?;
+        let deleted_by = actor.user_id.map(|user_id| user_id.id);
+        let deleted_at = get_now();
+
+        insert(trash::ActiveModel {
+            entity_type: Set(T::table_name().to_string()),
+            entity_id: Set(entity.get_id().id),
+            parent_type: Set(None),
+            parent_id: Set(None),
+            deleted_by: Set(deleted_by),
+            snapshot: Set(Some(serde_json::to_value(entity)?)),
+            deleted_at: Set(deleted_at),
+        })
+        .await?;
+        for (entity_type, entity_id) in children {
+            insert(trash::ActiveModel {
+                entity_type: Set(entity_type.to_string()),
+                entity_id: Set(entity_id.id),
+                parent_type: Set(Some(T::table_name().to_string())),
+                parent_id: Set(Some(entity.get_id().id)),
+                deleted_by: Set(deleted_by),
+                snapshot: Set(None),
+                deleted_at: Set(deleted_at),
+            })
+            .await?;
+            AuditLog::record_cascade(entity_type, entity_id, AuditAction::Delete).await?;
+        }
+
+        Ok(())
+    }
+
+    // children can only be restored together with their parent
+    pub(crate) async fn find(entity_type: &str, entity_id: Snowflake) -> Result<Self, ApiError> {
+        find_one_or_error(trash::Entity::find_entry(entity_type, entity_id).filter(trash::Column::ParentId.is_null()))
+            .await
+            .map(Self::from)
+    }
+
+    pub(crate) async fn find_all_by_user_paginated(
+        user_id: Snowflake,
+        page_size: &PageSizeParam,
+    ) -> Result<Vec<Self>, ApiError> {
+        Ok(find_all_paginated(trash::Entity::find_all_by_user_id(user_id), page_size)
+            .await?
+            .into_iter()
+            .map(Self::from)
+            .collect())
+    }
+
+    pub(crate) async fn count_all_by_user(user_id: Snowflake) -> Result<u64, ApiError> {
+        count(trash::Entity::find_all_by_user_id(user_id)).await
+    }
+
+    pub(crate) async fn find_all_expired() -> Result<Vec<Self>, ApiError> {
+        let deleted_before = get_now() - retention();
+
+        Ok(find_all(trash::Entity::find_all_deleted_before(deleted_before))
+            .await?
+            .into_iter()
+            .map(Self::from)
+            .collect())
+    }
+
+    // the permissions the user had before the entity was trashed
+    pub(crate) async fn has_permission_or_error(
+        &self,
+        user_id: Snowflake,
+        permissions: Permissions,
+    ) -> Result<(), ApiError> {
+        let user_permissions = get_user_permissions(&self.entity_type, self.entity_id, user_id).await?;
+
+        has_permission_or_error_raw(user_permissions, permissions, trash::Entity.table_name())
+    }
+
+    pub(crate) async fn restore(self) -> Result<(), ApiError> {
+        in_transaction(async move {
+            if self.restorable_until < get_now() {
+                return Err(ApiError::RestoreWindowExpired());
+            }
+
+            let children: Vec<(String, Snowflake)> =
+                find_all(trash::Entity::find_all_by_parent(&self.entity_type, self.entity_id))
+                    .await?
+                    .into_iter()
+                    .map(|child| (child.entity_type, Snowflake::from(child.entity_id)))
+                    .collect();
+            self.remove_from_trash().await?;
+            for (entity_type, entity_id) in &children {
+                AuditLog::record_cascade(entity_type, *entity_id, AuditAction::Restore).await?;
+            }
+
+            // accounts take their transactions with them, so their balances were never touched
+            let reapply_balances = self.entity_type != Account::table_name();
+            let restored = children.into_iter().chain([(self.entity_type.clone(), self.entity_id)]);
+            for (entity_type, entity_id) in restored {
+                if entity_type == Transaction::table_name() && reapply_balances {
+                    TransactionCreation::new(Transaction::find_by_id(entity_id).await?).fire().await?;
+                } else if entity_type == RecurringTransaction::table_name() {
+                    RecurringTransaction::find_by_id(entity_id).await?.start_recurring_transaction().await?;
+                }
+            }
+
+            AuditLog::record_restoration(&self.entity_type, self.entity_id, self.snapshot).await
+        })
+        .await
+    }
+
+    pub(crate) async fn purge(self) -> Result<(), ApiError> {
+        // children are removed by the foreign key cascades
+        delete_entity(&self.entity_type, self.entity_id, get_database_connection()).await?;
+
+        self.remove_from_trash().await
+    }
+
+    async fn remove_from_trash(&self) -> Result<(), ApiError> {
+        delete(
+            trash::Entity::delete_many()
+                .filter(trash::Column::ParentType.eq(self.entity_type.as_str()))
+                .filter(trash::Column::ParentId.eq(self.entity_id)),
+        )
+        .await?;
+        delete(trash::Entity::delete_by_id((self.entity_type.clone(), self.entity_id.id))).await
+    }
+}
+
+fn retention() -> Duration {
+    Duration::days(Config::get_config().trash.retention_days as i64)
+}
+
+impl From<trash::Model> for TrashEntry {
+    fn from(model: trash::Model) -> Self {
+        Self {
+            entity_type: model.entity_type,
+            entity_id: Snowflake::from(model.entity_id),
+            deleted_by: Phantom::from_option(model.deleted_by),
+            snapshot: model.snapshot,
+            deleted_at: model.deleted_at,
+            restorable_until: model.deleted_at + retention(),
+        }
+    }
+}
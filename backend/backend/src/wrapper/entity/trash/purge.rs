@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+use tokio::time::interval;
+use tracing::error;
+
+use crate::scheduling::schedule_task_with_interval;
+use crate::wrapper::entity::trash::TrashEntry;
+
+pub(crate) const PURGE_INTERVAL_SECONDS: u64 = 60 * 60;
+
+pub(crate) fn schedule_purge_task() {
+    let interval = interval(Duration::from_secs(PURGE_INTERVAL_SECONDS));
+    schedule_task_with_interval(interval, purge);
+}
+
+async fn purge() {
+    let entries = match TrashEntry::find_all_expired().await {
+        Ok(entries) => entries,
+        Err(e) => {
+            error!("Could not load expired trash entries: {}", e);
+            return;
+        }
+    };
+
+    for entry in entries {
+        let (entity_type, entity_id) = (entry.entity_type.clone(), entry.entity_id);
+        if let Err(e) = entry.purge().await {
+            error!("Could not purge {} {}: {}", entity_type, entity_id, e);
+        }
+    }
+}
@@ -2,24 +2,101 @@ use actix_web::dev::Payload;
 use actix_web::web::Json;
 use actix_web::{FromRequest, HttpRequest};
 use futures_util::future::LocalBoxFuture;
+use sea_orm::{EntityTrait, Order, QueryOrder, Select};
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 use validator::Validate;
 
+use entity::user;
+
 use crate::api::error::api::ApiError;
-use crate::util::validation::{validate_password, validate_unique_username};
+use crate::util::validation::{validate_password, validate_unique_email, validate_unique_username};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
 pub(crate) struct UserRegistration {
     #[validate(length(min = 1), custom(function = "validate_unique_username"))]
     pub(crate) username: String,
+    #[validate(email, custom(function = "validate_unique_email"))]
+    pub(crate) email: Option<String>,
+    pub(crate) display_name: Option<String>,
+    #[validate(custom(function = "validate_password"))]
+    pub(crate) password: String,
+    /// Required while registration is invite-only. If given otherwise, it still has to be valid and is used up.
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    pub(crate) invite_code: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
+pub(crate) struct UserCreation {
+    #[validate(length(min = 1), custom(function = "validate_unique_username"))]
+    pub(crate) username: String,
+    #[validate(email, custom(function = "validate_unique_email"))]
+    pub(crate) email: Option<String>,
+    pub(crate) display_name: Option<String>,
+    #[validate(custom(function = "validate_password"))]
+    pub(crate) password: String,
+    #[serde(default)]
+    pub(crate) is_admin: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct UserFilter {
+    /// Only return users whose username, email or display name contains this text, ignoring case.
+    pub(crate) search: Option<String>,
+}
+
+impl UserFilter {
+    pub(crate) fn select(&self) -> Select<user::Entity> {
+        match &self.search {
+            Some(search) => user::Entity::search(search),
+            None => user::Entity::find().order_by(user::Column::Id, Order::Desc),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
+pub(crate) struct UserProfile {
     #[validate(email)]
     pub(crate) email: Option<String>,
     pub(crate) display_name: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
+pub(crate) struct PasswordChange {
+    #[validate(length(min = 1))]
+    pub(crate) old_password: String,
     #[validate(custom(function = "validate_password"))]
+    pub(crate) new_password: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
+pub(crate) struct AdminPasswordReset {
+    #[validate(custom(function = "validate_password"))]
+    pub(crate) new_password: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
+pub(crate) struct UserDeletion {
+    #[validate(length(min = 1))]
     pub(crate) password: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
+pub(crate) struct PasswordResetRequest {
+    #[validate(email)]
+    pub(crate) email: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
+pub(crate) struct PasswordResetConfirmation {
+    #[validate(length(min = 1))]
+    pub(crate) token: String,
+    #[validate(custom(function = "validate_password"))]
+    pub(crate) new_password: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
 pub(crate) struct Credentials {
     #[validate(length(min = 1))]
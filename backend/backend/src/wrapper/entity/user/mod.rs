@@ -1,28 +1,48 @@
+use std::sync::Arc;
+
 use actix_web::dev::Payload;
 use actix_web::{FromRequest, HttpRequest};
 use futures_util::future::LocalBoxFuture;
-use sea_orm::{EntityName, EntityTrait};
+use sea_orm::{EntityName, EntityTrait, NotSet, Set};
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 use utoipa::ToSchema;
+use validator::ValidationErrors;
 
 use dto::Credentials;
+use entity::permissions;
 use entity::prelude::User as DbUser;
 use entity::user;
 use entity::user::Model;
+use entity::utility::time::get_now;
 use utility::snowflake::entity::Snowflake;
 
 use crate::api::error::api::ApiError;
-use crate::database::entity::{count, find_one, find_one_or_error, insert};
+use crate::api::error::validation::ValidationCode;
+use crate::api::pagination::PageSizeParam;
+use crate::config::{Config, RegistrationMode};
+use crate::database::entity::{
+    count, delete, find_all, find_all_paginated, find_one, find_one_or_error, insert, update,
+};
+use crate::database::transaction::in_transaction;
 use crate::permission_impl;
-use crate::util::auth::extract_bearer_token;
+use crate::util::auth::authenticate;
+use crate::wrapper::entity::account::Account;
+use crate::wrapper::entity::audit::AuditLog;
+use crate::wrapper::entity::budget::Budget;
+use crate::wrapper::entity::invitation::Invitation;
 use crate::wrapper::entity::session::Session;
-use crate::wrapper::entity::user::dto::UserRegistration;
+use crate::wrapper::entity::transaction::recurring::RecurringTransaction;
+use crate::wrapper::entity::transaction::template::TransactionTemplate;
+use crate::wrapper::entity::transaction::Transaction;
+use crate::wrapper::entity::user::dto::{UserCreation, UserFilter, UserProfile, UserRegistration};
 use crate::wrapper::entity::{TableName, WrapperEntity};
 use crate::wrapper::permission::{Permission, Permissions};
 use crate::wrapper::types::phantom::{Identifiable, Phantom};
 
 pub(crate) mod dto;
+pub(crate) mod password_reset;
+pub(crate) mod totp;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub(crate) struct User {
@@ -34,6 +54,8 @@ pub(crate) struct User {
     #[serde(with = "time::serde::rfc3339")]
     pub(crate) created_at: OffsetDateTime,
     pub(crate) is_admin: bool,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub(crate) disabled_at: Option<OffsetDateTime>,
 }
 
 impl User {
@@ -41,6 +63,48 @@ impl User {
         Ok(count(user::Entity::find_by_id(id)).await? > 0)
     }
 
+    pub(crate) fn is_disabled(&self) -> bool {
+        self.disabled_at.is_some()
+    }
+
+    pub(crate) fn require_admin(&self) -> Result<(), ApiError> {
+        if !self.is_admin {
+            return Err(ApiError::MissingPermissions());
+        }
+
+        Ok(())
+    }
+
+    pub(crate) async fn find_enabled_by_id(id: Snowflake) -> Result<Self, ApiError> {
+        let user = Self::find_by_id(id).await?;
+        if user.is_disabled() {
+            return Err(ApiError::UserDisabled());
+        }
+
+        Ok(user)
+    }
+
+    pub(crate) async fn find_all_paginated(
+        filter: &UserFilter,
+        page_size: &PageSizeParam,
+    ) -> Result<Vec<Self>, ApiError> {
+        Ok(find_all_paginated(filter.select(), page_size).await?.into_iter().map(Self::from).collect())
+    }
+
+    pub(crate) async fn count_all(filter: &UserFilter) -> Result<u64, ApiError> {
+        count(filter.select()).await
+    }
+
+    pub(crate) async fn find_all_by_ids(ids: Vec<Snowflake>) -> Result<Vec<Self>, ApiError> {
+        let ids = ids.into_iter().map(|id| id.id).collect();
+
+        Ok(find_all(user::Entity::find_all_by_ids(ids)).await?.into_iter().map(Self::from).collect())
+    }
+
+    pub(crate) async fn find_by_username(username: &str) -> Result<Self, ApiError> {
+        find_one_or_error(DbUser::find_by_username(username)).await.map(Self::from)
+    }
+
     pub(crate) async fn authenticate(credentials: &Credentials) -> Result<Self, ApiError> {
         let user = find_one(DbUser::find_by_username(credentials.username.as_str())).await;
         match user {
@@ -55,22 +119,164 @@ impl User {
         }
     }
 
+    pub(crate) async fn verify_password(&self, password: &str) -> Result<(), ApiError> {
+        let user = find_one_or_error(user::Entity::find_by_id(self.snowflake)).await?;
+        match user.verify_password(password.as_bytes()) {
+            Ok(true) => Ok(()),
+            _ => Err(ApiError::InvalidCredentials()),
+        }
+    }
+
+    /// Registers a user on their own, which the registration mode might require an invitation code for.
     pub(crate) async fn register(registration: UserRegistration) -> Result<Self, ApiError> {
-        match user::ActiveModel::register(
-            registration.username,
-            registration.email,
-            registration.display_name,
-            registration.password,
-        ) {
-            Ok(user) => {
-                let model = insert(user).await?;
-                let user = Self::from(model);
-                user.add_permission(user.snowflake, Permissions::all()).await?;
-
-                Ok(user)
+        match Config::get_config().registration.mode {
+            RegistrationMode::Closed => return Err(ApiError::RegistrationClosed()),
+            RegistrationMode::InviteOnly if registration.invite_code.is_none() => {
+                return Err(ApiError::InvalidInvitationCode())
+            }
+            _ => {}
+        }
+        // a registration that fails after the invitation was consumed gives the use back
+        in_transaction(async move {
+            if let Some(invite_code) = &registration.invite_code {
+                Invitation::consume(invite_code).await?;
+            }
+
+            Self::create(UserCreation {
+                username: registration.username,
+                email: registration.email,
+                display_name: registration.display_name,
+                password: registration.password,
+                is_admin: false,
+            })
+            .await
+        })
+        .await
+    }
+
+    pub(crate) async fn create(creation: UserCreation) -> Result<Self, ApiError> {
+        let mut active_model =
+            user::ActiveModel::register(creation.username, creation.email, creation.display_name, creation.password)?;
+        active_model.is_admin = Set(creation.is_admin);
+        in_transaction(async move {
+            let user = Self::from(insert(active_model).await?);
+            AuditLog::record_creation(&user).await?;
+            user.add_permission(user.snowflake, Permissions::all()).await?;
+
+            Ok(user)
+        })
+        .await
+    }
+
+    pub(crate) async fn set_admin(self, is_admin: bool) -> Result<Self, ApiError> {
+        let active_model = user::ActiveModel {
+            id: Set(self.snowflake.id),
+            is_admin: Set(is_admin),
+            ..Default::default()
+        };
+        in_transaction(async move {
+            let user = Self::from(update(active_model).await?);
+            AuditLog::record_update(&self, &user).await?;
+
+            Ok(user)
+        })
+        .await
+    }
+
+    /// Disabled users can not sign in or use their API tokens, all of their Sessions are signed out.
+    pub(crate) async fn set_disabled(self, disabled: bool) -> Result<Self, ApiError> {
+        let active_model = user::ActiveModel {
+            id: Set(self.snowflake.id),
+            disabled_at: Set(disabled.then(get_now)),
+            ..Default::default()
+        };
+        in_transaction(async move {
+            if disabled {
+                Session::delete_all_with_user(self.snowflake, None).await?;
+            }
+            let user = Self::from(update(active_model).await?);
+            AuditLog::record_update(&self, &user).await?;
+
+            Ok(user)
+        })
+        .await
+    }
+
+    pub(crate) async fn update_profile(self, profile: UserProfile) -> Result<Self, ApiError> {
+        if let Some(email) = &profile.email {
+            let owner = find_one(DbUser::find_by_email(email)).await?;
+            if owner.is_some_and(|owner| owner.id != self.snowflake.id) {
+                let mut errors = ValidationErrors::new();
+                errors.add("email", ValidationCode::EMAIL_NOT_UNIQUE.into());
+
+                return Err(ApiError::from(errors));
             }
-            Err(e) => Err(ApiError::from(e)),
         }
+
+        // omitted fields are left untouched
+        let active_model = user::ActiveModel {
+            id: Set(self.snowflake.id),
+            email: profile.email.map_or(NotSet, |email| Set(Some(email))),
+            display_name: profile.display_name.map_or(NotSet, |display_name| Set(Some(display_name))),
+            ..Default::default()
+        };
+        in_transaction(async move {
+            let user = Self::from(update(active_model).await?);
+            AuditLog::record_update(&self, &user).await?;
+
+            Ok(user)
+        })
+        .await
+    }
+
+    pub(crate) async fn change_password(&self, password: &str) -> Result<(), ApiError> {
+        update(user::ActiveModel::change_password(self.snowflake.id, password)?).await?;
+
+        Ok(())
+    }
+
+    // the user's permissions are cascaded, entities nobody else owns are trashed so the trash purge removes them
+    pub(crate) async fn delete(self) -> Result<(), ApiError> {
+        in_transaction(async move {
+            // accounts and budgets take their transactions, templates and recurring transactions with them
+            for account in self.find_owned_only::<Account>().await? {
+                account.delete().await?;
+            }
+            for budget in self.find_owned_only::<Budget>().await? {
+                budget.delete().await?;
+            }
+            for template in self.find_owned_only::<TransactionTemplate>().await? {
+                template.delete().await?;
+            }
+            for recurring_transaction in self.find_owned_only::<RecurringTransaction>().await? {
+                recurring_transaction.delete().await?;
+            }
+            for transaction in self.find_owned_only::<Transaction>().await? {
+                transaction.delete().await?;
+            }
+
+            AuditLog::record_deletion(&self).await?;
+            Session::delete_all_with_user(self.snowflake, None).await?;
+            delete(user::Entity::delete_by_id(self.snowflake.id)).await
+        })
+        .await
+    }
+
+    async fn find_owned_only<T: Identifiable + TableName>(&self) -> Result<Vec<T>, ApiError> {
+        let owner_permissions = Permissions::all().bits() as i32;
+        let grants = find_all(permissions::Entity::find_owned_only_by_user_id(
+            self.snowflake,
+            T::table_name(),
+            owner_permissions,
+        ))
+        .await?;
+
+        let mut entities = Vec::with_capacity(grants.len());
+        for grant in grants {
+            entities.push(T::find_by_id(Snowflake::from(grant.entity_id)).await?);
+        }
+
+        Ok(entities)
     }
 }
 
@@ -104,11 +310,9 @@ impl FromRequest for User {
     fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
         let req = req.clone();
         Box::pin(async move {
-            // get bearer token from request
-            let token = extract_bearer_token(&req)?;
-            let user_id = Session::find_user_id(token).await?;
+            let user_id = authenticate(&req).await?;
 
-            Self::find_by_id(user_id).await
+            Self::find_enabled_by_id(user_id).await
         })
     }
 }
@@ -120,11 +324,13 @@ impl FromRequest for Phantom<User> {
     fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
         let req = req.clone();
         Box::pin(async move {
-            let token = extract_bearer_token(&req)?;
-            let user_id = Session::find_user_id(token).await?;
-            User::exists(user_id).await?;
+            let user_id = authenticate(&req).await?;
+            let user = User::find_enabled_by_id(user_id).await?;
+
+            let mut phantom = Self::new(user_id);
+            phantom.set_inner(Arc::new(user));
 
-            Ok(Self::new(user_id))
+            Ok(phantom)
         })
     }
 }
@@ -138,6 +344,7 @@ impl From<Model> for User {
             display_name: value.display_name,
             created_at: value.created_at,
             is_admin: value.is_admin,
+            disabled_at: value.disabled_at,
         }
     }
 }
@@ -0,0 +1,86 @@
+use sea_orm::Set;
+use time::Duration;
+use tracing::{error, info};
+
+use entity::password_reset;
+use entity::prelude::User as DbUser;
+use entity::utility::hashing::{generate_salt, hash_token};
+use entity::utility::time::get_now;
+
+use crate::api::error::api::ApiError;
+use crate::config::Config;
+use crate::database::entity::{delete, delete_many, find_one, insert};
+use crate::database::transaction::{after_commit, in_transaction};
+use crate::mail::Mail;
+use crate::wrapper::entity::session::Session;
+use crate::wrapper::entity::user::User;
+use crate::wrapper::types::phantom::Identifiable;
+
+const RESET_TOKEN_LENGTH: usize = 48;
+
+/// Mailed, single-use tokens that allow setting a new password without knowing the old one.
+pub(crate) struct PasswordReset;
+
+impl PasswordReset {
+    // does not reveal whether the email belongs to a user, a pending token is replaced by the new one
+    // the mail is sent after the request completed, so neither its errors nor its latency tell the emails apart
+    pub(crate) async fn request(email: &str) -> Result<(), ApiError> {
+        let Some(user) = find_one(DbUser::find_by_email(email)).await? else {
+            info!("Password reset requested for unknown email.");
+            return Ok(());
+        };
+
+        let token = generate_salt(RESET_TOKEN_LENGTH);
+        let lifetime_minutes = Config::get_config().password_reset.lifetime_minutes;
+        let now = get_now();
+
+        let body = format!(
+            "Hello {},\n\nuse the following token to reset your password:\n\n{}\n\nThe token expires in {} minutes. \
+             If you did not request a password reset, you can ignore this mail.",
+            user.display_name.as_deref().unwrap_or(&user.username),
+            token,
+            lifetime_minutes
+        );
+        let mail = Mail::new(email, "Reset your password", body);
+
+        in_transaction(async move {
+            delete(password_reset::Entity::delete_all_by_user_id(user.id.into())).await?;
+            insert(password_reset::ActiveModel {
+                token_hash: Set(hash_token(&token)),
+                user: Set(user.id),
+                expires_at: Set(now + Duration::minutes(lifetime_minutes as i64)),
+                created_at: Set(now),
+            })
+            .await?;
+            after_commit(async move {
+                if let Err(err) = mail.send().await {
+                    error!("Could not send password reset mail. Error: {:?}", err);
+                }
+            });
+
+            Ok(())
+        })
+        .await
+    }
+
+    // a rejected password does not use up the token, it is validated with the request
+    pub(crate) async fn confirm(token: &str, new_password: &str) -> Result<(), ApiError> {
+        let now = get_now();
+        in_transaction(async move {
+            let reset = find_one(password_reset::Entity::find_valid_by_token(token, now))
+                .await?
+                .ok_or_else(ApiError::InvalidPasswordResetToken)?;
+            // a concurrent confirmation waits for this one and then deletes nothing, so the token is used only once
+            if delete_many(password_reset::Entity::delete_valid_by_token(token, now)).await? == 0 {
+                return Err(ApiError::InvalidPasswordResetToken());
+            }
+            delete(password_reset::Entity::delete_all_by_user_id(reset.user.into())).await?;
+
+            let user = User::find_by_id(reset.user.into()).await?;
+            user.change_password(new_password).await?;
+
+            Session::delete_all_with_user(user.snowflake, None).await
+        })
+        .await
+    }
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct TotpEnrollment {
+    /// The base32 encoded secret, for authenticator apps that can not scan the `otpauth_uri`.
+    pub(crate) secret: String,
+    pub(crate) otpauth_uri: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct TotpRecoveryCodes {
+    pub(crate) recovery_codes: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
+pub(crate) struct TotpCode {
+    #[validate(length(min = 1))]
+    pub(crate) code: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
+pub(crate) struct TotpDisable {
+    #[validate(length(min = 1))]
+    pub(crate) password: String,
+    /// A one-time password or a recovery code.
+    #[validate(length(min = 1))]
+    pub(crate) code: String,
+}
@@ -0,0 +1,181 @@
+use sea_orm::{EntityTrait, Set};
+use time::OffsetDateTime;
+
+use entity::utility::hashing::{generate_salt, hash_string};
+use entity::utility::time::get_now;
+use entity::{totp_recovery_code, user_totp};
+use utility::snowflake::entity::Snowflake;
+use utility::totp::Totp;
+
+use crate::api::error::api::ApiError;
+use crate::config::Config;
+use crate::database::entity::{delete, delete_many, find_all, find_one, insert, update, update_many};
+use crate::wrapper::entity::user::totp::dto::{TotpEnrollment, TotpRecoveryCodes};
+use crate::wrapper::entity::user::User;
+use crate::wrapper::types::phantom::Phantom;
+use crate::SNOWFLAKE_GENERATOR;
+
+pub(crate) mod dto;
+
+pub(crate) const RECOVERY_CODE_COUNT: usize = 10;
+const RECOVERY_CODE_LENGTH: usize = 12;
+
+/// The second factor of a user. The current time is always passed in, so the verification can be tested with a
+/// fixed clock.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct UserTotp {
+    pub(crate) user_id: Phantom<User>,
+    pub(crate) totp: Totp,
+    pub(crate) last_used_step: Option<i64>,
+    pub(crate) confirmed_at: Option<OffsetDateTime>,
+    pub(crate) created_at: OffsetDateTime,
+}
+
+impl UserTotp {
+    // a pending enrollment is replaced, so a lost secret can simply be enrolled again
+    pub(crate) async fn enroll(user: &User) -> Result<TotpEnrollment, ApiError> {
+        if let Some(existing) = Self::find_by_user(user.snowflake).await? {
+            if existing.is_confirmed() {
+                return Err(ApiError::TotpAlreadyEnabled());
+            }
+            delete(user_totp::Entity::delete_by_id(user.snowflake.id)).await?;
+        }
+
+        let totp = Totp::generate();
+        insert(user_totp::ActiveModel {
+            user: Set(user.snowflake.id),
+            secret: Set(encrypt_secret(&totp)?),
+            last_used_step: Set(None),
+            confirmed_at: Set(None),
+            created_at: Set(get_now()),
+        })
+        .await?;
+
+        Ok(TotpEnrollment {
+            secret: totp.to_base32(),
+            otpauth_uri: totp.provisioning_uri(&Config::get_config().totp.issuer, &user.username),
+        })
+    }
+
+    pub(crate) async fn confirm(mut self, code: &str, now: OffsetDateTime) -> Result<TotpRecoveryCodes, ApiError> {
+        if self.is_confirmed() {
+            return Err(ApiError::TotpAlreadyEnabled());
+        }
+        if !self.verify_code(code, now).await? {
+            return Err(ApiError::InvalidTotpCode());
+        }
+
+        self.confirmed_at = Some(now);
+        update(self.to_active_model()?).await?;
+
+        self.regenerate_recovery_codes().await
+    }
+
+    /// Accepts either a one-time password or one of the recovery codes, which is used up afterwards. Returns whether
+    /// the code was accepted, errors are only returned if the check itself failed.
+    pub(crate) async fn verify(&mut self, code: &str, now: OffsetDateTime) -> Result<bool, ApiError> {
+        Ok(self.verify_code(code, now).await? || self.use_recovery_code(code).await?)
+    }
+
+    pub(crate) async fn verify_or_error(&mut self, code: &str, now: OffsetDateTime) -> Result<(), ApiError> {
+        if !self.verify(code, now).await? {
+            return Err(ApiError::InvalidTotpCode());
+        }
+
+        Ok(())
+    }
+
+    // every step is only accepted once, so an observed code can not be replayed, not even by a concurrent login
+    async fn verify_code(&mut self, code: &str, now: OffsetDateTime) -> Result<bool, ApiError> {
+        let Some(step) = self.totp.verify(code, now) else {
+            return Ok(false);
+        };
+        if update_many(user_totp::Entity::use_step(self.user_id.get_id(), step)).await? == 0 {
+            return Ok(false);
+        }
+
+        self.last_used_step = Some(step);
+        Ok(true)
+    }
+
+    async fn use_recovery_code(&self, code: &str) -> Result<bool, ApiError> {
+        let code = code.trim().to_lowercase();
+        let recovery_codes = find_all(totp_recovery_code::Entity::find_all_by_user_id(self.user_id.get_id())).await?;
+
+        match recovery_codes.into_iter().find(|recovery_code| recovery_code.verify(&code)) {
+            // a code used by a concurrent login is already gone
+            Some(recovery_code) => {
+                Ok(delete_many(totp_recovery_code::Entity::delete_by_id(recovery_code.id)).await? > 0)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Replaces all recovery codes. The plain codes are only ever returned here.
+    pub(crate) async fn regenerate_recovery_codes(&self) -> Result<TotpRecoveryCodes, ApiError> {
+        delete(totp_recovery_code::Entity::delete_all_by_user_id(self.user_id.get_id())).await?;
+
+        let mut recovery_codes = Vec::with_capacity(RECOVERY_CODE_COUNT);
+        for _ in 0..RECOVERY_CODE_COUNT {
+            let code = generate_salt(RECOVERY_CODE_LENGTH).to_lowercase();
+            insert(totp_recovery_code::ActiveModel {
+                id: Set(SNOWFLAKE_GENERATOR.next_id()?),
+                user: Set(self.user_id.get_id().id),
+                code: Set(hash_string(&code)?),
+                created_at: Set(get_now()),
+            })
+            .await?;
+            recovery_codes.push(code);
+        }
+
+        Ok(TotpRecoveryCodes {
+            recovery_codes,
+        })
+    }
+
+    pub(crate) async fn disable(self) -> Result<(), ApiError> {
+        delete(totp_recovery_code::Entity::delete_all_by_user_id(self.user_id.get_id())).await?;
+        delete(user_totp::Entity::delete_by_id(self.user_id.get_id().id)).await
+    }
+
+    pub(crate) fn is_confirmed(&self) -> bool {
+        self.confirmed_at.is_some()
+    }
+
+    pub(crate) async fn find_by_user(user_id: Snowflake) -> Result<Option<Self>, ApiError> {
+        let Some(model) = find_one(user_totp::Entity::find_by_id(user_id.id)).await? else {
+            return Ok(None);
+        };
+        let totp = Totp::decrypt(&Config::get_config().totp.encryption_key, &model.secret)?;
+
+        Ok(Some(Self::from_model(model, totp)))
+    }
+
+    pub(crate) async fn find_confirmed_by_user(user_id: Snowflake) -> Result<Option<Self>, ApiError> {
+        Ok(Self::find_by_user(user_id).await?.filter(Self::is_confirmed))
+    }
+
+    fn to_active_model(&self) -> Result<user_totp::ActiveModel, ApiError> {
+        Ok(user_totp::ActiveModel {
+            user: Set(self.user_id.get_id().id),
+            secret: Set(encrypt_secret(&self.totp)?),
+            last_used_step: Set(self.last_used_step),
+            confirmed_at: Set(self.confirmed_at),
+            created_at: Set(self.created_at),
+        })
+    }
+
+    fn from_model(model: user_totp::Model, totp: Totp) -> Self {
+        Self {
+            user_id: Phantom::from(model.user),
+            totp,
+            last_used_step: model.last_used_step,
+            confirmed_at: model.confirmed_at,
+            created_at: model.created_at,
+        }
+    }
+}
+
+fn encrypt_secret(totp: &Totp) -> Result<String, ApiError> {
+    Ok(totp.encrypt(&Config::get_config().totp.encryption_key)?)
+}
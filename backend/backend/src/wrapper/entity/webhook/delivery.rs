@@ -0,0 +1,283 @@
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use reqwest::header::CONTENT_TYPE;
+use reqwest::redirect::Policy;
+use reqwest::{Client, ClientBuilder, Url};
+use sea_orm::{EntityName, Set};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use time::{Duration as TimeDuration, OffsetDateTime};
+use tokio::task::spawn_blocking;
+use utoipa::ToSchema;
+
+use entity::utility::time::get_now;
+use entity::webhook_delivery;
+use utility::signature::sign;
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::error::api::ApiError;
+use crate::api::pagination::PageSizeParam;
+use crate::config::Config;
+use crate::database::entity::{count, find_all, find_all_paginated, find_one_or_error, insert, update};
+use crate::wrapper::entity::webhook::event::WebhookEvent;
+use crate::wrapper::entity::webhook::Webhook;
+use crate::wrapper::entity::{TableName, WrapperEntity};
+use crate::wrapper::types::phantom::Phantom;
+use crate::SNOWFLAKE_GENERATOR;
+
+pub(crate) const EVENT_HEADER: &str = "X-Financrr-Event";
+pub(crate) const DELIVERY_HEADER: &str = "X-Financrr-Delivery";
+pub(crate) const TIMESTAMP_HEADER: &str = "X-Financrr-Timestamp";
+pub(crate) const SIGNATURE_HEADER: &str = "X-Financrr-Signature";
+// keeps the doubling from overflowing, the attempts are capped by the config anyway
+const MAX_BACKOFF_EXPONENT: u32 = 16;
+
+static HTTP_CLIENT: LazyLock<Client> =
+    LazyLock::new(|| http_client_builder().build().expect("Could not build webhook http client!"));
+
+/// The JSON body of every delivery. `previous` is only set for update events.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct WebhookPayload {
+    /// The id of the delivery, it stays the same when a delivery is retried.
+    pub(crate) id: Snowflake,
+    pub(crate) event: WebhookEvent,
+    #[serde(with = "time::serde::rfc3339")]
+    pub(crate) created_at: OffsetDateTime,
+    #[schema(value_type = Object)]
+    pub(crate) data: Value,
+    #[schema(value_type = Option<Object>)]
+    pub(crate) previous: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct WebhookDelivery {
+    #[serde(rename = "id")]
+    pub(crate) snowflake: Snowflake,
+    pub(crate) webhook_id: Phantom<Webhook>,
+    pub(crate) event: WebhookEvent,
+    pub(crate) payload: WebhookPayload,
+    pub(crate) attempts: i32,
+    /// The status code of the last attempt, if the receiver answered.
+    pub(crate) response_status: Option<i32>,
+    /// Why the last attempt failed.
+    pub(crate) error: Option<String>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub(crate) delivered_at: Option<OffsetDateTime>,
+    /// When the delivery is retried next, not set once it was delivered or ran out of attempts.
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub(crate) next_attempt_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub(crate) created_at: OffsetDateTime,
+}
+
+impl WebhookDelivery {
+    pub(crate) async fn create(
+        webhook: &Webhook,
+        event: WebhookEvent,
+        data: Value,
+        previous: Option<Value>,
+    ) -> Result<Self, ApiError> {
+        let snowflake = SNOWFLAKE_GENERATOR.next_id()?;
+        let now = get_now();
+        let retry_base_seconds = Config::get_config().webhook.retry_base_seconds;
+        let payload = WebhookPayload {
+            id: Snowflake::from(snowflake),
+            event,
+            created_at: now,
+            data,
+            previous,
+        };
+        let active_model = webhook_delivery::ActiveModel {
+            id: Set(snowflake),
+            webhook: Set(webhook.snowflake.id),
+            event: Set(event.as_str().to_string()),
+            payload: Set(serde_json::to_value(&payload)?),
+            attempts: Set(0),
+            response_status: Set(None),
+            error: Set(None),
+            delivered_at: Set(None),
+            // the first attempt is made right away, this only picks the delivery up if that never happens
+            next_attempt_at: Set(Some(now + TimeDuration::seconds(retry_base_seconds as i64))),
+            created_at: Set(now),
+        };
+
+        Self::try_from(insert(active_model).await?)
+    }
+
+    /// Sends the delivery once more to the webhook it belongs to.
+    pub(crate) async fn attempt(self) -> Result<Self, ApiError> {
+        let webhook = self.webhook_id.fetch_inner().await?;
+
+        self.deliver(&webhook).await
+    }
+
+    /// Sends the delivery and records the outcome. Failures are scheduled for a retry with exponential backoff
+    /// until the configured attempts are used up.
+    pub(crate) async fn deliver(self, webhook: &Webhook) -> Result<Self, ApiError> {
+        let body = serde_json::to_vec(&self.payload)?;
+        let outcome = self.send(webhook, body).await;
+
+        let config = &Config::get_config().webhook;
+        let attempts = self.attempts + 1;
+        let now = get_now();
+        let (response_status, error) = match outcome {
+            Ok(status) if (200..300).contains(&status) => (Some(status), None),
+            Ok(status) => (Some(status), Some(format!("The receiver answered with status {}.", status))),
+            Err(error) => (None, Some(error)),
+        };
+        let delivered_at = error.is_none().then_some(now);
+        let next_attempt_at = match error {
+            Some(_) if attempts < config.max_attempts as i32 => {
+                let exponent = (attempts as u32 - 1).min(MAX_BACKOFF_EXPONENT);
+                Some(now + TimeDuration::seconds((config.retry_base_seconds << exponent) as i64))
+            }
+            _ => None,
+        };
+
+        let active_model = webhook_delivery::ActiveModel {
+            id: Set(self.snowflake.id),
+            attempts: Set(attempts),
+            response_status: Set(response_status.map(i32::from)),
+            error: Set(error),
+            delivered_at: Set(delivered_at),
+            next_attempt_at: Set(next_attempt_at),
+            ..Default::default()
+        };
+
+        Self::try_from(update(active_model).await?)
+    }
+
+    async fn send(&self, webhook: &Webhook, body: Vec<u8>) -> Result<u16, String> {
+        let url = Url::parse(&webhook.url).map_err(|e| format!("Invalid URL: {}", e))?;
+        let client = if Config::get_config().webhook.allow_private_addresses {
+            HTTP_CLIENT.clone()
+        } else {
+            pinned_client(&url).await?
+        };
+
+        let timestamp = get_now().unix_timestamp();
+        let signature = sign(webhook.secret.as_bytes(), timestamp, &body);
+        let response = client
+            .post(url)
+            .header(CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, self.event.as_str())
+            .header(DELIVERY_HEADER, self.snowflake.to_string())
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, signature)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(response.status().as_u16())
+    }
+
+    pub(crate) async fn find_all_due() -> Result<Vec<Self>, ApiError> {
+        find_all(webhook_delivery::Entity::find_all_due(get_now())).await?.into_iter().map(Self::try_from).collect()
+    }
+
+    pub(crate) async fn find_by_id_and_webhook(id: Snowflake, webhook_id: Snowflake) -> Result<Self, ApiError> {
+        Self::try_from(find_one_or_error(webhook_delivery::Entity::find_by_id_and_webhook_id(id, webhook_id)).await?)
+    }
+
+    pub(crate) async fn find_all_by_webhook_paginated(
+        webhook_id: Snowflake,
+        page_size: &PageSizeParam,
+    ) -> Result<Vec<Self>, ApiError> {
+        find_all_paginated(webhook_delivery::Entity::find_all_by_webhook_id(webhook_id), page_size)
+            .await?
+            .into_iter()
+            .map(Self::try_from)
+            .collect()
+    }
+
+    pub(crate) async fn count_all_by_webhook(webhook_id: Snowflake) -> Result<u64, ApiError> {
+        count(webhook_delivery::Entity::find_all_by_webhook_id(webhook_id)).await
+    }
+}
+
+// redirects are not followed, they could lead to an address the URL itself was not allowed to point to
+fn http_client_builder() -> ClientBuilder {
+    Client::builder()
+        .timeout(Duration::from_secs(Config::get_config().webhook.timeout_seconds))
+        .redirect(Policy::none())
+}
+
+// the client connects to the addresses that were checked instead of resolving the host again, otherwise the DNS
+// record could be changed to a private address in between
+async fn pinned_client(url: &Url) -> Result<Client, String> {
+    let host = url.host_str().ok_or("The URL has no host.")?.to_string();
+    let port = url.port_or_known_default().ok_or("The URL has no port.")?;
+    let addresses = resolve_public_addresses(host.clone(), port).await?;
+
+    http_client_builder().resolve_to_addrs(&host, &addresses).build().map_err(|e| e.to_string())
+}
+
+async fn resolve_public_addresses(host: String, port: u16) -> Result<Vec<SocketAddr>, String> {
+    let addresses = spawn_blocking(move || (host, port).to_socket_addrs().map(Iterator::collect::<Vec<_>>))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("Could not resolve the host: {}", e))?;
+
+    if addresses.is_empty() {
+        return Err("The host did not resolve to any address.".to_string());
+    }
+    if addresses.iter().any(|address| is_private(address.ip())) {
+        return Err("The URL points to a loopback or private network address.".to_string());
+    }
+
+    Ok(addresses)
+}
+
+fn is_private(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            ip.is_loopback() || ip.is_private() || ip.is_link_local() || ip.is_unspecified() || ip.is_broadcast()
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_private(IpAddr::V4(ip)),
+            // unique local (fc00::/7) and link local (fe80::/10) addresses
+            None => {
+                ip.is_loopback()
+                    || ip.is_unspecified()
+                    || (ip.segments()[0] & 0xfe00) == 0xfc00
+                    || (ip.segments()[0] & 0xffc0) == 0xfe80
+            }
+        },
+    }
+}
+
+impl TableName for WebhookDelivery {
+    fn table_name() -> &'static str {
+        webhook_delivery::Entity.table_name()
+    }
+}
+
+impl WrapperEntity for WebhookDelivery {
+    fn get_id(&self) -> Snowflake {
+        self.snowflake
+    }
+}
+
+impl TryFrom<webhook_delivery::Model> for WebhookDelivery {
+    type Error = ApiError;
+
+    fn try_from(model: webhook_delivery::Model) -> Result<Self, Self::Error> {
+        let payload: WebhookPayload = serde_json::from_value(model.payload)?;
+
+        Ok(Self {
+            snowflake: Snowflake::from(model.id),
+            webhook_id: Phantom::from(model.webhook),
+            event: payload.event,
+            payload,
+            attempts: model.attempts,
+            response_status: model.response_status,
+            error: model.error,
+            delivered_at: model.delivered_at,
+            next_attempt_at: model.next_attempt_at,
+            created_at: model.created_at,
+        })
+    }
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::wrapper::entity::webhook::event::WebhookEvent;
+use crate::wrapper::entity::webhook::Webhook;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
+pub(crate) struct WebhookDTO {
+    /// Deliveries are sent to this URL as JSON POST requests.
+    #[validate(url)]
+    pub(crate) url: String,
+    #[validate(length(min = 1))]
+    pub(crate) events: Vec<WebhookEvent>,
+    #[serde(default = "default_active")]
+    pub(crate) active: bool,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+/// The only time the secret is returned, it can not be retrieved again.
+/// Every delivery carries `X-Financrr-Signature: sha256=<hex>`, the HMAC-SHA256 of
+/// `{X-Financrr-Timestamp}.{body}` keyed with this secret.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct CreatedWebhook {
+    pub(crate) secret: String,
+    pub(crate) webhook: Webhook,
+}
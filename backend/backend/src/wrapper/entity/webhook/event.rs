@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// The lifecycle events a webhook can subscribe to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) enum WebhookEvent {
+    #[serde(rename = "transaction.created")]
+    TransactionCreated,
+    #[serde(rename = "transaction.updated")]
+    TransactionUpdated,
+    #[serde(rename = "transaction.deleted")]
+    TransactionDeleted,
+    #[serde(rename = "account.created")]
+    AccountCreated,
+    #[serde(rename = "account.updated")]
+    AccountUpdated,
+    #[serde(rename = "account.deleted")]
+    AccountDeleted,
+    #[serde(rename = "budget.created")]
+    BudgetCreated,
+    #[serde(rename = "budget.updated")]
+    BudgetUpdated,
+    #[serde(rename = "budget.deleted")]
+    BudgetDeleted,
+    #[serde(rename = "recurring_transaction.executed")]
+    RecurringTransactionExecuted,
+}
+
+impl WebhookEvent {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::TransactionCreated => "transaction.created",
+            Self::TransactionUpdated => "transaction.updated",
+            Self::TransactionDeleted => "transaction.deleted",
+            Self::AccountCreated => "account.created",
+            Self::AccountUpdated => "account.updated",
+            Self::AccountDeleted => "account.deleted",
+            Self::BudgetCreated => "budget.created",
+            Self::BudgetUpdated => "budget.updated",
+            Self::BudgetDeleted => "budget.deleted",
+            Self::RecurringTransactionExecuted => "recurring_transaction.executed",
+        }
+    }
+}
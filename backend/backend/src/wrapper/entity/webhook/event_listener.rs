@@ -0,0 +1,62 @@
+use crate::api::error::api::ApiError;
+use crate::event::lifecycle::account::{AccountCreation, AccountDeletion, AccountUpdate};
+use crate::event::lifecycle::budget::{BudgetCreation, BudgetDeletion, BudgetUpdate};
+use crate::event::lifecycle::transaction::{
+    RecurringTransactionExecuted, TransactionCreation, TransactionDeletion, TransactionUpdate,
+};
+use crate::event::GenericEvent;
+use crate::wrapper::entity::webhook::event::WebhookEvent;
+use crate::wrapper::entity::webhook::Webhook;
+
+pub(crate) fn webhook_listener() {
+    TransactionCreation::subscribe("webhook.transaction_created", transaction_created);
+    TransactionUpdate::subscribe("webhook.transaction_updated", transaction_updated);
+    TransactionDeletion::subscribe("webhook.transaction_deleted", transaction_deleted);
+    AccountCreation::subscribe("webhook.account_created", account_created);
+    AccountUpdate::subscribe("webhook.account_updated", account_updated);
+    AccountDeletion::subscribe("webhook.account_deleted", account_deleted);
+    BudgetCreation::subscribe("webhook.budget_created", budget_created);
+    BudgetUpdate::subscribe("webhook.budget_updated", budget_updated);
+    BudgetDeletion::subscribe("webhook.budget_deleted", budget_deleted);
+    RecurringTransactionExecuted::subscribe("webhook.recurring_transaction_executed", recurring_transaction_executed);
+}
+
+async fn transaction_created(event: TransactionCreation) -> Result<(), ApiError> {
+    Webhook::dispatch(WebhookEvent::TransactionCreated, &event.transaction, None).await
+}
+
+async fn transaction_updated(event: TransactionUpdate) -> Result<(), ApiError> {
+    Webhook::dispatch(WebhookEvent::TransactionUpdated, &event.new_transaction, Some(&event.old_transaction)).await
+}
+
+async fn transaction_deleted(event: TransactionDeletion) -> Result<(), ApiError> {
+    Webhook::dispatch(WebhookEvent::TransactionDeleted, &event.transaction, None).await
+}
+
+async fn account_created(event: AccountCreation) -> Result<(), ApiError> {
+    Webhook::dispatch(WebhookEvent::AccountCreated, &event.account, None).await
+}
+
+async fn account_updated(event: AccountUpdate) -> Result<(), ApiError> {
+    Webhook::dispatch(WebhookEvent::AccountUpdated, &event.new_account, Some(&event.old_account)).await
+}
+
+async fn account_deleted(event: AccountDeletion) -> Result<(), ApiError> {
+    Webhook::dispatch(WebhookEvent::AccountDeleted, &event.account, None).await
+}
+
+async fn budget_created(event: BudgetCreation) -> Result<(), ApiError> {
+    Webhook::dispatch(WebhookEvent::BudgetCreated, &event.budget, None).await
+}
+
+async fn budget_updated(event: BudgetUpdate) -> Result<(), ApiError> {
+    Webhook::dispatch(WebhookEvent::BudgetUpdated, &event.new_budget, Some(&event.old_budget)).await
+}
+
+async fn budget_deleted(event: BudgetDeletion) -> Result<(), ApiError> {
+    Webhook::dispatch(WebhookEvent::BudgetDeleted, &event.budget, None).await
+}
+
+async fn recurring_transaction_executed(event: RecurringTransactionExecuted) -> Result<(), ApiError> {
+    Webhook::dispatch(WebhookEvent::RecurringTransactionExecuted, &event.transaction, None).await
+}
@@ -0,0 +1,172 @@
+use sea_orm::{EntityName, EntityTrait, Set};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use tracing::error;
+use utoipa::ToSchema;
+
+use entity::utility::hashing::generate_salt;
+use entity::utility::time::get_now;
+use entity::webhook;
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::error::api::ApiError;
+use crate::api::pagination::PageSizeParam;
+use crate::database::entity::{count, delete, find_all, find_all_paginated, find_one_or_error, insert, update};
+use crate::database::transaction::after_commit;
+use crate::wrapper::entity::user::User;
+use crate::wrapper::entity::webhook::delivery::WebhookDelivery;
+use crate::wrapper::entity::webhook::dto::{CreatedWebhook, WebhookDTO};
+use crate::wrapper::entity::webhook::event::WebhookEvent;
+use crate::wrapper::entity::{TableName, WrapperEntity};
+use crate::wrapper::types::phantom::{Identifiable, Phantom};
+use crate::SNOWFLAKE_GENERATOR;
+
+pub(crate) mod delivery;
+pub(crate) mod dto;
+pub(crate) mod event;
+pub(crate) mod event_listener;
+pub(crate) mod retry;
+
+const SECRET_PREFIX: &str = "whsec_";
+const SECRET_LENGTH: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct Webhook {
+    #[serde(rename = "id")]
+    pub(crate) snowflake: Snowflake,
+    pub(crate) user_id: Phantom<User>,
+    pub(crate) url: String,
+    pub(crate) events: Vec<WebhookEvent>,
+    pub(crate) active: bool,
+    #[serde(with = "time::serde::rfc3339")]
+    pub(crate) created_at: OffsetDateTime,
+    #[serde(skip)]
+    pub(crate) secret: String,
+}
+
+impl Webhook {
+    pub(crate) async fn create(user_id: Snowflake, dto: WebhookDTO) -> Result<CreatedWebhook, ApiError> {
+        let secret = format!("{}{}", SECRET_PREFIX, generate_salt(SECRET_LENGTH));
+        let active_model = webhook::ActiveModel {
+            id: Set(SNOWFLAKE_GENERATOR.next_id()?),
+            user: Set(user_id.id),
+            url: Set(dto.url),
+            secret: Set(secret.clone()),
+            events: Set(serde_json::to_value(&dto.events)?),
+            active: Set(dto.active),
+            created_at: Set(get_now()),
+        };
+        let webhook = Self::try_from(insert(active_model).await?)?;
+
+        Ok(CreatedWebhook {
+            secret,
+            webhook,
+        })
+    }
+
+    pub(crate) async fn update(self, dto: WebhookDTO) -> Result<Self, ApiError> {
+        let active_model = webhook::ActiveModel {
+            id: Set(self.snowflake.id),
+            url: Set(dto.url),
+            events: Set(serde_json::to_value(&dto.events)?),
+            active: Set(dto.active),
+            ..Default::default()
+        };
+
+        Self::try_from(update(active_model).await?)
+    }
+
+    pub(crate) async fn delete(self) -> Result<(), ApiError> {
+        delete(webhook::Entity::delete_by_id(self.snowflake.id)).await
+    }
+
+    /// Creates a delivery for every active webhook subscribed to the event whose owner can read the entity and sends
+    /// them in the background once they are committed. Deleted entities are still in the trash, so their grants are
+    /// not filtered by it.
+    pub(crate) async fn dispatch<T: WrapperEntity + Serialize>(
+        event: WebhookEvent,
+        entity: &T,
+        previous: Option<&T>,
+    ) -> Result<(), ApiError> {
+        let webhooks = find_all(webhook::Entity::find_all_active_by_event_and_reader(
+            event.as_str(),
+            T::table_name(),
+            entity.get_id(),
+        ))
+        .await?;
+        if webhooks.is_empty() {
+            return Ok(());
+        }
+
+        let data = serde_json::to_value(entity)?;
+        let previous = previous.map(serde_json::to_value).transpose()?;
+        for model in webhooks {
+            let webhook = Self::try_from(model)?;
+            let delivery = WebhookDelivery::create(&webhook, event, data.clone(), previous.clone()).await?;
+            after_commit(async move {
+                let delivery_id = delivery.snowflake;
+                if let Err(e) = delivery.deliver(&webhook).await {
+                    error!("Could not deliver webhook delivery {}: {}", delivery_id, e);
+                }
+            });
+        }
+
+        Ok(())
+    }
+
+    pub(crate) async fn find_by_id_and_user(id: Snowflake, user_id: Snowflake) -> Result<Self, ApiError> {
+        Self::try_from(find_one_or_error(webhook::Entity::find_by_id_and_user_id(id, user_id)).await?)
+    }
+
+    pub(crate) async fn find_all_by_user_paginated(
+        user_id: Snowflake,
+        page_size: &PageSizeParam,
+    ) -> Result<Vec<Self>, ApiError> {
+        find_all_paginated(webhook::Entity::find_by_user_id(user_id), page_size)
+            .await?
+            .into_iter()
+            .map(Self::try_from)
+            .collect()
+    }
+
+    pub(crate) async fn count_all_by_user(user_id: Snowflake) -> Result<u64, ApiError> {
+        count(webhook::Entity::find_by_user_id(user_id)).await
+    }
+}
+
+impl Identifiable for Webhook {
+    async fn find_by_id(id: Snowflake) -> Result<Self, ApiError>
+    where
+        Self: Sized,
+    {
+        Self::try_from(find_one_or_error(webhook::Entity::find_by_id(id)).await?)
+    }
+}
+
+impl TableName for Webhook {
+    fn table_name() -> &'static str {
+        webhook::Entity.table_name()
+    }
+}
+
+impl WrapperEntity for Webhook {
+    fn get_id(&self) -> Snowflake {
+        self.snowflake
+    }
+}
+
+impl TryFrom<webhook::Model> for Webhook {
+    type Error = ApiError;
+
+    fn try_from(model: webhook::Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            snowflake: Snowflake::from(model.id),
+            user_id: Phantom::from(model.user),
+            url: model.url,
+            events: serde_json::from_value(model.events)?,
+            active: model.active,
+            created_at: model.created_at,
+            secret: model.secret,
+        })
+    }
+}
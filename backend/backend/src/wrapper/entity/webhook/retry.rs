@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+use tokio::time::interval;
+use tracing::error;
+
+use crate::scheduling::schedule_task_with_interval;
+use crate::wrapper::entity::webhook::delivery::WebhookDelivery;
+
+pub(crate) const RETRY_INTERVAL_SECONDS: u64 = 15;
+
+pub(crate) fn schedule_retry_task() {
+    let interval = interval(Duration::from_secs(RETRY_INTERVAL_SECONDS));
+    schedule_task_with_interval(interval, retry);
+}
+
+async fn retry() {
+    let deliveries = match WebhookDelivery::find_all_due().await {
+        Ok(deliveries) => deliveries,
+        Err(e) => {
+            error!("Could not load due webhook deliveries: {}", e);
+            return;
+        }
+    };
+
+    for delivery in deliveries {
+        let delivery_id = delivery.snowflake;
+        if let Err(e) = delivery.attempt().await {
+            error!("Could not retry webhook delivery {}: {}", delivery_id, e);
+        }
+    }
+}
@@ -1,5 +1,8 @@
+pub(crate) mod batch;
 pub(crate) mod entity;
 pub(crate) mod permission;
 pub(crate) mod processor;
+pub(crate) mod realtime;
+pub(crate) mod sync;
 pub(crate) mod types;
 pub(crate) mod util;
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::wrapper::permission::collaborator::validation::validate_permissions_contain_read;
+use crate::wrapper::permission::Permissions;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
+pub(crate) struct CollaboratorDTO {
+    pub(crate) username: String,
+    #[validate(custom(function = "validate_permissions_contain_read"))]
+    pub(crate) permissions: Permissions,
+}
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+use std::future::Future;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::error::api::ApiError;
+use crate::wrapper::entity::group::dto::GroupGrantDTO;
+use crate::wrapper::entity::group::member::GroupRole;
+use crate::wrapper::entity::group::Group;
+use crate::wrapper::entity::user::User;
+use crate::wrapper::permission::collaborator::dto::CollaboratorDTO;
+use crate::wrapper::permission::{Permission, Permissions, PermissionsEntity, Principal};
+
+pub(crate) mod dto;
+pub(crate) mod validation;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct Collaborator {
+    pub(crate) user_id: Snowflake,
+    pub(crate) username: String,
+    pub(crate) display_name: Option<String>,
+    pub(crate) permissions: Permissions,
+}
+
+impl Collaborator {
+    pub(crate) fn new(user: User, permissions: Permissions) -> Self {
+        Self {
+            user_id: user.snowflake,
+            username: user.username,
+            display_name: user.display_name,
+            permissions,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct GroupGrant {
+    pub(crate) group_id: Snowflake,
+    pub(crate) name: String,
+    pub(crate) permissions: Permissions,
+}
+
+impl GroupGrant {
+    pub(crate) fn new(group: Group, permissions: Permissions) -> Self {
+        Self {
+            group_id: group.snowflake,
+            name: group.name,
+            permissions,
+        }
+    }
+}
+
+pub(crate) trait Shareable: Permission {
+    fn find_collaborators(&self) -> impl Future<Output = Result<Vec<Collaborator>, ApiError>> {
+        async move {
+            let permissions = PermissionsEntity::find_all_by_type_and_id(Self::table_name(), self.get_id()).await?;
+            let user_ids = permissions.iter().filter_map(|permission| permission.user_id).collect();
+            let mut users: HashMap<Snowflake, User> =
+                User::find_all_by_ids(user_ids).await?.into_iter().map(|user| (user.snowflake, user)).collect();
+
+            Ok(permissions
+                .into_iter()
+                .filter_map(|permission| {
+                    let user = users.remove(&permission.user_id?)?;
+                    Some(Collaborator::new(user, permission.permissions))
+                })
+                .collect())
+        }
+    }
+
+    fn find_group_grants(&self) -> impl Future<Output = Result<Vec<GroupGrant>, ApiError>> {
+        async move {
+            let permissions = PermissionsEntity::find_all_by_type_and_id(Self::table_name(), self.get_id()).await?;
+            let group_ids = permissions.iter().filter_map(|permission| permission.group_id).collect();
+            let mut groups: HashMap<Snowflake, Group> =
+                Group::find_all_by_ids(group_ids).await?.into_iter().map(|group| (group.snowflake, group)).collect();
+
+            Ok(permissions
+                .into_iter()
+                .filter_map(|permission| {
+                    let group = groups.remove(&permission.group_id?)?;
+                    Some(GroupGrant::new(group, permission.permissions))
+                })
+                .collect())
+        }
+    }
+
+    fn share(&self, dto: CollaboratorDTO) -> impl Future<Output = Result<Collaborator, ApiError>> {
+        async move {
+            let user = User::find_by_username(&dto.username).await?;
+            self.assert_owner_remains(user.snowflake, &dto.permissions).await?;
+            self.set_principal_permissions(Principal::User(user.snowflake), dto.permissions.clone()).await?;
+
+            Ok(Collaborator::new(user, dto.permissions))
+        }
+    }
+
+    // only members that are allowed to act on behalf of the group can share with it
+    fn share_with_group(
+        &self,
+        user_id: Snowflake,
+        dto: GroupGrantDTO,
+    ) -> impl Future<Output = Result<GroupGrant, ApiError>> {
+        async move {
+            let group = dto.group_id.fetch_inner().await?;
+            group.has_role_or_error(user_id, &[GroupRole::Owner, GroupRole::Member]).await?;
+            self.set_principal_permissions(Principal::Group(group.snowflake), dto.permissions.clone()).await?;
+
+            Ok(GroupGrant::new(group, dto.permissions))
+        }
+    }
+
+    fn revoke(&self, user_id: Snowflake) -> impl Future<Output = Result<(), ApiError>> {
+        async move {
+            if self.get_principal_permissions(user_id).await?.is_empty() {
+                return Err(ApiError::ResourceNotFound("collaborator"));
+            }
+            self.assert_owner_remains(user_id, &Permissions::empty()).await?;
+
+            self.set_principal_permissions(Principal::User(user_id), Permissions::empty()).await
+        }
+    }
+
+    fn revoke_group(&self, group_id: Snowflake) -> impl Future<Output = Result<(), ApiError>> {
+        async move {
+            let principal = Principal::Group(group_id);
+            if self.get_principal_permissions(principal).await?.is_empty() {
+                return Err(ApiError::ResourceNotFound("group"));
+            }
+
+            self.set_principal_permissions(principal, Permissions::empty()).await
+        }
+    }
+
+    fn set_principal_permissions(
+        &self,
+        principal: Principal,
+        permissions: Permissions,
+    ) -> impl Future<Output = Result<(), ApiError>> {
+        async move {
+            self.set_permission(principal, permissions).await?;
+
+            self.propagate_permissions(principal).await
+        }
+    }
+
+    // there always has to be a user left who is able to manage and delete the entity
+    fn assert_owner_remains(
+        &self,
+        user_id: Snowflake,
+        permissions: &Permissions,
+    ) -> impl Future<Output = Result<(), ApiError>> {
+        async move {
+            if permissions.is_all() {
+                return Ok(());
+            }
+
+            let other_owners = PermissionsEntity::find_all_by_type_and_id(Self::table_name(), self.get_id())
+                .await?
+                .into_iter()
+                .filter(|permission| {
+                    permission.user_id.is_some_and(|id| id != user_id) && permission.permissions.is_all()
+                })
+                .count();
+            if other_owners == 0 {
+                return Err(ApiError::LastOwner());
+            }
+
+            Ok(())
+        }
+    }
+
+    fn propagate_permissions(&self, _principal: Principal) -> impl Future<Output = Result<(), ApiError>> {
+        async move { Ok(()) }
+    }
+}
@@ -0,0 +1,12 @@
+use validator::ValidationError;
+
+use crate::api::error::validation::ValidationCode;
+use crate::wrapper::permission::Permissions;
+
+pub(crate) fn validate_permissions_contain_read(permissions: &Permissions) -> Result<(), ValidationError> {
+    if !permissions.contains(Permissions::READ) {
+        return ValidationCode::MISSING_READ_PERMISSION.into();
+    }
+
+    Ok(())
+}
@@ -1,26 +1,30 @@
 use std::future::Future;
 
 use bitflags::bitflags;
-use sea_orm::{EntityTrait, Set};
-use serde::Serialize;
+use sea_orm::{EntityName, EntityTrait, Select, Set};
+use serde::{Deserialize, Serialize};
 use utoipa::openapi::{KnownFormat, ObjectBuilder, RefOr, Schema, SchemaFormat, SchemaType};
 use utoipa::ToSchema;
 
 use entity::permissions;
-use entity::permissions::Model;
+use entity::permissions::{Model, UserGrant};
 use entity::utility::table::{does_entity_exist, does_table_exists};
 use utility::snowflake::entity::Snowflake;
 
 use crate::api::error::api::ApiError;
 use crate::api::pagination::PageSizeParam;
 use crate::database::connection::get_database_connection;
-use crate::database::entity::{count, delete, find_all, find_all_paginated, find_one, insert, update};
+use crate::database::entity::{count, delete, find_all, find_all_as, find_all_paginated, find_one, insert, update};
+use crate::wrapper::entity::audit::AuditLog;
+use crate::wrapper::entity::group::member::{GroupMember, GroupRole};
 use crate::wrapper::entity::{TableName, WrapperEntity};
+use crate::SNOWFLAKE_GENERATOR;
 
 pub(crate) mod cleanup;
+pub(crate) mod collaborator;
 
 bitflags! {
-    #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
     pub(crate) struct Permissions: u32 {
         const READ = 0b001;
         const WRITE = 0b010;
@@ -44,9 +48,33 @@ impl ToSchema<'static> for Permissions {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Principal {
+    User(Snowflake),
+    Group(Snowflake),
+}
+
+impl Principal {
+    fn find_permission(&self, entity_type: &str, entity_id: Snowflake) -> Select<permissions::Entity> {
+        match self {
+            Self::User(user_id) => permissions::Entity::find_permission(*user_id, entity_type, entity_id),
+            Self::Group(group_id) => permissions::Entity::find_group_permission(*group_id, entity_type, entity_id),
+        }
+    }
+}
+
+impl From<Snowflake> for Principal {
+    fn from(value: Snowflake) -> Self {
+        Self::User(value)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
 pub(crate) struct PermissionsEntity {
-    pub(crate) user_id: Snowflake,
+    #[serde(rename = "id")]
+    pub(crate) snowflake: Snowflake,
+    pub(crate) user_id: Option<Snowflake>,
+    pub(crate) group_id: Option<Snowflake>,
     pub(crate) entity_type: String,
     pub(crate) entity_id: Snowflake,
     pub(crate) permissions: Permissions,
@@ -62,7 +90,17 @@ impl PermissionsEntity {
     }
 
     pub(crate) async fn delete(self) -> Result<(), ApiError> {
-        delete(permissions::Entity::delete_by_id((self.user_id.id, self.entity_type, self.entity_id.id))).await
+        delete(permissions::Entity::delete_by_id(self.snowflake.id)).await?;
+
+        AuditLog::record_deletion(&self).await
+    }
+
+    pub(crate) fn principal(&self) -> Option<Principal> {
+        match (self.user_id, self.group_id) {
+            (Some(user_id), _) => Some(Principal::User(user_id)),
+            (None, Some(group_id)) => Some(Principal::Group(group_id)),
+            (None, None) => None,
+        }
     }
 
     pub(crate) async fn should_be_cleaned_up(&self) -> Result<bool, ApiError> {
@@ -92,10 +130,24 @@ impl PermissionsEntity {
     }
 }
 
+impl TableName for PermissionsEntity {
+    fn table_name() -> &'static str {
+        permissions::Entity.table_name()
+    }
+}
+
+impl WrapperEntity for PermissionsEntity {
+    fn get_id(&self) -> Snowflake {
+        self.snowflake
+    }
+}
+
 impl From<Model> for PermissionsEntity {
     fn from(value: Model) -> Self {
         Self {
-            user_id: Snowflake::from(value.user_id),
+            snowflake: Snowflake::from(value.id),
+            user_id: value.user_id.map(Snowflake::from),
+            group_id: value.group_id.map(Snowflake::from),
             entity_type: value.entity_type,
             entity_id: Snowflake::from(value.entity_id),
             permissions: Permissions::from_bits(value.permissions as u32).unwrap_or(Permissions::empty()),
@@ -128,12 +180,20 @@ macro_rules! permission_impl {
     };
 }
 
-#[allow(unused)] //TODO remove once multiple users per account has been implemented
+#[allow(unused)]
 pub(crate) trait Permission: PermissionByIds + WrapperEntity {
     fn get_permissions(&self, user_id: Snowflake) -> impl Future<Output = Result<Permissions, ApiError>> {
         async move { Self::get_permissions_by_id(self.get_id(), user_id).await }
     }
 
+    fn get_principal_permissions(
+        &self,
+        principal: impl Into<Principal>,
+    ) -> impl Future<Output = Result<Permissions, ApiError>> {
+        let principal = principal.into();
+        async move { Self::get_principal_permissions_by_id(self.get_id(), principal).await }
+    }
+
     fn has_permission(
         &self,
         user_id: Snowflake,
@@ -144,18 +204,29 @@ pub(crate) trait Permission: PermissionByIds + WrapperEntity {
 
     fn add_permission(
         &self,
-        user_id: Snowflake,
+        principal: impl Into<Principal>,
         permissions: Permissions,
     ) -> impl Future<Output = Result<(), ApiError>> {
-        async move { Self::add_permission_by_id(self.get_id(), user_id, permissions).await }
+        let principal = principal.into();
+        async move { Self::add_permission_by_id(self.get_id(), principal, permissions).await }
     }
 
     fn remove_permission(
         &self,
-        user_id: Snowflake,
+        principal: impl Into<Principal>,
         permissions: Permissions,
     ) -> impl Future<Output = Result<(), ApiError>> {
-        async move { Self::remove_permission_by_id(self.get_id(), user_id, permissions).await }
+        let principal = principal.into();
+        async move { Self::remove_permission_by_id(self.get_id(), principal, permissions).await }
+    }
+
+    fn set_permission(
+        &self,
+        principal: impl Into<Principal>,
+        permissions: Permissions,
+    ) -> impl Future<Output = Result<(), ApiError>> {
+        let principal = principal.into();
+        async move { Self::set_permission_by_id(self.get_id(), principal, permissions).await }
     }
 }
 
@@ -175,55 +246,73 @@ pub(crate) trait HasPermissionOrError: Permission {
 
 #[allow(unused)]
 pub(crate) trait PermissionByIds: TableName {
+    // trashed entities are inaccessible until they are restored
     fn get_permissions_by_id(
         entity_id: Snowflake,
         user_id: Snowflake,
     ) -> impl Future<Output = Result<Permissions, ApiError>> {
         async move {
-            Ok(Permissions::from(
-                find_one(permissions::Entity::find_permission(user_id, Self::table_name(), entity_id)).await?,
+            combine_grants(permissions::Entity::find_grants_by_user_id_not_trashed(
+                user_id,
+                Self::table_name(),
+                entity_id,
             ))
+            .await
         }
     }
 
+    fn get_principal_permissions_by_id(
+        entity_id: Snowflake,
+        principal: impl Into<Principal>,
+    ) -> impl Future<Output = Result<Permissions, ApiError>> {
+        let principal = principal.into();
+        async move { Ok(Permissions::from(find_one(principal.find_permission(Self::table_name(), entity_id)).await?)) }
+    }
+
     fn has_permission_by_id(
         entity_id: Snowflake,
         user_id: Snowflake,
         permissions: Permissions,
     ) -> impl Future<Output = Result<bool, ApiError>> {
-        async move {
-            Ok(Permissions::from(
-                find_one(permissions::Entity::find_permission(user_id, Self::table_name(), entity_id)).await?,
-            )
-            .contains(permissions))
-        }
+        async move { Ok(Self::get_permissions_by_id(entity_id, user_id).await?.contains(permissions)) }
     }
 
     fn add_permission_by_id(
         entity_id: Snowflake,
-        user_id: Snowflake,
+        principal: impl Into<Principal>,
         permissions: Permissions,
     ) -> impl Future<Output = Result<(), ApiError>> {
+        let principal = principal.into();
         async move {
-            let current_permissions = Self::get_permissions_by_id(entity_id, user_id).await?;
+            let current_permissions = Self::get_principal_permissions_by_id(entity_id, principal).await?;
             let permissions = current_permissions | permissions;
 
-            save_permission_active_model(entity_id, Self::table_name(), user_id, permissions).await
+            save_permission_active_model(entity_id, Self::table_name(), principal, permissions).await
         }
     }
 
     fn remove_permission_by_id(
         entity_id: Snowflake,
-        user_id: Snowflake,
+        principal: impl Into<Principal>,
         permissions: Permissions,
     ) -> impl Future<Output = Result<(), ApiError>> {
+        let principal = principal.into();
         async move {
-            let mut current_permissions = Self::get_permissions_by_id(entity_id, user_id).await?;
+            let mut current_permissions = Self::get_principal_permissions_by_id(entity_id, principal).await?;
             current_permissions.remove(permissions);
 
-            save_permission_active_model(entity_id, Self::table_name(), user_id, current_permissions).await
+            save_permission_active_model(entity_id, Self::table_name(), principal, current_permissions).await
         }
     }
+
+    fn set_permission_by_id(
+        entity_id: Snowflake,
+        principal: impl Into<Principal>,
+        permissions: Permissions,
+    ) -> impl Future<Output = Result<(), ApiError>> {
+        let principal = principal.into();
+        async move { save_permission_active_model(entity_id, Self::table_name(), principal, permissions).await }
+    }
 }
 
 pub(crate) trait HasPermissionByIdOrError: PermissionByIds {
@@ -240,7 +329,51 @@ pub(crate) trait HasPermissionByIdOrError: PermissionByIds {
     }
 }
 
-fn has_permission_or_error_raw(
+// the union of the direct grant and the grants of all groups the user is a member of
+pub(crate) async fn get_user_permissions(
+    entity_type: &str,
+    entity_id: Snowflake,
+    user_id: Snowflake,
+) -> Result<Permissions, ApiError> {
+    combine_grants(permissions::Entity::find_grants_by_user_id(user_id, entity_type, entity_id)).await
+}
+
+async fn combine_grants(grants: Select<permissions::Entity>) -> Result<Permissions, ApiError> {
+    let mut permissions = Permissions::empty();
+    for grant in find_all_as(grants.into_model::<UserGrant>()).await? {
+        let granted = Permissions::from_bits(grant.permissions as u32).unwrap_or(Permissions::empty());
+        permissions |= match grant.role {
+            Some(role) => GroupRole::try_from(role.as_str())?.restrict(granted),
+            None => granted,
+        };
+    }
+
+    Ok(permissions)
+}
+
+/// Records that the principal can no longer read the entity through this grant, a group's revocation is recorded
+/// for each of its members. Synced clients drop the entity unless it is still readable through another grant.
+pub(crate) async fn record_revocation(
+    principal: Principal,
+    entity_type: &str,
+    entity_id: Snowflake,
+) -> Result<(), ApiError> {
+    let user_ids = match principal {
+        Principal::User(user_id) => vec![user_id],
+        Principal::Group(group_id) => GroupMember::find_all_by_group_id(group_id)
+            .await?
+            .into_iter()
+            .map(|member| member.user_id.get_id())
+            .collect(),
+    };
+    for user_id in user_ids {
+        AuditLog::record_revocation(entity_type, entity_id, user_id).await?;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn has_permission_or_error_raw(
     user_permissions: Permissions,
     permissions: Permissions,
     table_name: &str,
@@ -258,19 +391,42 @@ fn has_permission_or_error_raw(
 async fn save_permission_active_model(
     entity_id: Snowflake,
     table_name: &str,
-    user_id: Snowflake,
+    principal: Principal,
     permissions: Permissions,
 ) -> Result<(), ApiError> {
-    let active_model = permissions::ActiveModel {
-        user_id: Set(user_id.id),
-        entity_type: Set(table_name.to_string()),
-        entity_id: Set(entity_id.id),
-        permissions: Set(permissions.bits() as i32),
-    };
-    if count(permissions::Entity::find_permission(user_id, table_name, entity_id)).await? > 0 {
-        update(active_model).await?;
-    } else {
-        insert(active_model).await?;
+    let existing = find_one(principal.find_permission(table_name, entity_id)).await?;
+    if existing.as_ref().is_some_and(|model| Permissions::from(model.clone()).contains(Permissions::READ))
+        && !permissions.contains(Permissions::READ)
+    {
+        record_revocation(principal, table_name, entity_id).await?;
+    }
+
+    match existing {
+        Some(model) if permissions.is_empty() => PermissionsEntity::from(model).delete().await?,
+        Some(model) => {
+            let old = PermissionsEntity::from(model.clone());
+            let mut active_model: permissions::ActiveModel = model.into();
+            active_model.permissions = Set(permissions.bits() as i32);
+            let new = PermissionsEntity::from(update(active_model).await?);
+            AuditLog::record_update(&old, &new).await?;
+        }
+        None if permissions.is_empty() => {}
+        None => {
+            let (user_id, group_id) = match principal {
+                Principal::User(user_id) => (Some(user_id.id), None),
+                Principal::Group(group_id) => (None, Some(group_id.id)),
+            };
+            let active_model = permissions::ActiveModel {
+                id: Set(SNOWFLAKE_GENERATOR.next_id()?),
+                user_id: Set(user_id),
+                group_id: Set(group_id),
+                entity_type: Set(table_name.to_string()),
+                entity_id: Set(entity_id.id),
+                permissions: Set(permissions.bits() as i32),
+            };
+            let permission = PermissionsEntity::from(insert(active_model).await?);
+            AuditLog::record_creation(&permission).await?;
+        }
     }
 
     Ok(())
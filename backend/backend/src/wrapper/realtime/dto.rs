@@ -0,0 +1,27 @@
+use actix_web::http::header::HeaderMap;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use utility::snowflake::entity::Snowflake;
+
+const LAST_EVENT_ID_HEADER: &str = "Last-Event-ID";
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct RealtimeQuery {
+    /// The id of the last event the client received, the events after it are sent first.
+    /// Server-sent event clients may use the `Last-Event-ID` header instead.
+    pub(crate) last_event_id: Option<Snowflake>,
+}
+
+impl RealtimeQuery {
+    /// The header browsers send when they reconnect an `EventSource` takes precedence over the query.
+    pub(crate) fn last_event_id(&self, headers: &HeaderMap) -> Option<Snowflake> {
+        headers
+            .get(LAST_EVENT_ID_HEADER)
+            .and_then(|header| header.to_str().ok())
+            .and_then(|header| header.trim().parse::<i64>().ok())
+            .map(Snowflake::from)
+            .or(self.last_event_id)
+    }
+}
@@ -0,0 +1,55 @@
+use crate::api::error::api::ApiError;
+use crate::event::lifecycle::account::{AccountCreation, AccountDeletion, AccountUpdate};
+use crate::event::lifecycle::budget::{BudgetCreation, BudgetDeletion, BudgetUpdate};
+use crate::event::lifecycle::transaction::{TransactionCreation, TransactionDeletion, TransactionUpdate};
+use crate::event::GenericEvent;
+use crate::wrapper::entity::webhook::event::WebhookEvent;
+use crate::wrapper::realtime::RealtimeHub;
+
+pub(crate) fn realtime_listener() {
+    TransactionCreation::subscribe("realtime.transaction_created", transaction_created);
+    TransactionUpdate::subscribe("realtime.transaction_updated", transaction_updated);
+    TransactionDeletion::subscribe("realtime.transaction_deleted", transaction_deleted);
+    AccountCreation::subscribe("realtime.account_created", account_created);
+    AccountUpdate::subscribe("realtime.account_updated", account_updated);
+    AccountDeletion::subscribe("realtime.account_deleted", account_deleted);
+    BudgetCreation::subscribe("realtime.budget_created", budget_created);
+    BudgetUpdate::subscribe("realtime.budget_updated", budget_updated);
+    BudgetDeletion::subscribe("realtime.budget_deleted", budget_deleted);
+}
+
+async fn transaction_created(event: TransactionCreation) -> Result<(), ApiError> {
+    RealtimeHub::publish(WebhookEvent::TransactionCreated, &event.transaction, None).await
+}
+
+async fn transaction_updated(event: TransactionUpdate) -> Result<(), ApiError> {
+    RealtimeHub::publish(WebhookEvent::TransactionUpdated, &event.new_transaction, Some(&event.old_transaction)).await
+}
+
+async fn transaction_deleted(event: TransactionDeletion) -> Result<(), ApiError> {
+    RealtimeHub::publish(WebhookEvent::TransactionDeleted, &event.transaction, None).await
+}
+
+async fn account_created(event: AccountCreation) -> Result<(), ApiError> {
+    RealtimeHub::publish(WebhookEvent::AccountCreated, &event.account, None).await
+}
+
+async fn account_updated(event: AccountUpdate) -> Result<(), ApiError> {
+    RealtimeHub::publish(WebhookEvent::AccountUpdated, &event.new_account, Some(&event.old_account)).await
+}
+
+async fn account_deleted(event: AccountDeletion) -> Result<(), ApiError> {
+    RealtimeHub::publish(WebhookEvent::AccountDeleted, &event.account, None).await
+}
+
+async fn budget_created(event: BudgetCreation) -> Result<(), ApiError> {
+    RealtimeHub::publish(WebhookEvent::BudgetCreated, &event.budget, None).await
+}
+
+async fn budget_updated(event: BudgetUpdate) -> Result<(), ApiError> {
+    RealtimeHub::publish(WebhookEvent::BudgetUpdated, &event.new_budget, Some(&event.old_budget)).await
+}
+
+async fn budget_deleted(event: BudgetDeletion) -> Result<(), ApiError> {
+    RealtimeHub::publish(WebhookEvent::BudgetDeleted, &event.budget, None).await
+}
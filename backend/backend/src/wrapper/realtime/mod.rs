@@ -0,0 +1,221 @@
+use std::collections::VecDeque;
+use std::sync::{LazyLock, Mutex};
+
+use futures_util::stream::{self, Stream, StreamExt};
+use serde::Serialize;
+use serde_json::Value;
+use time::OffsetDateTime;
+use tokio::sync::broadcast::{channel, Sender};
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::BroadcastStream;
+use tracing::{error, warn};
+use utoipa::ToSchema;
+
+use entity::utility::time::get_now;
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::error::api::ApiError;
+use crate::config::Config;
+use crate::database::transaction::after_commit;
+use crate::wrapper::entity::webhook::event::WebhookEvent;
+use crate::wrapper::entity::WrapperEntity;
+use crate::wrapper::permission::{get_user_permissions, Permissions};
+use crate::SNOWFLAKE_GENERATOR;
+
+pub(crate) mod dto;
+pub(crate) mod event_listener;
+pub(crate) mod socket;
+
+const CHANNEL_SIZE: usize = 1024;
+
+static HUB: LazyLock<RealtimeHub> = LazyLock::new(RealtimeHub::new);
+
+/// A lifecycle event as it is pushed to connected clients. `previous` is only set for update events.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub(crate) struct RealtimeEvent {
+    /// Increases with every event, clients send the last one they received to resume after reconnecting.
+    pub(crate) id: Snowflake,
+    pub(crate) event: WebhookEvent,
+    #[serde(with = "time::serde::rfc3339")]
+    pub(crate) created_at: OffsetDateTime,
+    #[schema(value_type = Object)]
+    pub(crate) data: Value,
+    #[schema(value_type = Option<Object>)]
+    pub(crate) previous: Option<Value>,
+    #[serde(skip)]
+    pub(crate) entity_type: &'static str,
+    #[serde(skip)]
+    pub(crate) entity_id: Snowflake,
+}
+
+/// What a client receives. `resync` tells it that events were missed, e.g. because the server restarted or the
+/// client was away for too long, and that it has to reload its data.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum RealtimeMessage {
+    Event(RealtimeEvent),
+    Resync,
+}
+
+impl RealtimeMessage {
+    /// The message in the `text/event-stream` format, ids are only sent for events so resync does not move the
+    /// client's last event id.
+    pub(crate) fn to_sse(&self) -> Result<String, ApiError> {
+        Ok(match self {
+            Self::Event(event) => {
+                format!(
+                    "id: {}\nevent: {}\ndata: {}\n\n",
+                    event.id,
+                    event.event.as_str(),
+                    serde_json::to_string(event)?
+                )
+            }
+            Self::Resync => "event: resync\ndata: {}\n\n".to_string(),
+        })
+    }
+}
+
+/// An event whose transaction has not committed yet, it gets its id once it enters the buffer.
+struct PendingEvent {
+    event: WebhookEvent,
+    created_at: OffsetDateTime,
+    data: Value,
+    previous: Option<Value>,
+    entity_type: &'static str,
+    entity_id: Snowflake,
+}
+
+impl PendingEvent {
+    fn into_event(self, id: Snowflake) -> RealtimeEvent {
+        RealtimeEvent {
+            id,
+            event: self.event,
+            created_at: self.created_at,
+            data: self.data,
+            previous: self.previous,
+            entity_type: self.entity_type,
+            entity_id: self.entity_id,
+        }
+    }
+}
+
+struct ReplayBuffer {
+    events: VecDeque<RealtimeEvent>,
+    /// Events with a smaller id are no longer available, either evicted or from before the server started.
+    oldest_available: i64,
+}
+
+/// Fans lifecycle events out to all connected clients and keeps the most recent ones for clients that reconnect.
+/// The buffer only lives in memory, so a restart makes every resuming client resync.
+pub(crate) struct RealtimeHub {
+    sender: Sender<RealtimeEvent>,
+    buffer: Mutex<ReplayBuffer>,
+}
+
+impl RealtimeHub {
+    fn new() -> Self {
+        let (sender, _) = channel(CHANNEL_SIZE);
+        let oldest_available = SNOWFLAKE_GENERATOR.next_id().expect("Could not generate the realtime start id!");
+
+        Self {
+            sender,
+            buffer: Mutex::new(ReplayBuffer {
+                events: VecDeque::new(),
+                oldest_available,
+            }),
+        }
+    }
+
+    pub(crate) async fn publish<T: WrapperEntity + Serialize>(
+        event: WebhookEvent,
+        entity: &T,
+        previous: Option<&T>,
+    ) -> Result<(), ApiError> {
+        let event = PendingEvent {
+            event,
+            created_at: get_now(),
+            data: serde_json::to_value(entity)?,
+            previous: previous.map(serde_json::to_value).transpose()?,
+            entity_type: T::table_name(),
+            entity_id: entity.get_id(),
+        };
+        after_commit(async move { HUB.push(event) });
+
+        Ok(())
+    }
+
+    fn push(&self, pending: PendingEvent) {
+        let buffer_size = Config::get_config().realtime.replay_buffer_size;
+        let mut buffer = self.buffer.lock().expect("Realtime buffer lock is poisoned!");
+        // the id is taken while holding the lock, transactions commit in any order but the buffer is always sorted
+        // by id, so resuming after an id never skips an event
+        let id = match SNOWFLAKE_GENERATOR.next_id() {
+            Ok(id) => Snowflake::from(id),
+            Err(e) => {
+                error!("Could not generate a realtime event id: {}", e);
+                return;
+            }
+        };
+        let event = pending.into_event(id);
+        buffer.events.push_back(event.clone());
+        while buffer.events.len() > buffer_size {
+            if let Some(evicted) = buffer.events.pop_front() {
+                buffer.oldest_available = evicted.id.id + 1;
+            }
+        }
+        // sent while holding the lock so a client subscribing at the same time gets the event exactly once
+        let _ = self.sender.send(event);
+    }
+
+    /// The messages for one client: first the events it missed since `last_event_id`, then the live ones. Only
+    /// events about entities the user can read are included.
+    pub(crate) fn subscribe(
+        user_id: Snowflake,
+        last_event_id: Option<Snowflake>,
+    ) -> impl Stream<Item = RealtimeMessage> + 'static {
+        let (replay, receiver) = {
+            let buffer = HUB.buffer.lock().expect("Realtime buffer lock is poisoned!");
+            let receiver = HUB.sender.subscribe();
+            let replay = match last_event_id {
+                None => vec![],
+                Some(last_event_id) if last_event_id.id + 1 < buffer.oldest_available => {
+                    vec![RealtimeMessage::Resync]
+                }
+                Some(last_event_id) => buffer
+                    .events
+                    .iter()
+                    .filter(|event| event.id.id > last_event_id.id)
+                    .cloned()
+                    .map(RealtimeMessage::Event)
+                    .collect(),
+            };
+
+            (replay, receiver)
+        };
+
+        let live = BroadcastStream::new(receiver).map(move |result| match result {
+            Ok(event) => RealtimeMessage::Event(event),
+            Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                warn!("Realtime client of user {} lagged behind by {} events.", user_id, skipped);
+                RealtimeMessage::Resync
+            }
+        });
+
+        stream::iter(replay).chain(live).filter_map(move |message| is_visible(message, user_id))
+    }
+}
+
+async fn is_visible(message: RealtimeMessage, user_id: Snowflake) -> Option<RealtimeMessage> {
+    let RealtimeMessage::Event(event) = &message else {
+        return Some(message);
+    };
+
+    match get_user_permissions(event.entity_type, event.entity_id, user_id).await {
+        Ok(permissions) if permissions.contains(Permissions::READ) => Some(message),
+        Ok(_) => None,
+        Err(e) => {
+            error!("Could not check realtime event permissions for user {}: {}", user_id, e);
+            None
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use std::pin::pin;
+use std::time::{Duration, Instant};
+
+use actix_ws::{CloseCode, CloseReason, Message, MessageStream, Session};
+use futures_util::{Stream, StreamExt};
+use tokio::select;
+use tokio::time::interval;
+use tracing::error;
+
+use crate::wrapper::realtime::RealtimeMessage;
+
+/// Clients that did not answer this many heartbeats in a row are disconnected.
+const MISSED_HEARTBEATS: u32 = 2;
+
+/// Pushes the messages to the socket as JSON text frames until either side closes the connection. Messages sent
+/// by the client are ignored, apart from pings and closing.
+pub(crate) async fn run_socket(
+    mut session: Session,
+    mut incoming: MessageStream,
+    messages: impl Stream<Item = RealtimeMessage>,
+    heartbeat: Duration,
+) {
+    let mut messages = pin!(messages);
+    let mut heartbeat_interval = interval(heartbeat);
+    let mut last_seen = Instant::now();
+
+    let reason = loop {
+        select! {
+            message = messages.next() => {
+                let Some(message) = message else {
+                    break None;
+                };
+                let text = match serde_json::to_string(&message) {
+                    Ok(text) => text,
+                    Err(e) => {
+                        error!("Could not serialize realtime message: {}", e);
+                        continue;
+                    }
+                };
+                if session.text(text).await.is_err() {
+                    return;
+                }
+            }
+            incoming_message = incoming.next() => {
+                match incoming_message {
+                    Some(Ok(Message::Ping(bytes))) => {
+                        last_seen = Instant::now();
+                        if session.pong(&bytes).await.is_err() {
+                            return;
+                        }
+                    }
+                    Some(Ok(Message::Close(reason))) => break reason,
+                    Some(Ok(_)) => last_seen = Instant::now(),
+                    Some(Err(_)) => {
+                        break Some(CloseReason::from(CloseCode::Protocol));
+                    }
+                    None => return,
+                }
+            }
+            _ = heartbeat_interval.tick() => {
+                if last_seen.elapsed() > heartbeat * MISSED_HEARTBEATS {
+                    break Some(CloseReason::from(CloseCode::Away));
+                }
+                if session.ping(b"").await.is_err() {
+                    return;
+                }
+            }
+        }
+    };
+
+    let _ = session.close(reason).await;
+}
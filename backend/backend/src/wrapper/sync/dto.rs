@@ -0,0 +1,83 @@
+use actix_web::http::header::EntityTag;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use time::OffsetDateTime;
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use utility::snowflake::entity::Snowflake;
+
+use crate::wrapper::batch::dto::{BatchAction, BatchEntity, BatchOperation, BatchResult, MAX_OPERATIONS};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(crate) struct SyncQuery {
+    /// The token of the last sync. Without it only a token is issued, the data is then loaded through the list
+    /// routes.
+    pub(crate) token: Option<String>,
+}
+
+/// A changed entity with its current state, an entity that was deleted or that the user lost access to is sent as a
+/// tombstone without data.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub(crate) struct SyncEntry {
+    pub(crate) entity_type: String,
+    pub(crate) entity_id: Snowflake,
+    pub(crate) deleted: bool,
+    #[schema(value_type = Option<Object>)]
+    pub(crate) data: Option<Value>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub(crate) changed_at: OffsetDateTime,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub(crate) struct SyncPull {
+    /// The token for the next sync.
+    pub(crate) token: String,
+    /// More changes are waiting, sync again right away with the new token.
+    pub(crate) has_more: bool,
+    pub(crate) changes: Vec<SyncEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate, ToSchema)]
+pub(crate) struct SyncPushDTO {
+    #[validate(length(min = 1, max = MAX_OPERATIONS))]
+    pub(crate) changes: Vec<SyncChange>,
+}
+
+/// A change made on the client while it was offline, `data` is the body the single-item route expects.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub(crate) struct SyncChange {
+    pub(crate) action: BatchAction,
+    pub(crate) entity: BatchEntity,
+    /// The entity to update or delete.
+    pub(crate) id: Option<Snowflake>,
+    /// The version of the entity the change is based on, required to update or delete accounts, budgets and
+    /// transactions.
+    pub(crate) version: Option<i32>,
+    #[schema(value_type = Option<Object>)]
+    pub(crate) data: Option<Value>,
+}
+
+impl From<SyncChange> for BatchOperation {
+    fn from(change: SyncChange) -> Self {
+        Self {
+            action: change.action,
+            entity: change.entity,
+            id: change.id,
+            // the version is what the entity's ETag is made of
+            if_match: change.version.map(|version| EntityTag::new_strong(version.to_string()).to_string()),
+            data: change.data,
+        }
+    }
+}
+
+/// The outcome of one change. If the entity was changed on the server in the meantime, `current` contains its
+/// current state so the client can resolve the conflict.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub(crate) struct SyncPushResult {
+    #[serde(flatten)]
+    pub(crate) result: BatchResult,
+    #[schema(value_type = Option<Object>)]
+    pub(crate) current: Option<Value>,
+}
@@ -0,0 +1,195 @@
+use std::collections::{HashMap, HashSet};
+
+use actix_web::http::StatusCode;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, Select};
+use serde::Serialize;
+use serde_json::Value;
+
+use entity::{account, budget, currency, recurring_transaction, transaction, transaction_template};
+use utility::datetime::get_epoch_millis;
+use utility::snowflake::entity::Snowflake;
+
+use crate::api::error::api::ApiError;
+use crate::config::Config;
+use crate::database::entity::find_all;
+use crate::database::transaction::in_transaction;
+use crate::wrapper::batch::dto::{BatchOperation, BatchResult};
+use crate::wrapper::batch::execute_operation;
+use crate::wrapper::entity::account::Account;
+use crate::wrapper::entity::audit::{AuditAction, AuditLog};
+use crate::wrapper::entity::budget::Budget;
+use crate::wrapper::entity::currency::Currency;
+use crate::wrapper::entity::transaction::recurring::RecurringTransaction;
+use crate::wrapper::entity::transaction::template::TransactionTemplate;
+use crate::wrapper::entity::transaction::Transaction;
+use crate::wrapper::entity::{TableName, WrapperEntity};
+use crate::wrapper::sync::dto::{SyncEntry, SyncPull, SyncPushDTO, SyncPushResult};
+use crate::SNOWFLAKE_GENERATOR;
+
+pub(crate) mod dto;
+
+const MILLIS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+fn synced_types() -> Vec<&'static str> {
+    vec![
+        Account::table_name(),
+        Budget::table_name(),
+        Currency::table_name(),
+        Transaction::table_name(),
+        TransactionTemplate::table_name(),
+        RecurringTransaction::table_name(),
+    ]
+}
+
+/// The changes to the entities the user can read since the token, taken from the audit log. Without a token only a
+/// token for the current state is issued.
+pub(crate) async fn pull(user_id: Snowflake, token: Option<&str>) -> Result<SyncPull, ApiError> {
+    let config = &Config::get_config().sync;
+    let now = get_epoch_millis()?;
+    // a change after the horizon may still be committed with a smaller id than one that was already sent
+    let horizon = SNOWFLAKE_GENERATOR.first_id_at(now.saturating_sub(config.lag_seconds * 1000));
+    let Some(token) = token else {
+        return Ok(SyncPull {
+            token: (horizon - 1).to_string(),
+            has_more: false,
+            changes: Vec::new(),
+        });
+    };
+
+    let after = token.parse::<i64>().ok().filter(|after| *after >= 0).ok_or_else(ApiError::InvalidSyncToken)?;
+    // tombstones of older deletions may already be purged along with the trash
+    let retention = Config::get_config().trash.retention_days * MILLIS_PER_DAY;
+    if SNOWFLAKE_GENERATOR.epoch_millis_of(after) < now.saturating_sub(retention) {
+        return Err(ApiError::SyncTokenExpired());
+    }
+
+    let changes =
+        AuditLog::find_changes_by_user(user_id, after.into(), horizon.into(), synced_types(), config.page_size).await?;
+    let has_more = changes.len() as u64 == config.page_size;
+    let next = match changes.last() {
+        Some(last) if has_more => last.snowflake.id,
+        _ => after.max(horizon - 1),
+    };
+
+    Ok(SyncPull {
+        token: next.to_string(),
+        has_more,
+        changes: load_entries(user_id, changes).await?,
+    })
+}
+
+/// Applies the client's changes one by one like a best-effort batch. A change based on an outdated version fails
+/// with `412 Precondition Failed` and is answered with the entity's current state.
+pub(crate) async fn push(user_id: Snowflake, push: SyncPushDTO) -> Result<Vec<SyncPushResult>, ApiError> {
+    let mut results = Vec::with_capacity(push.changes.len());
+    for change in push.changes {
+        let (entity_type, id) = (change.entity.table_name(), change.id);
+        let result = BatchResult::from(in_transaction(execute_operation(user_id, BatchOperation::from(change))).await);
+        let current = match (result.status == StatusCode::PRECONDITION_FAILED.as_u16(), id) {
+            (true, Some(id)) => load_states(user_id, entity_type, vec![id]).await?.remove(&id),
+            _ => None,
+        };
+
+        results.push(SyncPushResult {
+            result,
+            current,
+        });
+    }
+
+    Ok(results)
+}
+
+// only the latest change of an entity matters, the entity is sent with its current state
+async fn load_entries(user_id: Snowflake, changes: Vec<AuditLog>) -> Result<Vec<SyncEntry>, ApiError> {
+    let mut seen = HashSet::new();
+    let mut latest: Vec<AuditLog> = changes
+        .into_iter()
+        .rev()
+        .filter(|change| seen.insert((change.entity_type.clone(), change.entity_id)))
+        .collect();
+    latest.reverse();
+
+    let mut ids: HashMap<&str, Vec<Snowflake>> = HashMap::new();
+    for change in latest.iter().filter(|change| change.action != AuditAction::Delete) {
+        ids.entry(change.entity_type.as_str()).or_default().push(change.entity_id);
+    }
+    let mut states = HashMap::new();
+    for (entity_type, ids) in ids {
+        states.insert(entity_type.to_string(), load_states(user_id, entity_type, ids).await?);
+    }
+
+    Ok(latest
+        .into_iter()
+        .map(|change| {
+            // an entity that can no longer be found was deleted along with another one
+            let data = states.get_mut(&change.entity_type).and_then(|states| states.remove(&change.entity_id));
+            SyncEntry {
+                entity_type: change.entity_type,
+                entity_id: change.entity_id,
+                deleted: data.is_none(),
+                data,
+                changed_at: change.created_at,
+            }
+        })
+        .collect())
+}
+
+/// The current state of the entities the user can read, entities they can not read or that were deleted are left
+/// out.
+async fn load_states(
+    user_id: Snowflake,
+    entity_type: &str,
+    ids: Vec<Snowflake>,
+) -> Result<HashMap<Snowflake, Value>, ApiError> {
+    let ids: Vec<i64> = ids.iter().map(i64::from).collect();
+    match entity_type {
+        entity_type if entity_type == Account::table_name() => {
+            states::<Account, _>(account::Entity::find_all_by_user_id(user_id).filter(account::Column::Id.is_in(ids)))
+                .await
+        }
+        entity_type if entity_type == Budget::table_name() => {
+            states::<Budget, _>(budget::Entity::find_all_by_user_id(user_id).filter(budget::Column::Id.is_in(ids)))
+                .await
+        }
+        entity_type if entity_type == Currency::table_name() => {
+            states::<Currency, _>(
+                currency::Entity::find_all_with_no_user_and_user_id(user_id).filter(currency::Column::Id.is_in(ids)),
+            )
+            .await
+        }
+        entity_type if entity_type == Transaction::table_name() => {
+            states::<Transaction, _>(
+                transaction::Entity::find_all_by_user_id(user_id).filter(transaction::Column::Id.is_in(ids)),
+            )
+            .await
+        }
+        entity_type if entity_type == TransactionTemplate::table_name() => {
+            states::<TransactionTemplate, _>(
+                transaction_template::Entity::find_all_by_user_id(user_id)
+                    .filter(transaction_template::Column::Id.is_in(ids)),
+            )
+            .await
+        }
+        entity_type if entity_type == RecurringTransaction::table_name() => {
+            states::<RecurringTransaction, _>(
+                recurring_transaction::Entity::find_all_by_user_id(user_id)
+                    .filter(recurring_transaction::Column::Id.is_in(ids)),
+            )
+            .await
+        }
+        _ => Ok(HashMap::new()),
+    }
+}
+
+async fn states<W, E>(select: Select<E>) -> Result<HashMap<Snowflake, Value>, ApiError>
+where
+    E: EntityTrait,
+    W: From<E::Model> + WrapperEntity + Serialize,
+{
+    find_all(select)
+        .await?
+        .into_iter()
+        .map(W::from)
+        .map(|entity| Ok((entity.get_id(), serde_json::to_value(&entity)?)))
+        .collect()
+}
@@ -3,14 +3,16 @@
 use sea_orm::entity::prelude::*;
 use sea_orm::sea_query::IntoCondition;
 use sea_orm::ActiveValue::Set;
-use sea_orm::{JoinType, Order, QueryOrder, QuerySelect};
+use sea_orm::{JoinType, Order, QueryOrder, QuerySelect, UpdateMany};
 use serde::{Deserialize, Serialize};
 
 use utility::snowflake::entity::Snowflake;
 
 use crate::permissions;
 use crate::permissions::find_all_by_user_id;
+use crate::trash::soft_delete;
 use crate::utility::time::get_now;
+use crate::utility::version::versioned;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
 #[sea_orm(table_name = "account")]
@@ -27,6 +29,8 @@ pub struct Model {
     pub original_balance: i64,
     pub currency: i64,
     pub created_at: TimeDateTimeWithTimeZone,
+    pub archived_at: Option<TimeDateTimeWithTimeZone>,
+    pub version: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -50,10 +54,20 @@ impl Related<super::currency::Entity> for Entity {
 impl ActiveModelBehavior for ActiveModel {}
 
 find_all_by_user_id!(Entity);
+soft_delete!(Entity);
+versioned!(Entity);
 
 impl Entity {
+    pub fn find_all_active_by_user_id(user_id: Snowflake) -> Select<Self> {
+        Self::find_all_by_user_id(user_id).filter(Column::ArchivedAt.is_null())
+    }
+
+    pub fn find_all_archived_by_user_id(user_id: Snowflake) -> Select<Self> {
+        Self::find_all_by_user_id(user_id).filter(Column::ArchivedAt.is_not_null())
+    }
+
     pub fn find_by_id_and_user_id(id: Snowflake, user_id: Snowflake) -> Select<Self> {
-        Self::find()
+        Self::find_not_trashed()
             .join_rev(
                 JoinType::InnerJoin,
                 permissions::Entity::belongs_to(Self)
@@ -68,6 +82,11 @@ impl Entity {
             .filter(Column::Id.eq(id))
             .order_by(Column::Id, Order::Desc)
     }
+
+    // a single statement, so concurrent transactions can not overwrite each other's change of the balance
+    pub fn add_to_balance(id: Snowflake, amount: i64) -> UpdateMany<Self> {
+        Self::update_many().col_expr(Column::Balance, Expr::col(Column::Balance).add(amount)).filter(Column::Id.eq(id))
+    }
 }
 
 impl ActiveModel {
@@ -88,6 +107,8 @@ impl ActiveModel {
             original_balance: Set(balance),
             currency: Set(currency_id.id),
             created_at: Set(get_now()),
+            archived_at: Set(None),
+            version: Set(0),
         }
     }
 }
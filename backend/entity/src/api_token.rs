@@ -0,0 +1,59 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::{Order, QueryOrder};
+use serde::{Deserialize, Serialize};
+
+use utility::snowflake::entity::Snowflake;
+
+use crate::utility::hashing::hash_token;
+
+/// Personal access tokens. Only the hash of a token is stored, the token itself is shown once on creation.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "api_token")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: i64,
+    pub user: i64,
+    #[sea_orm(column_type = "Text")]
+    pub name: String,
+    #[sea_orm(column_type = "Text", unique)]
+    pub token_hash: String,
+    #[sea_orm(column_type = "JsonBinary")]
+    pub scopes: Json,
+    pub expires_at: Option<TimeDateTimeWithTimeZone>,
+    pub last_used_at: Option<TimeDateTimeWithTimeZone>,
+    pub created_at: TimeDateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::User",
+        to = "super::user::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    pub fn find_by_token(token: &str) -> Select<Self> {
+        Self::find().filter(Column::TokenHash.eq(hash_token(token)))
+    }
+
+    pub fn find_by_user_id(user_id: Snowflake) -> Select<Self> {
+        Self::find().filter(Column::User.eq(user_id)).order_by(Column::Id, Order::Desc)
+    }
+
+    pub fn find_by_id_and_user_id(id: Snowflake, user_id: Snowflake) -> Select<Self> {
+        Self::find_by_id(id).filter(Column::User.eq(user_id))
+    }
+}
@@ -0,0 +1,89 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Expr, SimpleExpr};
+use sea_orm::{Condition, Order, QueryOrder, QuerySelect, QueryTrait};
+use serde::{Deserialize, Serialize};
+
+use utility::snowflake::entity::Snowflake;
+
+use crate::{currency, permissions};
+
+const CHANGE_ACTIONS: [&str; 4] = ["create", "update", "delete", "restore"];
+const REVOKE_ACTION: &str = "revoke";
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "audit_log")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: i64,
+    pub actor: Option<i64>,
+    pub session: Option<i64>,
+    #[sea_orm(column_type = "Text")]
+    pub entity_type: String,
+    pub entity_id: i64,
+    #[sea_orm(column_type = "Text")]
+    pub action: String,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub before: Option<Json>,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub after: Option<Json>,
+    pub created_at: TimeDateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    pub fn find_all() -> Select<Self> {
+        Self::find().order_by(Column::Id, Order::Desc)
+    }
+
+    // entries the user caused themselves or that belong to an entity they (or one of their groups) were granted
+    pub fn find_all_by_user_id(user_id: Snowflake) -> Select<Self> {
+        let entity: SimpleExpr =
+            Expr::tuple([Expr::col((Self, Column::EntityType)).into(), Expr::col((Self, Column::EntityId)).into()])
+                .in_subquery(permissions::Entity::find_entities_by_user_id(user_id).into_query());
+
+        Self::find()
+            .filter(Condition::any().add(Column::Actor.eq(user_id)).add(entity))
+            .order_by(Column::Id, Order::Desc)
+    }
+
+    // changes between the two ids to entities the user (or one of their groups) was granted, global currencies have
+    // no grants and are readable by everyone. Revocations of the user's access are included, so the entities they
+    // can no longer read are removed from their copy.
+    pub fn find_changes_by_user_id(
+        user_id: Snowflake,
+        after: Snowflake,
+        before: Snowflake,
+        entity_types: Vec<&str>,
+    ) -> Select<Self> {
+        let granted: SimpleExpr =
+            Expr::tuple([Expr::col((Self, Column::EntityType)).into(), Expr::col((Self, Column::EntityId)).into()])
+                .in_subquery(permissions::Entity::find_entities_by_user_id(user_id).into_query());
+        let global_currency = Condition::all().add(Column::EntityType.eq(currency::Entity.table_name())).add(
+            Column::EntityId.in_subquery(
+                currency::Entity::find()
+                    .select_only()
+                    .column(currency::Column::Id)
+                    .filter(currency::Column::User.is_null())
+                    .into_query(),
+            ),
+        );
+
+        let changes = Condition::all()
+            .add(Column::Action.is_in(CHANGE_ACTIONS))
+            .add(Condition::any().add(granted).add(global_currency));
+        let revocations = Condition::all()
+            .add(Column::Action.eq(REVOKE_ACTION))
+            .add(Expr::cust_with_values(r#""audit_log"."before" ->> 'user_id' = $1"#, [user_id.id.to_string()]));
+
+        Self::find()
+            .filter(Column::Id.gt(after))
+            .filter(Column::Id.lt(before))
+            .filter(Column::EntityType.is_in(entity_types))
+            .filter(Condition::any().add(changes).add(revocations))
+            .order_by(Column::Id, Order::Asc)
+    }
+}
@@ -1,10 +1,11 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.0.0-rc.4
 
 use sea_orm::entity::prelude::*;
-use sea_orm::{Order, QueryOrder};
 use serde::{Deserialize, Serialize};
 
-use utility::snowflake::entity::Snowflake;
+use crate::permissions::find_all_by_user_id;
+use crate::trash::soft_delete;
+use crate::utility::version::versioned;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
 #[sea_orm(table_name = "budget")]
@@ -18,6 +19,7 @@ pub struct Model {
     #[sea_orm(column_type = "Text", nullable)]
     pub description: Option<String>,
     pub created_at: TimeDateTimeWithTimeZone,
+    pub version: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -56,8 +58,6 @@ impl Related<super::user::Entity> for Entity {
 
 impl ActiveModelBehavior for ActiveModel {}
 
-impl Entity {
-    pub fn find_all_by_user_id(user_id: Snowflake) -> Select<Self> {
-        Self::find().filter(Column::User.eq(user_id)).order_by(Column::Id, Order::Desc)
-    }
-}
+find_all_by_user_id!(Entity);
+soft_delete!(Entity);
+versioned!(Entity);
@@ -10,4 +10,6 @@ pub enum EntityError {
     DatabaseError(#[from] DbErr),
     #[error("An parsing error occurred")]
     ParsingError,
+    #[error("Unknown {0} stored in the database: {1}")]
+    UnknownValue(&'static str, String),
 }
@@ -0,0 +1,73 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::{Order, QueryOrder, QuerySelect, UpdateMany};
+use serde::{Deserialize, Serialize};
+
+use utility::snowflake::entity::Snowflake;
+
+/// The progress of one subscriber on one event. Pending deliveries are handled at `next_attempt_at`, deliveries
+/// whose handler kept failing end up in the dead-letter list with `failed_at` set.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "event_delivery")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: i64,
+    pub event: i64,
+    #[sea_orm(column_type = "Text")]
+    pub subscriber: String,
+    pub attempts: i32,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub error: Option<String>,
+    pub next_attempt_at: Option<TimeDateTimeWithTimeZone>,
+    pub processed_at: Option<TimeDateTimeWithTimeZone>,
+    pub failed_at: Option<TimeDateTimeWithTimeZone>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::event_outbox::Entity",
+        from = "Column::Event",
+        to = "super::event_outbox::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    EventOutbox,
+}
+
+impl Related<super::event_outbox::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::EventOutbox.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    pub fn find_all_due(now: TimeDateTimeWithTimeZone, limit: u64) -> Select<Self> {
+        Self::find()
+            .filter(Column::NextAttemptAt.lte(now))
+            .order_by(Column::NextAttemptAt, Order::Asc)
+            .order_by(Column::Event, Order::Asc)
+            .limit(limit)
+    }
+
+    /// Takes a delivery for this dispatcher by moving it to `lease_until`, only one dispatcher can succeed.
+    pub fn claim(
+        id: Snowflake,
+        next_attempt_at: TimeDateTimeWithTimeZone,
+        lease_until: TimeDateTimeWithTimeZone,
+    ) -> UpdateMany<Self> {
+        Self::update_many()
+            .col_expr(Column::NextAttemptAt, Expr::value(lease_until))
+            .filter(Column::Id.eq(id))
+            .filter(Column::NextAttemptAt.eq(next_attempt_at))
+    }
+
+    pub fn find_all_failed() -> Select<Self> {
+        Self::find().filter(Column::FailedAt.is_not_null()).order_by(Column::FailedAt, Order::Desc)
+    }
+
+    pub fn find_failed_by_id(id: Snowflake) -> Select<Self> {
+        Self::find_by_id(id).filter(Column::FailedAt.is_not_null())
+    }
+}
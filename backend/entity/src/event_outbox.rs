@@ -0,0 +1,43 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::{DeleteMany, QuerySelect, QueryTrait};
+use serde::{Deserialize, Serialize};
+
+/// Every fired lifecycle event, written in the same database transaction as the change it describes.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "event_outbox")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: i64,
+    #[sea_orm(column_type = "Text")]
+    pub event: String,
+    #[sea_orm(column_type = "JsonBinary")]
+    pub payload: Json,
+    pub created_at: TimeDateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::event_delivery::Entity")]
+    EventDelivery,
+}
+
+impl Related<super::event_delivery::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::EventDelivery.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    /// Events that were handled by all of their subscribers, or have none.
+    pub fn delete_all_handled_before(before: TimeDateTimeWithTimeZone) -> DeleteMany<Self> {
+        let pending = super::event_delivery::Entity::find()
+            .select_only()
+            .column(super::event_delivery::Column::Event)
+            .filter(super::event_delivery::Column::ProcessedAt.is_null())
+            .into_query();
+
+        Self::delete_many().filter(Column::CreatedAt.lt(before)).filter(Column::Id.not_in_subquery(pending))
+    }
+}
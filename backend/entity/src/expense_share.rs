@@ -0,0 +1,69 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::{Order, QueryOrder, QueryTrait};
+use serde::{Deserialize, Serialize};
+
+use utility::snowflake::entity::Snowflake;
+
+use crate::{expense, transaction, trash};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "expense_share")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub expense: i64,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user: i64,
+    pub value: i64,
+    pub amount: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::expense::Entity",
+        from = "Column::Expense",
+        to = "super::expense::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Expense,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::User",
+        to = "super::user::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::expense::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Expense.def()
+    }
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    pub fn find_all_by_expense_id(expense_id: Snowflake) -> Select<Self> {
+        Self::find().filter(Column::Expense.eq(expense_id)).order_by(Column::User, Order::Asc)
+    }
+
+    pub fn find_all_by_group_id(group_id: Snowflake) -> Select<Self> {
+        Self::find()
+            .inner_join(expense::Entity)
+            .filter(expense::Column::Group.eq(group_id))
+            .filter(
+                expense::Column::Transaction
+                    .not_in_subquery(trash::Entity::find_entity_ids(transaction::Entity.table_name()).into_query()),
+            )
+            .order_by(Column::Expense, Order::Desc)
+    }
+}
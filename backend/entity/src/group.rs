@@ -0,0 +1,53 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::{Order, QueryOrder, QueryTrait};
+use serde::{Deserialize, Serialize};
+
+use utility::snowflake::entity::Snowflake;
+
+use crate::group_member;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "group")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: i64,
+    #[sea_orm(column_type = "Text")]
+    pub name: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub description: Option<String>,
+    pub created_at: TimeDateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::group_member::Entity")]
+    GroupMember,
+    #[sea_orm(has_many = "super::permissions::Entity")]
+    Permissions,
+}
+
+impl Related<super::group_member::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::GroupMember.def()
+    }
+}
+
+impl Related<super::permissions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Permissions.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    pub fn find_all_by_user_id(user_id: Snowflake) -> Select<Self> {
+        Self::find()
+            .filter(Column::Id.in_subquery(group_member::Entity::find_group_ids_by_user_id(user_id).into_query()))
+            .order_by(Column::Id, Order::Desc)
+    }
+
+    pub fn find_all_by_ids(ids: Vec<i64>) -> Select<Self> {
+        Self::find().filter(Column::Id.is_in(ids)).order_by(Column::Id, Order::Desc)
+    }
+}
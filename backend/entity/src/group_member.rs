@@ -0,0 +1,69 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::{Order, QueryOrder, QuerySelect};
+use serde::{Deserialize, Serialize};
+
+use utility::snowflake::entity::Snowflake;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "group_member")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub group_id: i64,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: i64,
+    #[sea_orm(column_type = "Text")]
+    pub role: String,
+    pub joined_at: TimeDateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::group::Entity",
+        from = "Column::GroupId",
+        to = "super::group::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Group,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::group::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Group.def()
+    }
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    pub fn find_all_by_group_id(group_id: Snowflake) -> Select<Self> {
+        Self::find().filter(Column::GroupId.eq(group_id)).order_by(Column::JoinedAt, Order::Asc)
+    }
+
+    pub fn find_all_by_user_id(user_id: Snowflake) -> Select<Self> {
+        Self::find().filter(Column::UserId.eq(user_id)).order_by(Column::GroupId, Order::Desc)
+    }
+
+    pub fn find_group_ids_by_user_id(user_id: Snowflake) -> Select<Self> {
+        Self::find().select_only().column(Column::GroupId).filter(Column::UserId.eq(user_id))
+    }
+
+    pub fn find_membership(group_id: Snowflake, user_id: Snowflake) -> Select<Self> {
+        Self::find().filter(Column::GroupId.eq(group_id)).filter(Column::UserId.eq(user_id))
+    }
+}
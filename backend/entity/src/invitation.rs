@@ -0,0 +1,60 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::Expr;
+use sea_orm::{Condition, Order, QueryOrder, UpdateMany};
+use serde::{Deserialize, Serialize};
+
+use crate::utility::hashing::hash_token;
+
+/// Invitation codes for registering while registration is invite-only. Only the hash of a code is stored.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "invitation")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: i64,
+    #[sea_orm(column_type = "Text", unique)]
+    pub code_hash: String,
+    pub created_by: Option<i64>,
+    pub max_uses: Option<i32>,
+    pub uses: i32,
+    pub expires_at: Option<TimeDateTimeWithTimeZone>,
+    pub created_at: TimeDateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::CreatedBy",
+        to = "super::user::Column::Id",
+        on_update = "Cascade",
+        on_delete = "SetNull"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    pub fn find_all() -> Select<Self> {
+        Self::find().order_by(Column::Id, Order::Desc)
+    }
+
+    // a single statement, so concurrent registrations can not use a code more often than allowed
+    pub fn consume(code: &str, now: TimeDateTimeWithTimeZone) -> UpdateMany<Self> {
+        Self::update_many()
+            .col_expr(Column::Uses, Expr::col(Column::Uses).add(1))
+            .filter(Column::CodeHash.eq(hash_token(code)))
+            .filter(
+                Condition::any()
+                    .add(Column::MaxUses.is_null())
+                    .add(Expr::col(Column::Uses).lt(Expr::col(Column::MaxUses))),
+            )
+            .filter(Condition::any().add(Column::ExpiresAt.is_null()).add(Column::ExpiresAt.gt(now)))
+    }
+}
@@ -3,13 +3,32 @@
 pub mod prelude;
 
 pub mod account;
+pub mod api_token;
+pub mod audit_log;
 pub mod budget;
 pub mod currency;
 pub mod error;
+pub mod event_delivery;
+pub mod event_outbox;
+pub mod expense;
+pub mod expense_share;
+pub mod group;
+pub mod group_member;
+pub mod invitation;
+pub mod login_attempt;
+pub mod password_reset;
 pub mod permissions;
 pub mod recurring_transaction;
 pub mod session;
+pub mod settlement;
+pub mod totp_recovery_code;
 pub mod transaction;
 pub mod transaction_template;
+pub mod transaction_version;
+pub mod trash;
 pub mod user;
+pub mod user_identity;
+pub mod user_totp;
 pub mod utility;
+pub mod webhook;
+pub mod webhook_delivery;
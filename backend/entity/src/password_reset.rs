@@ -0,0 +1,52 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::DeleteMany;
+use serde::{Deserialize, Serialize};
+
+use utility::snowflake::entity::Snowflake;
+
+use crate::utility::hashing::hash_token;
+
+/// Pending password resets, only the hash of the mailed token is stored and it is deleted once used.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "password_reset")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
+    pub token_hash: String,
+    pub user: i64,
+    pub expires_at: TimeDateTimeWithTimeZone,
+    pub created_at: TimeDateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::User",
+        to = "super::user::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    pub fn find_valid_by_token(token: &str, now: TimeDateTimeWithTimeZone) -> Select<Self> {
+        Self::find().filter(Column::TokenHash.eq(hash_token(token))).filter(Column::ExpiresAt.gt(now))
+    }
+
+    pub fn delete_valid_by_token(token: &str, now: TimeDateTimeWithTimeZone) -> DeleteMany<Self> {
+        Self::delete_many().filter(Column::TokenHash.eq(hash_token(token))).filter(Column::ExpiresAt.gt(now))
+    }
+
+    pub fn delete_all_by_user_id(user_id: Snowflake) -> DeleteMany<Self> {
+        Self::delete_many().filter(Column::User.eq(user_id))
+    }
+}
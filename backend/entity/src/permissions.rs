@@ -1,27 +1,37 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.0.0-rc.7
 
 use sea_orm::entity::prelude::*;
-use sea_orm::{Order, QueryOrder, QuerySelect};
+use sea_orm::sea_query::{Expr, IntoCondition};
+use sea_orm::{Condition, FromQueryResult, JoinType, Order, QueryOrder, QuerySelect, QueryTrait};
 use serde::{Deserialize, Serialize};
 
 use utility::snowflake::entity::Snowflake;
 
-use crate::account;
+use crate::{account, group_member, trash};
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
 #[sea_orm(table_name = "permissions")]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
-    pub user_id: i64,
-    #[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
+    pub id: i64,
+    pub user_id: Option<i64>,
+    pub group_id: Option<i64>,
+    #[sea_orm(column_type = "Text")]
     pub entity_type: String,
-    #[sea_orm(primary_key, auto_increment = false)]
     pub entity_id: i64,
     pub permissions: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::group::Entity",
+        from = "Column::GroupId",
+        to = "super::group::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Group,
     #[sea_orm(
         belongs_to = "super::user::Entity",
         from = "Column::UserId",
@@ -32,6 +42,12 @@ pub enum Relation {
     User,
 }
 
+impl Related<super::group::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Group.def()
+    }
+}
+
 impl Related<super::user::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::User.def()
@@ -40,6 +56,13 @@ impl Related<super::user::Entity> for Entity {
 
 impl ActiveModelBehavior for ActiveModel {}
 
+/// A grant that applies to a user, `role` is the user's role in the group for grants to one of their groups.
+#[derive(Clone, Debug, PartialEq, Eq, FromQueryResult)]
+pub struct UserGrant {
+    pub permissions: i32,
+    pub role: Option<String>,
+}
+
 impl Entity {
     pub fn find_all() -> Select<Self> {
         Self::find().order_by(Column::EntityId, Order::Desc)
@@ -57,6 +80,91 @@ impl Entity {
             .order_by(Column::EntityId, Order::Desc)
     }
 
+    pub fn find_group_permission(group_id: Snowflake, entity_type: &str, entity_id: Snowflake) -> Select<Self> {
+        Self::find()
+            .filter(Column::GroupId.eq(group_id))
+            .filter(Column::EntityType.eq(entity_type))
+            .filter(Column::EntityId.eq(entity_id))
+            .order_by(Column::EntityId, Order::Desc)
+    }
+
+    pub fn find_all_by_group_id(group_id: Snowflake) -> Select<Self> {
+        Self::find().filter(Column::GroupId.eq(group_id)).order_by(Column::EntityId, Order::Desc)
+    }
+
+    // a group's members can read what the group can read, whatever their role
+    pub fn find_read_grants(entity_type: &str, entity_id: Snowflake) -> Select<Self> {
+        Self::find()
+            .filter(Column::EntityType.eq(entity_type))
+            .filter(Column::EntityId.eq(entity_id))
+            .filter(Expr::cust(r#""permissions"."permissions" & 1 <> 0"#))
+    }
+
+    // the direct grant and the grants of the user's groups in one statement
+    pub fn find_grants_by_user_id(user_id: Snowflake, entity_type: &str, entity_id: Snowflake) -> Select<Self> {
+        Self::find()
+            .select_only()
+            .column(Column::Permissions)
+            .column_as(group_member::Column::Role, "role")
+            .join(
+                JoinType::LeftJoin,
+                Self::belongs_to(group_member::Entity)
+                    .from(Column::GroupId)
+                    .to(group_member::Column::GroupId)
+                    .on_condition(move |_left, _right| {
+                        Expr::col((group_member::Entity, group_member::Column::UserId)).eq(user_id).into_condition()
+                    })
+                    .into(),
+            )
+            .filter(Column::EntityType.eq(entity_type))
+            .filter(Column::EntityId.eq(entity_id))
+            .filter(Condition::any().add(Column::UserId.eq(user_id)).add(group_member::Column::UserId.eq(user_id)))
+    }
+
+    // trashed entities are inaccessible until they are restored
+    pub fn find_grants_by_user_id_not_trashed(
+        user_id: Snowflake,
+        entity_type: &str,
+        entity_id: Snowflake,
+    ) -> Select<Self> {
+        Self::find_grants_by_user_id(user_id, entity_type, entity_id)
+            .filter(Column::EntityId.not_in_subquery(trash::Entity::find_entity_ids(entity_type).into_query()))
+    }
+
+    // entities no other user holds the owner permissions on, trashed ones are already on their way out
+    pub fn find_owned_only_by_user_id(user_id: Snowflake, entity_type: &str, owner_permissions: i32) -> Select<Self> {
+        let co_owned = Self::find()
+            .select_only()
+            .column(Column::EntityId)
+            .filter(Column::EntityType.eq(entity_type))
+            .filter(Column::UserId.ne(user_id))
+            .filter(Column::Permissions.eq(owner_permissions));
+
+        Self::find()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::EntityType.eq(entity_type))
+            .filter(Column::Permissions.eq(owner_permissions))
+            .filter(Column::EntityId.not_in_subquery(co_owned.into_query()))
+            .filter(Column::EntityId.not_in_subquery(trash::Entity::find_entity_ids(entity_type).into_query()))
+            .order_by(Column::EntityId, Order::Desc)
+    }
+
+    pub fn find_entity_ids_by_user_id(user_id: Snowflake, entity_type: &str) -> Select<Self> {
+        Self::find().select_only().column(Column::EntityId).filter(Column::EntityType.eq(entity_type)).filter(
+            Condition::any().add(Column::UserId.eq(user_id)).add(
+                Column::GroupId.in_subquery(group_member::Entity::find_group_ids_by_user_id(user_id).into_query()),
+            ),
+        )
+    }
+
+    pub fn find_entities_by_user_id(user_id: Snowflake) -> Select<Self> {
+        Self::find().select_only().column(Column::EntityType).column(Column::EntityId).filter(
+            Condition::any().add(Column::UserId.eq(user_id)).add(
+                Column::GroupId.in_subquery(group_member::Entity::find_group_ids_by_user_id(user_id).into_query()),
+            ),
+        )
+    }
+
     pub fn find_all_accounts_for_user_id(user_id: Snowflake) -> Select<Self> {
         Self::find()
             .filter(Column::UserId.eq(user_id))
@@ -85,19 +193,14 @@ macro_rules! find_all_by_user_id {
         impl $entity {
             pub fn find_all_by_user_id(user_id: utility::snowflake::entity::Snowflake) -> Select<Self> {
                 use sea_orm::QueryOrder;
-                use sea_orm::QuerySelect;
-
-                Self::find()
-                    .join_rev(
-                        sea_orm::JoinType::InnerJoin,
-                        crate::permissions::Entity::belongs_to(Self)
-                            .from(crate::permissions::Column::EntityId)
-                            .to(Column::Id)
-                            .into(),
-                    )
-                    .filter(crate::permissions::Column::UserId.eq(user_id))
-                    .filter(crate::permissions::Column::EntityType.eq(Self.table_name()))
-                    .order_by(crate::permissions::Column::EntityId, sea_orm::Order::Desc)
+                use sea_orm::QueryTrait;
+
+                // includes entities that were granted to one of the user's groups
+                Self::find_not_trashed()
+                    .filter(Column::Id.in_subquery(
+                        crate::permissions::Entity::find_entity_ids_by_user_id(user_id, Self.table_name()).into_query(),
+                    ))
+                    .order_by(Column::Id, sea_orm::Order::Desc)
             }
         }
     };
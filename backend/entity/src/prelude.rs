@@ -3,6 +3,8 @@
 pub use super::account::Entity as Account;
 pub use super::budget::Entity as Budget;
 pub use super::currency::Entity as Currency;
+pub use super::group::Entity as Group;
+pub use super::group_member::Entity as GroupMember;
 pub use super::permissions::Entity as Permissions;
 pub use super::session::Entity as Session;
 pub use super::transaction::Entity as Transaction;
@@ -1,9 +1,14 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.0.0-rc.7
 
 use sea_orm::entity::prelude::*;
+use sea_orm::{Condition, Order, QueryOrder};
 use serde::{Deserialize, Serialize};
 
+use utility::snowflake::entity::Snowflake;
+
 use crate::permissions::find_all_by_user_id;
+use crate::transaction_template;
+use crate::trash::soft_delete;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
 #[sea_orm(table_name = "recurring_transaction")]
@@ -37,3 +42,21 @@ impl Related<super::transaction_template::Entity> for Entity {
 impl ActiveModelBehavior for ActiveModel {}
 
 find_all_by_user_id!(Entity);
+soft_delete!(Entity);
+
+impl Entity {
+    pub fn find_all_by_account_id(account_id: Snowflake) -> Select<Self> {
+        Self::find_not_trashed()
+            .inner_join(transaction_template::Entity)
+            .filter(
+                Condition::any()
+                    .add(transaction_template::Column::Source.eq(account_id))
+                    .add(transaction_template::Column::Destination.eq(account_id)),
+            )
+            .order_by(Column::Id, Order::Desc)
+    }
+
+    pub fn find_all_by_template_id(template_id: Snowflake) -> Select<Self> {
+        Self::find_not_trashed().filter(Column::Template.eq(template_id)).order_by(Column::Id, Order::Desc)
+    }
+}
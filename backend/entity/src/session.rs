@@ -1,7 +1,8 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.0.0-rc.7
 
 use sea_orm::entity::prelude::*;
-use sea_orm::{DeleteMany, Order, QueryOrder, QuerySelect};
+use sea_orm::sea_query::Expr;
+use sea_orm::{DeleteMany, Order, QueryOrder, QuerySelect, UpdateMany};
 use serde::{Deserialize, Serialize};
 
 use utility::snowflake::entity::Snowflake;
@@ -20,6 +21,12 @@ pub struct Model {
     #[sea_orm(column_type = "Text", nullable)]
     pub platform: Option<String>,
     pub user: i64,
+    #[sea_orm(column_type = "Text")]
+    pub ip: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub user_agent: Option<String>,
+    pub last_used_at: Option<TimeDateTimeWithTimeZone>,
+    pub expires_at: TimeDateTimeWithTimeZone,
     pub created_at: TimeDateTimeWithTimeZone,
 }
 
@@ -56,6 +63,12 @@ impl Entity {
         Self::find().filter(Column::User.eq(user_id)).order_by_asc(Column::CreatedAt).limit(1)
     }
 
+    pub fn update_last_used_at(session_token: String, last_used_at: TimeDateTimeWithTimeZone) -> UpdateMany<Self> {
+        Self::update_many()
+            .col_expr(Column::LastUsedAt, Expr::value(last_used_at))
+            .filter(Column::Token.eq(session_token))
+    }
+
     pub fn delete_by_token(session_token: String) -> DeleteMany<Self> {
         Self::delete_many().filter(Column::Token.contains(session_token))
     }
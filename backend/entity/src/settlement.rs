@@ -0,0 +1,68 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::{Order, QueryOrder, QueryTrait};
+use serde::{Deserialize, Serialize};
+
+use utility::snowflake::entity::Snowflake;
+
+use crate::{transaction, trash};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "settlement")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: i64,
+    pub group: i64,
+    pub payer: i64,
+    pub payee: i64,
+    pub amount: i64,
+    pub currency: i64,
+    #[sea_orm(unique)]
+    pub transaction: i64,
+    pub created_at: TimeDateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::group::Entity",
+        from = "Column::Group",
+        to = "super::group::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Group,
+    #[sea_orm(
+        belongs_to = "super::transaction::Entity",
+        from = "Column::Transaction",
+        to = "super::transaction::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Transaction,
+}
+
+impl Related<super::group::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Group.def()
+    }
+}
+
+impl Related<super::transaction::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Transaction.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    pub fn find_all_by_group_id(group_id: Snowflake) -> Select<Self> {
+        Self::find()
+            .filter(Column::Group.eq(group_id))
+            .filter(
+                Column::Transaction
+                    .not_in_subquery(trash::Entity::find_entity_ids(transaction::Entity.table_name()).into_query()),
+            )
+            .order_by(Column::Id, Order::Desc)
+    }
+}
@@ -0,0 +1,53 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::DeleteMany;
+use serde::{Deserialize, Serialize};
+
+use utility::snowflake::entity::Snowflake;
+
+/// Hashed one-time recovery codes, a code is deleted once it has been used.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "totp_recovery_code")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: i64,
+    pub user: i64,
+    #[sea_orm(column_type = "Text")]
+    pub code: String,
+    pub created_at: TimeDateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::User",
+        to = "super::user::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    pub fn find_all_by_user_id(user_id: Snowflake) -> Select<Self> {
+        Self::find().filter(Column::User.eq(user_id))
+    }
+
+    pub fn delete_all_by_user_id(user_id: Snowflake) -> DeleteMany<Self> {
+        Self::delete_many().filter(Column::User.eq(user_id))
+    }
+}
+
+impl Model {
+    pub fn verify(&self, code: &str) -> bool {
+        argon2::verify_encoded(&self.code, code.as_bytes()).unwrap_or(false)
+    }
+}
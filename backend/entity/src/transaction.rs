@@ -7,6 +7,8 @@ use serde::{Deserialize, Serialize};
 use utility::snowflake::entity::Snowflake;
 
 use crate::permissions::find_all_by_user_id;
+use crate::trash::soft_delete;
+use crate::utility::version::versioned;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
 #[sea_orm(table_name = "transaction")]
@@ -24,6 +26,7 @@ pub struct Model {
     pub budget: Option<i64>,
     pub executed_at: TimeDateTimeWithTimeZone,
     pub created_at: TimeDateTimeWithTimeZone,
+    pub version: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -77,15 +80,17 @@ impl Related<super::currency::Entity> for Entity {
 impl ActiveModelBehavior for ActiveModel {}
 
 find_all_by_user_id!(Entity);
+soft_delete!(Entity);
+versioned!(Entity);
 
 impl Entity {
     pub fn find_all_by_account_id(account_id: Snowflake) -> Select<Self> {
-        Self::find()
+        Self::find_not_trashed()
             .filter(Condition::any().add(Column::Source.eq(account_id)).add(Column::Destination.eq(account_id)))
             .order_by(Column::Id, Order::Desc)
     }
 
     pub fn find_all_by_budget_id(budget_id: Snowflake) -> Select<Self> {
-        Self::find().filter(Column::Budget.eq(budget_id)).order_by(Column::Id, Order::Desc)
+        Self::find_not_trashed().filter(Column::Budget.eq(budget_id)).order_by(Column::Id, Order::Desc)
     }
 }
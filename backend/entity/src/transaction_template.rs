@@ -1,9 +1,13 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.0.0-rc.7
 
 use sea_orm::entity::prelude::*;
+use sea_orm::{Condition, Order, QueryOrder};
 use serde::{Deserialize, Serialize};
 
+use utility::snowflake::entity::Snowflake;
+
 use crate::permissions::find_all_by_user_id;
+use crate::trash::soft_delete;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
 #[sea_orm(table_name = "transaction_template")]
@@ -81,3 +85,16 @@ impl Related<super::recurring_transaction::Entity> for Entity {
 impl ActiveModelBehavior for ActiveModel {}
 
 find_all_by_user_id!(Entity);
+soft_delete!(Entity);
+
+impl Entity {
+    pub fn find_all_by_account_id(account_id: Snowflake) -> Select<Self> {
+        Self::find_not_trashed()
+            .filter(Condition::any().add(Column::Source.eq(account_id)).add(Column::Destination.eq(account_id)))
+            .order_by(Column::Id, Order::Desc)
+    }
+
+    pub fn find_all_by_budget_id(budget_id: Snowflake) -> Select<Self> {
+        Self::find_not_trashed().filter(Column::Budget.eq(budget_id)).order_by(Column::Id, Order::Desc)
+    }
+}
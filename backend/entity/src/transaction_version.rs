@@ -0,0 +1,56 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::{Order, QueryOrder};
+use serde::{Deserialize, Serialize};
+
+use utility::snowflake::entity::Snowflake;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "transaction_version")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub transaction: i64,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub revision: i32,
+    pub source: Option<i64>,
+    pub destination: Option<i64>,
+    pub amount: i64,
+    pub currency: i64,
+    #[sea_orm(column_type = "Text")]
+    pub name: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub description: Option<String>,
+    pub budget: Option<i64>,
+    pub executed_at: TimeDateTimeWithTimeZone,
+    pub actor: Option<i64>,
+    pub created_at: TimeDateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::transaction::Entity",
+        from = "Column::Transaction",
+        to = "super::transaction::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Transaction,
+}
+
+impl Related<super::transaction::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Transaction.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    pub fn find_all_by_transaction_id(transaction_id: Snowflake) -> Select<Self> {
+        Self::find().filter(Column::Transaction.eq(transaction_id)).order_by(Column::Revision, Order::Desc)
+    }
+
+    pub fn find_revision(transaction_id: Snowflake, revision: i32) -> Select<Self> {
+        Self::find().filter(Column::Transaction.eq(transaction_id)).filter(Column::Revision.eq(revision))
+    }
+}
@@ -0,0 +1,83 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Expr, SimpleExpr};
+use sea_orm::{Condition, Order, QueryOrder, QuerySelect, QueryTrait};
+use serde::{Deserialize, Serialize};
+
+use utility::snowflake::entity::Snowflake;
+
+use crate::permissions;
+
+/// Soft-deleted entities. Entries with a parent were deleted together with it and are restored with it.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "trash")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
+    pub entity_type: String,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub entity_id: i64,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub parent_type: Option<String>,
+    pub parent_id: Option<i64>,
+    pub deleted_by: Option<i64>,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub snapshot: Option<Json>,
+    pub deleted_at: TimeDateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    pub fn find_entity_ids(entity_type: &str) -> Select<Self> {
+        Self::find().select_only().column(Column::EntityId).filter(Column::EntityType.eq(entity_type))
+    }
+
+    pub fn find_entry(entity_type: &str, entity_id: Snowflake) -> Select<Self> {
+        Self::find().filter(Column::EntityType.eq(entity_type)).filter(Column::EntityId.eq(entity_id))
+    }
+
+    pub fn find_all_by_parent(parent_type: &str, parent_id: Snowflake) -> Select<Self> {
+        Self::find().filter(Column::ParentType.eq(parent_type)).filter(Column::ParentId.eq(parent_id))
+    }
+
+    // only the entries the user deleted or still holds a grant on, children are listed through their parent
+    pub fn find_all_by_user_id(user_id: Snowflake) -> Select<Self> {
+        let granted: SimpleExpr =
+            Expr::tuple([Expr::col((Self, Column::EntityType)).into(), Expr::col((Self, Column::EntityId)).into()])
+                .in_subquery(permissions::Entity::find_entities_by_user_id(user_id).into_query());
+
+        Self::find()
+            .filter(Column::ParentId.is_null())
+            .filter(Condition::any().add(Column::DeletedBy.eq(user_id)).add(granted))
+            .order_by(Column::DeletedAt, Order::Desc)
+    }
+
+    pub fn find_all_deleted_before(deleted_before: TimeDateTimeWithTimeZone) -> Select<Self> {
+        Self::find()
+            .filter(Column::ParentId.is_null())
+            .filter(Column::DeletedAt.lt(deleted_before))
+            .order_by(Column::DeletedAt, Order::Asc)
+    }
+}
+
+macro_rules! soft_delete {
+    ($entity:ty) => {
+        impl $entity {
+            pub fn find_not_trashed() -> Select<Self> {
+                use sea_orm::QueryTrait;
+
+                Self::find().filter(
+                    Column::Id.not_in_subquery(crate::trash::Entity::find_entity_ids(Self.table_name()).into_query()),
+                )
+            }
+
+            pub fn find_by_id_not_trashed(id: utility::snowflake::entity::Snowflake) -> Select<Self> {
+                Self::find_not_trashed().filter(Column::Id.eq(id))
+            }
+        }
+    };
+}
+
+pub(crate) use soft_delete;
@@ -1,8 +1,9 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.0.0-rc.7
 
 use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Expr, Func};
 use sea_orm::ActiveValue::Set;
-use sea_orm::{Order, QueryOrder};
+use sea_orm::{Condition, Order, QueryOrder};
 use serde::{Deserialize, Serialize};
 
 use crate::error::EntityError;
@@ -24,6 +25,7 @@ pub struct Model {
     pub password: String,
     pub created_at: TimeDateTimeWithTimeZone,
     pub is_admin: bool,
+    pub disabled_at: Option<TimeDateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -87,6 +89,15 @@ impl ActiveModel {
             password: Set(hashed_password),
             created_at: Set(get_now()),
             is_admin: Set(false),
+            disabled_at: Set(None),
+        })
+    }
+
+    pub fn change_password(id: i64, password: &str) -> Result<Self, EntityError> {
+        Ok(Self {
+            id: Set(id),
+            password: Set(hash_string(password)?),
+            ..Default::default()
         })
     }
 }
@@ -95,4 +106,27 @@ impl Entity {
     pub fn find_by_username(username: &str) -> Select<Self> {
         Self::find().filter(Column::Username.eq(username.to_string())).order_by(Column::Id, Order::Desc)
     }
+
+    pub fn find_all_by_ids(ids: Vec<i64>) -> Select<Self> {
+        Self::find().filter(Column::Id.is_in(ids)).order_by(Column::Id, Order::Desc)
+    }
+
+    pub fn find_by_email(email: &str) -> Select<Self> {
+        Self::find().filter(Column::Email.eq(email.to_string()))
+    }
+
+    // case-insensitive substring match on the username, email and display name
+    pub fn search(term: &str) -> Select<Self> {
+        let pattern = format!("%{}%", term.to_lowercase());
+        let matches = |column: Column| Expr::expr(Func::lower(Expr::col((Self, column)))).like(pattern.as_str());
+
+        Self::find()
+            .filter(
+                Condition::any()
+                    .add(matches(Column::Username))
+                    .add(matches(Column::Email))
+                    .add(matches(Column::DisplayName)),
+            )
+            .order_by(Column::Id, Order::Desc)
+    }
 }
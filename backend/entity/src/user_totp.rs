@@ -0,0 +1,49 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::Expr;
+use sea_orm::{Condition, UpdateMany};
+use serde::{Deserialize, Serialize};
+
+use utility::snowflake::entity::Snowflake;
+
+/// The encrypted TOTP secret of a user. It only guards the login once `confirmed_at` is set.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "user_totp")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user: i64,
+    #[sea_orm(column_type = "Text")]
+    pub secret: String,
+    pub last_used_step: Option<i64>,
+    pub confirmed_at: Option<TimeDateTimeWithTimeZone>,
+    pub created_at: TimeDateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::User",
+        to = "super::user::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    // only matches if the step was not used yet, so of two requests with the same code only one updates the row
+    pub fn use_step(user_id: Snowflake, step: i64) -> UpdateMany<Self> {
+        Self::update_many()
+            .col_expr(Column::LastUsedStep, Expr::value(step))
+            .filter(Column::User.eq(user_id))
+            .filter(Condition::any().add(Column::LastUsedStep.is_null()).add(Column::LastUsedStep.lt(step)))
+    }
+}
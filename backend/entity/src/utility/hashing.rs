@@ -1,6 +1,9 @@
+use std::fmt::Write;
+
 use argon2::Config;
 use rand::distributions::Alphanumeric;
 use rand::Rng;
+use sha2::{Digest, Sha256};
 
 use crate::error::EntityError;
 
@@ -17,3 +20,11 @@ pub fn hash_string_with_salt(password: &str, salt: &str) -> Result<String, Entit
     let config = Config::rfc9106_low_mem();
     argon2::hash_encoded(password.as_bytes(), salt.as_bytes(), &config).map_err(EntityError::HashingFailed)
 }
+
+// tokens are long and random, so a fast hash is enough and keeps them searchable
+pub fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes()).iter().fold(String::with_capacity(64), |mut hash, byte| {
+        let _ = write!(hash, "{:02x}", byte);
+        hash
+    })
+}
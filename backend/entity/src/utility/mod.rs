@@ -2,3 +2,4 @@ pub mod hashing;
 pub mod loading;
 pub mod table;
 pub mod time;
+pub mod version;
@@ -24,27 +24,120 @@ CREATE TABLE IF NOT EXISTS "user"
     display_name TEXT,
     password     TEXT                     NOT NULL,
     created_at   timestamp with time zone NOT NULL DEFAULT current_timestamp,
-    is_admin     BOOLEAN                  NOT NULL DEFAULT FALSE
+    is_admin     BOOLEAN                  NOT NULL DEFAULT FALSE,
+    disabled_at  timestamp with time zone
 );
 
 CREATE TABLE IF NOT EXISTS session
+(
+    id           BIGINT PRIMARY KEY,
+    token        TEXT UNIQUE                                                        NOT NULL,
+    name         TEXT                                                               NOT NULL,
+    description  TEXT,
+    platform     TEXT,
+    "user"       BIGINT REFERENCES "user" (id) ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    ip           TEXT                                                               NOT NULL,
+    user_agent   TEXT,
+    last_used_at timestamp with time zone,
+    expires_at   timestamp with time zone                                           NOT NULL,
+    created_at   timestamp with time zone                                           NOT NULL DEFAULT current_timestamp
+);
+
+CREATE TABLE IF NOT EXISTS api_token
+(
+    id           BIGINT PRIMARY KEY,
+    "user"       BIGINT REFERENCES "user" (id) ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    name         TEXT                                                               NOT NULL,
+    token_hash   TEXT UNIQUE                                                        NOT NULL,
+    scopes       JSONB                                                              NOT NULL,
+    expires_at   timestamp with time zone,
+    last_used_at timestamp with time zone,
+    created_at   timestamp with time zone                                           NOT NULL DEFAULT current_timestamp
+);
+
+CREATE TABLE IF NOT EXISTS user_identity
+(
+    issuer     TEXT                                                               NOT NULL,
+    subject    TEXT                                                               NOT NULL,
+    "user"     BIGINT REFERENCES "user" (id) ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    created_at timestamp with time zone                                           NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (issuer, subject)
+);
+
+CREATE TABLE IF NOT EXISTS login_attempt
+(
+    id         BIGINT PRIMARY KEY,
+    "user"     BIGINT REFERENCES "user" (id) ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    ip         TEXT                                                               NOT NULL,
+    user_agent TEXT,
+    successful BOOLEAN                                                            NOT NULL,
+    created_at timestamp with time zone                                           NOT NULL DEFAULT current_timestamp
+);
+
+CREATE TABLE IF NOT EXISTS password_reset
+(
+    token_hash TEXT PRIMARY KEY,
+    "user"     BIGINT REFERENCES "user" (id) ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    expires_at timestamp with time zone                                           NOT NULL,
+    created_at timestamp with time zone                                           NOT NULL DEFAULT current_timestamp
+);
+
+CREATE TABLE IF NOT EXISTS invitation
+(
+    id         BIGINT PRIMARY KEY,
+    code_hash  TEXT UNIQUE                                                     NOT NULL,
+    created_by BIGINT REFERENCES "user" (id) ON UPDATE CASCADE ON DELETE SET NULL,
+    max_uses   INTEGER,
+    uses       INTEGER                                                         NOT NULL DEFAULT 0,
+    expires_at timestamp with time zone,
+    created_at timestamp with time zone                                        NOT NULL DEFAULT current_timestamp
+);
+
+CREATE TABLE IF NOT EXISTS user_totp
+(
+    "user"         BIGINT PRIMARY KEY REFERENCES "user" (id) ON UPDATE CASCADE ON DELETE CASCADE,
+    secret         TEXT                     NOT NULL,
+    last_used_step BIGINT,
+    confirmed_at   timestamp with time zone,
+    created_at     timestamp with time zone NOT NULL DEFAULT current_timestamp
+);
+
+CREATE TABLE IF NOT EXISTS totp_recovery_code
+(
+    id         BIGINT PRIMARY KEY,
+    "user"     BIGINT REFERENCES "user" (id) ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    code       TEXT                                                               NOT NULL,
+    created_at timestamp with time zone                                           NOT NULL DEFAULT current_timestamp
+);
+
+CREATE TABLE IF NOT EXISTS "group"
 (
     id          BIGINT PRIMARY KEY,
-    token       TEXT UNIQUE                                                        NOT NULL,
-    name        TEXT                                                               NOT NULL,
+    name        TEXT                     NOT NULL,
     description TEXT,
-    platform    TEXT,
-    "user"      BIGINT REFERENCES "user" (id) ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
-    created_at  timestamp with time zone                                           NOT NULL DEFAULT current_timestamp
+    created_at  timestamp with time zone NOT NULL DEFAULT current_timestamp
+);
+
+CREATE TABLE IF NOT EXISTS group_member
+(
+    group_id  BIGINT REFERENCES "group" (id) ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    user_id   BIGINT REFERENCES "user" (id) ON UPDATE CASCADE ON DELETE CASCADE  NOT NULL,
+    role      TEXT                                                               NOT NULL,
+    joined_at timestamp with time zone                                           NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (group_id, user_id),
+    CHECK (role IN ('owner', 'member', 'viewer'))
 );
 
 CREATE TABLE IF NOT EXISTS permissions
 (
-    user_id     BIGINT REFERENCES "user" (id) ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
-    entity_type TEXT                                                              NOT NULL,
-    entity_id   BIGINT                                                            NOT NULL,
-    permissions INTEGER                                                           NOT NULL,
-    PRIMARY KEY (user_id, entity_type, entity_id)
+    id          BIGINT PRIMARY KEY,
+    user_id     BIGINT REFERENCES "user" (id) ON UPDATE CASCADE ON DELETE CASCADE,
+    group_id    BIGINT REFERENCES "group" (id) ON UPDATE CASCADE ON DELETE CASCADE,
+    entity_type TEXT    NOT NULL,
+    entity_id   BIGINT  NOT NULL,
+    permissions INTEGER NOT NULL,
+    UNIQUE NULLS NOT DISTINCT (user_id, group_id, entity_type, entity_id),
+    CHECK ((user_id IS NULL) <> (group_id IS NULL))
 );
 
 CREATE TABLE IF NOT EXISTS currency
@@ -66,7 +159,9 @@ CREATE TABLE IF NOT EXISTS account
     balance          BIGINT                                                              NOT NULL DEFAULT 0,
     original_balance BIGINT                                                              NOT NULL DEFAULT 0,
     currency         BIGINT REFERENCES Currency (id) ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
-    created_at       timestamp with time zone                                            NOT NULL DEFAULT current_timestamp
+    created_at       timestamp with time zone                                            NOT NULL DEFAULT current_timestamp,
+    archived_at      timestamp with time zone,
+    version          INTEGER                                                             NOT NULL DEFAULT 0
 );
 
 CREATE TABLE IF NOT EXISTS budget
@@ -76,7 +171,8 @@ CREATE TABLE IF NOT EXISTS budget
     amount      BIGINT                                                            NOT NULL,
     name        TEXT                                                              NOT NULL,
     description TEXT,
-    created_at  timestamp with time zone                                          NOT NULL DEFAULT current_timestamp
+    created_at  timestamp with time zone                                          NOT NULL DEFAULT current_timestamp,
+    version     INTEGER                                                           NOT NULL DEFAULT 0
 );
 
 CREATE TABLE IF NOT EXISTS transaction_template
@@ -115,5 +211,153 @@ CREATE TABLE IF NOT EXISTS transaction
     budget      BIGINT REFERENCES budget (id) ON UPDATE CASCADE ON DELETE CASCADE,
     executed_at timestamp with time zone                                            NOT NULL DEFAULT current_timestamp,
     created_at  timestamp with time zone                                            NOT NULL DEFAULT current_timestamp,
+    version     INTEGER                                                             NOT NULL DEFAULT 0,
     CHECK (source IS NOT NULL OR destination IS NOT NULL)
 );
+
+CREATE TABLE IF NOT EXISTS transaction_version
+(
+    transaction BIGINT REFERENCES transaction (id) ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    revision    INTEGER                                                                NOT NULL,
+    source      BIGINT,
+    destination BIGINT,
+    amount      BIGINT                                                                 NOT NULL,
+    currency    BIGINT                                                                 NOT NULL,
+    name        TEXT                                                                   NOT NULL,
+    description TEXT,
+    budget      BIGINT,
+    executed_at timestamp with time zone                                               NOT NULL,
+    actor       BIGINT,
+    created_at  timestamp with time zone                                               NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (transaction, revision)
+);
+
+CREATE TABLE IF NOT EXISTS expense
+(
+    id          BIGINT PRIMARY KEY,
+    transaction BIGINT UNIQUE REFERENCES transaction (id) ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    "group"     BIGINT REFERENCES "group" (id) ON UPDATE CASCADE ON DELETE CASCADE           NOT NULL,
+    payer       BIGINT REFERENCES "user" (id) ON UPDATE CASCADE ON DELETE CASCADE            NOT NULL,
+    amount      BIGINT                                                                       NOT NULL,
+    currency    BIGINT REFERENCES currency (id) ON UPDATE CASCADE ON DELETE CASCADE          NOT NULL,
+    split_type  TEXT                                                                         NOT NULL,
+    created_at  timestamp with time zone                                                     NOT NULL DEFAULT current_timestamp,
+    CHECK (split_type IN ('shares', 'percentages', 'exact'))
+);
+
+CREATE TABLE IF NOT EXISTS expense_share
+(
+    expense BIGINT REFERENCES expense (id) ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    "user"  BIGINT REFERENCES "user" (id) ON UPDATE CASCADE ON DELETE CASCADE  NOT NULL,
+    value   BIGINT                                                             NOT NULL,
+    amount  BIGINT                                                             NOT NULL,
+    PRIMARY KEY (expense, "user")
+);
+
+CREATE TABLE IF NOT EXISTS settlement
+(
+    id          BIGINT PRIMARY KEY,
+    "group"     BIGINT REFERENCES "group" (id) ON UPDATE CASCADE ON DELETE CASCADE           NOT NULL,
+    payer       BIGINT REFERENCES "user" (id) ON UPDATE CASCADE ON DELETE CASCADE            NOT NULL,
+    payee       BIGINT REFERENCES "user" (id) ON UPDATE CASCADE ON DELETE CASCADE            NOT NULL,
+    amount      BIGINT                                                                       NOT NULL,
+    currency    BIGINT REFERENCES currency (id) ON UPDATE CASCADE ON DELETE CASCADE          NOT NULL,
+    transaction BIGINT UNIQUE REFERENCES transaction (id) ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    created_at  timestamp with time zone                                                     NOT NULL DEFAULT current_timestamp,
+    CHECK (amount > 0 AND payer <> payee)
+);
+
+CREATE TABLE IF NOT EXISTS audit_log
+(
+    id          BIGINT PRIMARY KEY,
+    actor       BIGINT,
+    session     BIGINT,
+    entity_type TEXT                     NOT NULL,
+    entity_id   BIGINT                   NOT NULL,
+    action      TEXT                     NOT NULL,
+    before      jsonb,
+    after       jsonb,
+    created_at  timestamp with time zone NOT NULL DEFAULT current_timestamp,
+    CHECK (action IN ('create', 'update', 'delete', 'restore', 'reset_password', 'unlock', 'revoke'))
+);
+
+CREATE INDEX IF NOT EXISTS audit_log_entity_idx ON audit_log (entity_type, entity_id);
+CREATE INDEX IF NOT EXISTS audit_log_revoked_user_idx ON audit_log ((before ->> 'user_id')) WHERE action = 'revoke';
+
+CREATE OR REPLACE FUNCTION reject_audit_log_modification() RETURNS trigger AS
+$$
+BEGIN
+    RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER audit_log_append_only
+    BEFORE UPDATE OR DELETE
+    ON audit_log
+    FOR EACH ROW
+EXECUTE FUNCTION reject_audit_log_modification();
+
+CREATE TABLE IF NOT EXISTS trash
+(
+    entity_type TEXT                     NOT NULL,
+    entity_id   BIGINT                   NOT NULL,
+    parent_type TEXT,
+    parent_id   BIGINT,
+    deleted_by  BIGINT,
+    snapshot    jsonb,
+    deleted_at  timestamp with time zone NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (entity_type, entity_id)
+);
+
+CREATE INDEX IF NOT EXISTS trash_parent_idx ON trash (parent_type, parent_id);
+
+CREATE TABLE IF NOT EXISTS webhook
+(
+    id         BIGINT PRIMARY KEY,
+    "user"     BIGINT REFERENCES "user" (id) ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    url        TEXT                                                               NOT NULL,
+    secret     TEXT                                                               NOT NULL,
+    events     JSONB                                                              NOT NULL,
+    active     BOOLEAN                                                            NOT NULL DEFAULT TRUE,
+    created_at timestamp with time zone                                           NOT NULL DEFAULT current_timestamp
+);
+
+CREATE TABLE IF NOT EXISTS webhook_delivery
+(
+    id              BIGINT PRIMARY KEY,
+    webhook         BIGINT REFERENCES webhook (id) ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    event           TEXT                                                             NOT NULL,
+    payload         JSONB                                                            NOT NULL,
+    attempts        INTEGER                                                          NOT NULL DEFAULT 0,
+    response_status INTEGER,
+    error           TEXT,
+    delivered_at    timestamp with time zone,
+    next_attempt_at timestamp with time zone,
+    created_at      timestamp with time zone                                         NOT NULL DEFAULT current_timestamp
+);
+
+CREATE INDEX IF NOT EXISTS webhook_delivery_due_idx ON webhook_delivery (next_attempt_at) WHERE delivered_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS event_outbox
+(
+    id         BIGINT PRIMARY KEY,
+    event      TEXT                     NOT NULL,
+    payload    JSONB                    NOT NULL,
+    created_at timestamp with time zone NOT NULL DEFAULT current_timestamp
+);
+
+CREATE TABLE IF NOT EXISTS event_delivery
+(
+    id              BIGINT PRIMARY KEY,
+    event           BIGINT REFERENCES event_outbox (id) ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    subscriber      TEXT                                                                    NOT NULL,
+    attempts        INTEGER                                                                 NOT NULL DEFAULT 0,
+    error           TEXT,
+    next_attempt_at timestamp with time zone,
+    processed_at    timestamp with time zone,
+    failed_at       timestamp with time zone,
+    UNIQUE (event, subscriber)
+);
+
+CREATE INDEX IF NOT EXISTS event_delivery_due_idx ON event_delivery (next_attempt_at) WHERE next_attempt_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS event_delivery_failed_idx ON event_delivery (failed_at) WHERE failed_at IS NOT NULL;
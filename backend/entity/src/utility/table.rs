@@ -25,3 +25,11 @@ pub async fn does_entity_exist(table_name: &str, id: Snowflake, db: &impl Connec
         None => Ok(false),
     }
 }
+
+pub async fn delete_entity(table_name: &str, id: Snowflake, db: &impl ConnectionTrait) -> Result<(), DbErr> {
+    let query = format!("DELETE FROM {} WHERE id = {}", table_name, id.id);
+    let statement = Statement::from_string(DbBackend::Postgres, query);
+    db.execute(statement).await?;
+
+    Ok(())
+}
@@ -0,0 +1,13 @@
+/// Optimistic concurrency for entities with a `version` column, which every update increments.
+macro_rules! versioned {
+    ($entity:ty) => {
+        impl $entity {
+            /// Only updates the row if it is still at `version`, so changes made in the meantime are not overwritten.
+            pub fn update_if_version(active_model: ActiveModel, version: i32) -> sea_orm::UpdateOne<ActiveModel> {
+                Self::update(active_model).filter(Column::Version.eq(version))
+            }
+        }
+    };
+}
+
+pub(crate) use versioned;
@@ -0,0 +1,82 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::{Condition, Order, QueryOrder, QuerySelect, QueryTrait};
+use serde::{Deserialize, Serialize};
+
+use utility::snowflake::entity::Snowflake;
+
+use crate::{group_member, permissions};
+
+/// Outgoing webhooks of a user. The secret is kept in plain text, it is needed to sign every delivery.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "webhook")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: i64,
+    pub user: i64,
+    #[sea_orm(column_type = "Text")]
+    pub url: String,
+    #[sea_orm(column_type = "Text")]
+    pub secret: String,
+    #[sea_orm(column_type = "JsonBinary")]
+    pub events: Json,
+    pub active: bool,
+    pub created_at: TimeDateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::User",
+        to = "super::user::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    User,
+    #[sea_orm(has_many = "super::webhook_delivery::Entity")]
+    WebhookDelivery,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl Related<super::webhook_delivery::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WebhookDelivery.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    pub fn find_by_user_id(user_id: Snowflake) -> Select<Self> {
+        Self::find().filter(Column::User.eq(user_id)).order_by(Column::Id, Order::Desc)
+    }
+
+    pub fn find_by_id_and_user_id(id: Snowflake, user_id: Snowflake) -> Select<Self> {
+        Self::find_by_id(id).filter(Column::User.eq(user_id))
+    }
+
+    pub fn find_all_active_by_event(event: &str) -> Select<Self> {
+        Self::find()
+            .filter(Column::Active.eq(true))
+            .filter(Expr::cust_with_values(r#""webhook"."events" @> $1"#, [Json::from(vec![event])]))
+    }
+
+    // only the webhooks whose owner can read the entity, directly or through one of their groups
+    pub fn find_all_active_by_event_and_reader(event: &str, entity_type: &str, entity_id: Snowflake) -> Select<Self> {
+        let read_grants = || permissions::Entity::find_read_grants(entity_type, entity_id).select_only();
+        let group_readers = group_member::Entity::find().select_only().column(group_member::Column::UserId).filter(
+            group_member::Column::GroupId.in_subquery(read_grants().column(permissions::Column::GroupId).into_query()),
+        );
+
+        Self::find_all_active_by_event(event).filter(
+            Condition::any()
+                .add(Column::User.in_subquery(read_grants().column(permissions::Column::UserId).into_query()))
+                .add(Column::User.in_subquery(group_readers.into_query())),
+        )
+    }
+}
@@ -0,0 +1,62 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::{Order, QueryOrder};
+use serde::{Deserialize, Serialize};
+
+use utility::snowflake::entity::Snowflake;
+
+/// Every event sent to a webhook. Failed deliveries are retried at `next_attempt_at` until they run out of attempts.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "webhook_delivery")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: i64,
+    pub webhook: i64,
+    #[sea_orm(column_type = "Text")]
+    pub event: String,
+    #[sea_orm(column_type = "JsonBinary")]
+    pub payload: Json,
+    pub attempts: i32,
+    pub response_status: Option<i32>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub error: Option<String>,
+    pub delivered_at: Option<TimeDateTimeWithTimeZone>,
+    pub next_attempt_at: Option<TimeDateTimeWithTimeZone>,
+    pub created_at: TimeDateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::webhook::Entity",
+        from = "Column::Webhook",
+        to = "super::webhook::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Webhook,
+}
+
+impl Related<super::webhook::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Webhook.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    pub fn find_all_by_webhook_id(webhook_id: Snowflake) -> Select<Self> {
+        Self::find().filter(Column::Webhook.eq(webhook_id)).order_by(Column::Id, Order::Desc)
+    }
+
+    pub fn find_by_id_and_webhook_id(id: Snowflake, webhook_id: Snowflake) -> Select<Self> {
+        Self::find_by_id(id).filter(Column::Webhook.eq(webhook_id))
+    }
+
+    pub fn find_all_due(now: TimeDateTimeWithTimeZone) -> Select<Self> {
+        Self::find()
+            .filter(Column::DeliveredAt.is_null())
+            .filter(Column::NextAttemptAt.lte(now))
+            .order_by(Column::NextAttemptAt, Order::Asc)
+    }
+}
@@ -1,6 +1,10 @@
 use sea_orm::{DatabaseBackend, QueryTrait};
+use time::OffsetDateTime;
 
-use entity::{account, transaction};
+use entity::{
+    account, audit_log, budget, event_delivery, event_outbox, expense_share, group, invitation, permissions,
+    recurring_transaction, settlement, transaction, trash, user, webhook,
+};
 
 #[test]
 fn test_find_all_accounts_by_user_id() {
@@ -8,7 +12,18 @@ fn test_find_all_accounts_by_user_id() {
     let query = account::Entity::find_all_by_user_id(user_id.into());
     let actual_sql = query.build(DatabaseBackend::Postgres).to_string();
 
-    let expected_sql = "SELECT \"account\".\"id\", \"account\".\"name\", \"account\".\"description\", \"account\".\"iban\", \"account\".\"balance\", \"account\".\"original_balance\", \"account\".\"currency\", \"account\".\"created_at\" FROM \"account\" INNER JOIN \"permissions\" ON \"permissions\".\"entity_id\" = \"account\".\"id\" WHERE \"permissions\".\"user_id\" = 1 AND \"permissions\".\"entity_type\" = 'account' ORDER BY \"permissions\".\"entity_id\" DESC";
+    let expected_sql = "SELECT \"account\".\"id\", \"account\".\"name\", \"account\".\"description\", \"account\".\"iban\", \"account\".\"balance\", \"account\".\"original_balance\", \"account\".\"currency\", \"account\".\"created_at\", \"account\".\"archived_at\", \"account\".\"version\" FROM \"account\" WHERE \"account\".\"id\" NOT IN (SELECT \"trash\".\"entity_id\" FROM \"trash\" WHERE \"trash\".\"entity_type\" = 'account') AND \"account\".\"id\" IN (SELECT \"permissions\".\"entity_id\" FROM \"permissions\" WHERE \"permissions\".\"entity_type\" = 'account' AND (\"permissions\".\"user_id\" = 1 OR \"permissions\".\"group_id\" IN (SELECT \"group_member\".\"group_id\" FROM \"group_member\" WHERE \"group_member\".\"user_id\" = 1))) ORDER BY \"account\".\"id\" DESC";
+
+    assert_eq!(actual_sql, expected_sql);
+}
+
+#[test]
+fn test_find_all_active_accounts_by_user_id() {
+    let user_id = 1;
+    let query = account::Entity::find_all_active_by_user_id(user_id.into());
+    let actual_sql = query.build(DatabaseBackend::Postgres).to_string();
+
+    let expected_sql = "SELECT \"account\".\"id\", \"account\".\"name\", \"account\".\"description\", \"account\".\"iban\", \"account\".\"balance\", \"account\".\"original_balance\", \"account\".\"currency\", \"account\".\"created_at\", \"account\".\"archived_at\", \"account\".\"version\" FROM \"account\" WHERE \"account\".\"id\" NOT IN (SELECT \"trash\".\"entity_id\" FROM \"trash\" WHERE \"trash\".\"entity_type\" = 'account') AND \"account\".\"id\" IN (SELECT \"permissions\".\"entity_id\" FROM \"permissions\" WHERE \"permissions\".\"entity_type\" = 'account' AND (\"permissions\".\"user_id\" = 1 OR \"permissions\".\"group_id\" IN (SELECT \"group_member\".\"group_id\" FROM \"group_member\" WHERE \"group_member\".\"user_id\" = 1))) AND \"account\".\"archived_at\" IS NULL ORDER BY \"account\".\"id\" DESC";
 
     assert_eq!(actual_sql, expected_sql);
 }
@@ -20,7 +35,7 @@ fn test_find_by_id_and_user_id() {
     let query = account::Entity::find_by_id_and_user_id(account_id.into(), user_id.into());
     let actual_sql = query.build(DatabaseBackend::Postgres).to_string();
 
-    let expected_sql = "SELECT \"account\".\"id\", \"account\".\"name\", \"account\".\"description\", \"account\".\"iban\", \"account\".\"balance\", \"account\".\"original_balance\", \"account\".\"currency\", \"account\".\"created_at\" FROM \"account\" INNER JOIN \"permissions\" ON \"permissions\".\"entity_id\" = \"account\".\"id\" AND \"entity_type\" = 'account' WHERE \"permissions\".\"user_id\" = 1 AND \"account\".\"id\" = 13 ORDER BY \"account\".\"id\" DESC";
+    let expected_sql = "SELECT \"account\".\"id\", \"account\".\"name\", \"account\".\"description\", \"account\".\"iban\", \"account\".\"balance\", \"account\".\"original_balance\", \"account\".\"currency\", \"account\".\"created_at\", \"account\".\"archived_at\", \"account\".\"version\" FROM \"account\" INNER JOIN \"permissions\" ON \"permissions\".\"entity_id\" = \"account\".\"id\" AND \"entity_type\" = 'account' WHERE \"account\".\"id\" NOT IN (SELECT \"trash\".\"entity_id\" FROM \"trash\" WHERE \"trash\".\"entity_type\" = 'account') AND \"permissions\".\"user_id\" = 1 AND \"account\".\"id\" = 13 ORDER BY \"account\".\"id\" DESC";
 
     assert_eq!(actual_sql, expected_sql);
 }
@@ -31,7 +46,180 @@ fn test_find_all_transactions_by_user_id() {
     let query = transaction::Entity::find_all_by_user_id(user_id.into());
     let actual_sql = query.build(DatabaseBackend::Postgres).to_string();
 
-    let expected = "SELECT \"transaction\".\"id\", \"transaction\".\"source\", \"transaction\".\"destination\", \"transaction\".\"amount\", \"transaction\".\"currency\", \"transaction\".\"name\", \"transaction\".\"description\", \"transaction\".\"budget\", \"transaction\".\"executed_at\", \"transaction\".\"created_at\" FROM \"transaction\" INNER JOIN \"permissions\" ON \"permissions\".\"entity_id\" = \"transaction\".\"id\" WHERE \"permissions\".\"user_id\" = 1 AND \"permissions\".\"entity_type\" = 'transaction' ORDER BY \"permissions\".\"entity_id\" DESC";
+    let expected = "SELECT \"transaction\".\"id\", \"transaction\".\"source\", \"transaction\".\"destination\", \"transaction\".\"amount\", \"transaction\".\"currency\", \"transaction\".\"name\", \"transaction\".\"description\", \"transaction\".\"budget\", \"transaction\".\"executed_at\", \"transaction\".\"created_at\", \"transaction\".\"version\" FROM \"transaction\" WHERE \"transaction\".\"id\" NOT IN (SELECT \"trash\".\"entity_id\" FROM \"trash\" WHERE \"trash\".\"entity_type\" = 'transaction') AND \"transaction\".\"id\" IN (SELECT \"permissions\".\"entity_id\" FROM \"permissions\" WHERE \"permissions\".\"entity_type\" = 'transaction' AND (\"permissions\".\"user_id\" = 1 OR \"permissions\".\"group_id\" IN (SELECT \"group_member\".\"group_id\" FROM \"group_member\" WHERE \"group_member\".\"user_id\" = 1))) ORDER BY \"transaction\".\"id\" DESC";
 
     assert_eq!(actual_sql, expected);
 }
+
+#[test]
+fn test_find_all_recurring_transactions_by_account_id() {
+    let account_id = 13;
+    let query = recurring_transaction::Entity::find_all_by_account_id(account_id.into());
+    let actual_sql = query.build(DatabaseBackend::Postgres).to_string();
+
+    let expected_sql = "SELECT \"recurring_transaction\".\"id\", \"recurring_transaction\".\"template\", \"recurring_transaction\".\"recurring_rule\", \"recurring_transaction\".\"last_executed_at\", \"recurring_transaction\".\"created_at\" FROM \"recurring_transaction\" INNER JOIN \"transaction_template\" ON \"recurring_transaction\".\"template\" = \"transaction_template\".\"id\" WHERE \"recurring_transaction\".\"id\" NOT IN (SELECT \"trash\".\"entity_id\" FROM \"trash\" WHERE \"trash\".\"entity_type\" = 'recurring_transaction') AND (\"transaction_template\".\"source\" = 13 OR \"transaction_template\".\"destination\" = 13) ORDER BY \"recurring_transaction\".\"id\" DESC";
+
+    assert_eq!(actual_sql, expected_sql);
+}
+
+#[test]
+fn test_find_all_budgets_by_user_id() {
+    let user_id = 1;
+    let query = budget::Entity::find_all_by_user_id(user_id.into());
+    let actual_sql = query.build(DatabaseBackend::Postgres).to_string();
+
+    let expected_sql = "SELECT \"budget\".\"id\", \"budget\".\"user\", \"budget\".\"amount\", \"budget\".\"name\", \"budget\".\"description\", \"budget\".\"created_at\", \"budget\".\"version\" FROM \"budget\" WHERE \"budget\".\"id\" NOT IN (SELECT \"trash\".\"entity_id\" FROM \"trash\" WHERE \"trash\".\"entity_type\" = 'budget') AND \"budget\".\"id\" IN (SELECT \"permissions\".\"entity_id\" FROM \"permissions\" WHERE \"permissions\".\"entity_type\" = 'budget' AND (\"permissions\".\"user_id\" = 1 OR \"permissions\".\"group_id\" IN (SELECT \"group_member\".\"group_id\" FROM \"group_member\" WHERE \"group_member\".\"user_id\" = 1))) ORDER BY \"budget\".\"id\" DESC";
+
+    assert_eq!(actual_sql, expected_sql);
+}
+
+#[test]
+fn test_find_all_groups_by_user_id() {
+    let user_id = 1;
+    let query = group::Entity::find_all_by_user_id(user_id.into());
+    let actual_sql = query.build(DatabaseBackend::Postgres).to_string();
+
+    let expected_sql = "SELECT \"group\".\"id\", \"group\".\"name\", \"group\".\"description\", \"group\".\"created_at\" FROM \"group\" WHERE \"group\".\"id\" IN (SELECT \"group_member\".\"group_id\" FROM \"group_member\" WHERE \"group_member\".\"user_id\" = 1) ORDER BY \"group\".\"id\" DESC";
+
+    assert_eq!(actual_sql, expected_sql);
+}
+
+#[test]
+fn test_find_grants_by_user_id_not_trashed() {
+    let user_id = 1;
+    let account_id = 13;
+    let query = permissions::Entity::find_grants_by_user_id_not_trashed(user_id.into(), "account", account_id.into());
+    let actual_sql = query.build(DatabaseBackend::Postgres).to_string();
+
+    let expected_sql = "SELECT \"permissions\".\"permissions\", \"group_member\".\"role\" AS \"role\" FROM \"permissions\" LEFT JOIN \"group_member\" ON \"permissions\".\"group_id\" = \"group_member\".\"group_id\" AND \"group_member\".\"user_id\" = 1 WHERE \"permissions\".\"entity_type\" = 'account' AND \"permissions\".\"entity_id\" = 13 AND (\"permissions\".\"user_id\" = 1 OR \"group_member\".\"user_id\" = 1) AND \"permissions\".\"entity_id\" NOT IN (SELECT \"trash\".\"entity_id\" FROM \"trash\" WHERE \"trash\".\"entity_type\" = 'account')";
+
+    assert_eq!(actual_sql, expected_sql);
+}
+
+#[test]
+fn test_find_permissions_owned_only_by_user_id() {
+    let user_id = 1;
+    let query = permissions::Entity::find_owned_only_by_user_id(user_id.into(), "account", 7);
+    let actual_sql = query.build(DatabaseBackend::Postgres).to_string();
+
+    let expected_sql = "SELECT \"permissions\".\"id\", \"permissions\".\"user_id\", \"permissions\".\"group_id\", \"permissions\".\"entity_type\", \"permissions\".\"entity_id\", \"permissions\".\"permissions\" FROM \"permissions\" WHERE \"permissions\".\"user_id\" = 1 AND \"permissions\".\"entity_type\" = 'account' AND \"permissions\".\"permissions\" = 7 AND \"permissions\".\"entity_id\" NOT IN (SELECT \"permissions\".\"entity_id\" FROM \"permissions\" WHERE \"permissions\".\"entity_type\" = 'account' AND \"permissions\".\"user_id\" <> 1 AND \"permissions\".\"permissions\" = 7) AND \"permissions\".\"entity_id\" NOT IN (SELECT \"trash\".\"entity_id\" FROM \"trash\" WHERE \"trash\".\"entity_type\" = 'account') ORDER BY \"permissions\".\"entity_id\" DESC";
+
+    assert_eq!(actual_sql, expected_sql);
+}
+
+#[test]
+fn test_find_all_expense_shares_by_group_id() {
+    let group_id = 1;
+    let query = expense_share::Entity::find_all_by_group_id(group_id.into());
+    let actual_sql = query.build(DatabaseBackend::Postgres).to_string();
+
+    let expected_sql = "SELECT \"expense_share\".\"expense\", \"expense_share\".\"user\", \"expense_share\".\"value\", \"expense_share\".\"amount\" FROM \"expense_share\" INNER JOIN \"expense\" ON \"expense_share\".\"expense\" = \"expense\".\"id\" WHERE \"expense\".\"group\" = 1 AND \"expense\".\"transaction\" NOT IN (SELECT \"trash\".\"entity_id\" FROM \"trash\" WHERE \"trash\".\"entity_type\" = 'transaction') ORDER BY \"expense_share\".\"expense\" DESC";
+
+    assert_eq!(actual_sql, expected_sql);
+}
+
+#[test]
+fn test_find_all_settlements_by_group_id() {
+    let group_id = 1;
+    let query = settlement::Entity::find_all_by_group_id(group_id.into());
+    let actual_sql = query.build(DatabaseBackend::Postgres).to_string();
+
+    let expected_sql = "SELECT \"settlement\".\"id\", \"settlement\".\"group\", \"settlement\".\"payer\", \"settlement\".\"payee\", \"settlement\".\"amount\", \"settlement\".\"currency\", \"settlement\".\"transaction\", \"settlement\".\"created_at\" FROM \"settlement\" WHERE \"settlement\".\"group\" = 1 AND \"settlement\".\"transaction\" NOT IN (SELECT \"trash\".\"entity_id\" FROM \"trash\" WHERE \"trash\".\"entity_type\" = 'transaction') ORDER BY \"settlement\".\"id\" DESC";
+
+    assert_eq!(actual_sql, expected_sql);
+}
+
+#[test]
+fn test_find_all_audit_logs_by_user_id() {
+    let user_id = 1;
+    let query = audit_log::Entity::find_all_by_user_id(user_id.into());
+    let actual_sql = query.build(DatabaseBackend::Postgres).to_string();
+
+    let expected_sql = "SELECT \"audit_log\".\"id\", \"audit_log\".\"actor\", \"audit_log\".\"session\", \"audit_log\".\"entity_type\", \"audit_log\".\"entity_id\", \"audit_log\".\"action\", \"audit_log\".\"before\", \"audit_log\".\"after\", \"audit_log\".\"created_at\" FROM \"audit_log\" WHERE \"audit_log\".\"actor\" = 1 OR (\"audit_log\".\"entity_type\", \"audit_log\".\"entity_id\") IN (SELECT \"permissions\".\"entity_type\", \"permissions\".\"entity_id\" FROM \"permissions\" WHERE \"permissions\".\"user_id\" = 1 OR \"permissions\".\"group_id\" IN (SELECT \"group_member\".\"group_id\" FROM \"group_member\" WHERE \"group_member\".\"user_id\" = 1)) ORDER BY \"audit_log\".\"id\" DESC";
+
+    assert_eq!(actual_sql, expected_sql);
+}
+
+#[test]
+fn test_find_audit_log_changes_by_user_id() {
+    let user_id = 1;
+    let query =
+        audit_log::Entity::find_changes_by_user_id(user_id.into(), 2.into(), 3.into(), vec!["account", "currency"]);
+    let actual_sql = query.build(DatabaseBackend::Postgres).to_string();
+
+    let expected_sql = "SELECT \"audit_log\".\"id\", \"audit_log\".\"actor\", \"audit_log\".\"session\", \"audit_log\".\"entity_type\", \"audit_log\".\"entity_id\", \"audit_log\".\"action\", \"audit_log\".\"before\", \"audit_log\".\"after\", \"audit_log\".\"created_at\" FROM \"audit_log\" WHERE \"audit_log\".\"id\" > 2 AND \"audit_log\".\"id\" < 3 AND \"audit_log\".\"entity_type\" IN ('account', 'currency') AND ((\"audit_log\".\"action\" IN ('create', 'update', 'delete', 'restore') AND ((\"audit_log\".\"entity_type\", \"audit_log\".\"entity_id\") IN (SELECT \"permissions\".\"entity_type\", \"permissions\".\"entity_id\" FROM \"permissions\" WHERE \"permissions\".\"user_id\" = 1 OR \"permissions\".\"group_id\" IN (SELECT \"group_member\".\"group_id\" FROM \"group_member\" WHERE \"group_member\".\"user_id\" = 1)) OR (\"audit_log\".\"entity_type\" = 'currency' AND \"audit_log\".\"entity_id\" IN (SELECT \"currency\".\"id\" FROM \"currency\" WHERE \"currency\".\"user\" IS NULL)))) OR (\"audit_log\".\"action\" = 'revoke' AND (\"audit_log\".\"before\" ->> 'user_id' = '1'))) ORDER BY \"audit_log\".\"id\" ASC";
+
+    assert_eq!(actual_sql, expected_sql);
+}
+
+#[test]
+fn test_find_all_trash_entries_by_user_id() {
+    let user_id = 1;
+    let query = trash::Entity::find_all_by_user_id(user_id.into());
+    let actual_sql = query.build(DatabaseBackend::Postgres).to_string();
+
+    let expected_sql = "SELECT \"trash\".\"entity_type\", \"trash\".\"entity_id\", \"trash\".\"parent_type\", \"trash\".\"parent_id\", \"trash\".\"deleted_by\", \"trash\".\"snapshot\", \"trash\".\"deleted_at\" FROM \"trash\" WHERE \"trash\".\"parent_id\" IS NULL AND (\"trash\".\"deleted_by\" = 1 OR (\"trash\".\"entity_type\", \"trash\".\"entity_id\") IN (SELECT \"permissions\".\"entity_type\", \"permissions\".\"entity_id\" FROM \"permissions\" WHERE \"permissions\".\"user_id\" = 1 OR \"permissions\".\"group_id\" IN (SELECT \"group_member\".\"group_id\" FROM \"group_member\" WHERE \"group_member\".\"user_id\" = 1))) ORDER BY \"trash\".\"deleted_at\" DESC";
+
+    assert_eq!(actual_sql, expected_sql);
+}
+
+#[test]
+fn test_search_users() {
+    let query = user::Entity::search("Admin");
+    let actual_sql = query.build(DatabaseBackend::Postgres).to_string();
+
+    let expected_sql = "SELECT \"user\".\"id\", \"user\".\"username\", \"user\".\"email\", \"user\".\"display_name\", \"user\".\"password\", \"user\".\"created_at\", \"user\".\"is_admin\", \"user\".\"disabled_at\" FROM \"user\" WHERE LOWER(\"user\".\"username\") LIKE '%admin%' OR LOWER(\"user\".\"email\") LIKE '%admin%' OR LOWER(\"user\".\"display_name\") LIKE '%admin%' ORDER BY \"user\".\"id\" DESC";
+
+    assert_eq!(actual_sql, expected_sql);
+}
+
+#[test]
+fn test_consume_invitation() {
+    let query = invitation::Entity::consume("code", OffsetDateTime::UNIX_EPOCH);
+    let actual_sql = query.build(DatabaseBackend::Postgres).to_string();
+
+    let expected_sql = "UPDATE \"invitation\" SET \"uses\" = \"uses\" + 1 WHERE \"invitation\".\"code_hash\" = '5694d08a2e53ffcae0c3103e5ad6f6076abd960eb1f8a56577040bc1028f702b' AND (\"invitation\".\"max_uses\" IS NULL OR \"uses\" < \"max_uses\") AND (\"invitation\".\"expires_at\" IS NULL OR \"invitation\".\"expires_at\" > '1970-01-01 00:00:00.000000 +00:00')";
+
+    assert_eq!(actual_sql, expected_sql);
+}
+
+#[test]
+fn test_find_all_active_webhooks_by_event() {
+    let query = webhook::Entity::find_all_active_by_event("account.created");
+    let actual_sql = query.build(DatabaseBackend::Postgres).to_string();
+
+    let expected_sql = "SELECT \"webhook\".\"id\", \"webhook\".\"user\", \"webhook\".\"url\", \"webhook\".\"secret\", \"webhook\".\"events\", \"webhook\".\"active\", \"webhook\".\"created_at\" FROM \"webhook\" WHERE \"webhook\".\"active\" = TRUE AND (\"webhook\".\"events\" @> E'[\\\"account.created\\\"]')";
+
+    assert_eq!(actual_sql, expected_sql);
+}
+
+#[test]
+fn test_find_all_active_webhooks_by_event_and_reader() {
+    let account_id = 13;
+    let query = webhook::Entity::find_all_active_by_event_and_reader("account.created", "account", account_id.into());
+    let actual_sql = query.build(DatabaseBackend::Postgres).to_string();
+
+    let expected_sql = "SELECT \"webhook\".\"id\", \"webhook\".\"user\", \"webhook\".\"url\", \"webhook\".\"secret\", \"webhook\".\"events\", \"webhook\".\"active\", \"webhook\".\"created_at\" FROM \"webhook\" WHERE \"webhook\".\"active\" = TRUE AND (\"webhook\".\"events\" @> E'[\\\"account.created\\\"]') AND (\"webhook\".\"user\" IN (SELECT \"permissions\".\"user_id\" FROM \"permissions\" WHERE \"permissions\".\"entity_type\" = 'account' AND \"permissions\".\"entity_id\" = 13 AND (\"permissions\".\"permissions\" & 1 <> 0)) OR \"webhook\".\"user\" IN (SELECT \"group_member\".\"user_id\" FROM \"group_member\" WHERE \"group_member\".\"group_id\" IN (SELECT \"permissions\".\"group_id\" FROM \"permissions\" WHERE \"permissions\".\"entity_type\" = 'account' AND \"permissions\".\"entity_id\" = 13 AND (\"permissions\".\"permissions\" & 1 <> 0))))";
+
+    assert_eq!(actual_sql, expected_sql);
+}
+
+#[test]
+fn test_find_all_due_event_deliveries() {
+    let query = event_delivery::Entity::find_all_due(OffsetDateTime::UNIX_EPOCH, 100);
+    let actual_sql = query.build(DatabaseBackend::Postgres).to_string();
+
+    let expected_sql = "SELECT \"event_delivery\".\"id\", \"event_delivery\".\"event\", \"event_delivery\".\"subscriber\", \"event_delivery\".\"attempts\", \"event_delivery\".\"error\", \"event_delivery\".\"next_attempt_at\", \"event_delivery\".\"processed_at\", \"event_delivery\".\"failed_at\" FROM \"event_delivery\" WHERE \"event_delivery\".\"next_attempt_at\" <= '1970-01-01 00:00:00.000000 +00:00' ORDER BY \"event_delivery\".\"next_attempt_at\" ASC, \"event_delivery\".\"event\" ASC LIMIT 100";
+
+    assert_eq!(actual_sql, expected_sql);
+}
+
+#[test]
+fn test_delete_all_handled_events() {
+    let query = event_outbox::Entity::delete_all_handled_before(OffsetDateTime::UNIX_EPOCH);
+    let actual_sql = query.build(DatabaseBackend::Postgres).to_string();
+
+    let expected_sql = "DELETE FROM \"event_outbox\" WHERE \"event_outbox\".\"created_at\" < '1970-01-01 00:00:00.000000 +00:00' AND \"event_outbox\".\"id\" NOT IN (SELECT \"event_delivery\".\"event\" FROM \"event_delivery\" WHERE \"event_delivery\".\"processed_at\" IS NULL)";
+
+    assert_eq!(actual_sql, expected_sql);
+}
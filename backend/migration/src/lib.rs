@@ -2,6 +2,11 @@ pub use sea_orm_migration::prelude::*;
 
 mod m20240110_084613_default_currencies;
 mod m20240116_172139_seed_admin_user;
+mod m20261019_084918_group_permissions;
+mod m20261019_141203_account_archived_at;
+mod m20261019_141517_session_details;
+mod m20261019_141842_user_disabled_at;
+mod m20261019_142210_entity_versions;
 
 pub struct Migrator;
 
@@ -11,6 +16,11 @@ impl MigratorTrait for Migrator {
         vec![
             Box::new(m20240110_084613_default_currencies::Migration),
             Box::new(m20240116_172139_seed_admin_user::Migration),
+            Box::new(m20261019_084918_group_permissions::Migration),
+            Box::new(m20261019_141203_account_archived_at::Migration),
+            Box::new(m20261019_141517_session_details::Migration),
+            Box::new(m20261019_141842_user_disabled_at::Migration),
+            Box::new(m20261019_142210_entity_versions::Migration),
         ]
     }
 }
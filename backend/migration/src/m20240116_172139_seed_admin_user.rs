@@ -26,6 +26,7 @@ impl MigrationTrait for Migration {
             password: Set(hashed_password.to_string()),
             created_at: Set(get_now()),
             is_admin: Set(true),
+            disabled_at: Set(None),
         };
         match user.insert(manager.get_connection()).await {
             Ok(_) => Ok(()),
@@ -0,0 +1,68 @@
+use sea_orm::{ConnectionTrait, Statement};
+use sea_orm_migration::prelude::*;
+
+use utility::snowflake::generator::{FINANCRR_SNOWFLAKE_EPOCH, NODE_ID_BITS, SEQUENCE_BITS};
+
+/// Moves databases created before groups to the permissions table of the schema, which has its own id and grants to
+/// either a user or a group.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager.has_column("permissions", "group_id").await? {
+            return Ok(());
+        }
+
+        let db = manager.get_connection();
+        db.execute_unprepared(
+            r#"ALTER TABLE permissions
+                ADD COLUMN id BIGINT,
+                ADD COLUMN group_id BIGINT REFERENCES "group" (id) ON UPDATE CASCADE ON DELETE CASCADE"#,
+        )
+        .await?;
+
+        // the ids count down from the first snowflake of the current millisecond, so no later snowflake collides
+        db.execute(Statement::from_sql_and_values(
+            db.get_database_backend(),
+            "UPDATE permissions SET id = numbered.id
+                FROM (SELECT ctid,
+                             ((floor(extract(epoch FROM now()) * 1000)::BIGINT - $1) << $2) - row_number() OVER () AS id
+                      FROM permissions) AS numbered
+                WHERE permissions.ctid = numbered.ctid",
+            [(FINANCRR_SNOWFLAKE_EPOCH as i64).into(), i32::from(NODE_ID_BITS + SEQUENCE_BITS).into()],
+        ))
+        .await?;
+
+        db.execute_unprepared(
+            "ALTER TABLE permissions
+                DROP CONSTRAINT permissions_pkey,
+                ADD PRIMARY KEY (id),
+                ALTER COLUMN user_id DROP NOT NULL,
+                ADD UNIQUE NULLS NOT DISTINCT (user_id, group_id, entity_type, entity_id),
+                ADD CHECK ((user_id IS NULL) <> (group_id IS NULL))",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // grants to groups have no place in the old table
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "DELETE FROM permissions WHERE group_id IS NOT NULL;
+                ALTER TABLE permissions
+                    DROP CONSTRAINT permissions_pkey,
+                    DROP COLUMN id,
+                    DROP COLUMN group_id,
+                    ALTER COLUMN user_id SET NOT NULL,
+                    ADD PRIMARY KEY (user_id, entity_type, entity_id);",
+            )
+            .await?;
+
+        Ok(())
+    }
+}
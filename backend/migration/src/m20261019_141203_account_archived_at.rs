@@ -0,0 +1,24 @@
+use sea_orm::ConnectionTrait;
+use sea_orm_migration::prelude::*;
+
+/// Adds the archive timestamp to accounts of databases created before accounts could be archived.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared("ALTER TABLE account ADD COLUMN IF NOT EXISTS archived_at timestamp with time zone")
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.get_connection().execute_unprepared("ALTER TABLE account DROP COLUMN IF EXISTS archived_at").await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+use std::env;
+
+use sea_orm::{ConnectionTrait, Statement};
+use sea_orm_migration::prelude::*;
+
+/// Adds the client details and the expiry to sessions of databases created before they were stored.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        db.execute_unprepared(
+            "ALTER TABLE session
+                ADD COLUMN IF NOT EXISTS ip TEXT,
+                ADD COLUMN IF NOT EXISTS user_agent TEXT,
+                ADD COLUMN IF NOT EXISTS last_used_at timestamp with time zone,
+                ADD COLUMN IF NOT EXISTS expires_at timestamp with time zone",
+        )
+        .await?;
+
+        // older sessions did not record their client and lived for the configured lifetime from their creation
+        let lifetime_hours: i32 = env::var("SESSION_LIFETIME_HOURS")
+            .unwrap_or_else(|_| "168".to_string())
+            .parse()
+            .expect("Could not parse SESSION_LIFETIME_HOURS to i32!");
+        db.execute_unprepared("UPDATE session SET ip = 'unknown' WHERE ip IS NULL").await?;
+        db.execute(Statement::from_sql_and_values(
+            db.get_database_backend(),
+            "UPDATE session SET expires_at = created_at + make_interval(hours => $1) WHERE expires_at IS NULL",
+            [lifetime_hours.into()],
+        ))
+        .await?;
+
+        db.execute_unprepared(
+            "ALTER TABLE session
+                ALTER COLUMN ip SET NOT NULL,
+                ALTER COLUMN expires_at SET NOT NULL",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "ALTER TABLE session
+                    DROP COLUMN IF EXISTS ip,
+                    DROP COLUMN IF EXISTS user_agent,
+                    DROP COLUMN IF EXISTS last_used_at,
+                    DROP COLUMN IF EXISTS expires_at",
+            )
+            .await?;
+
+        Ok(())
+    }
+}
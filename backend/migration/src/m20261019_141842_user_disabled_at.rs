@@ -0,0 +1,24 @@
+use sea_orm::ConnectionTrait;
+use sea_orm_migration::prelude::*;
+
+/// Adds the disable timestamp to users of databases created before admins could disable them.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(r#"ALTER TABLE "user" ADD COLUMN IF NOT EXISTS disabled_at timestamp with time zone"#)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.get_connection().execute_unprepared(r#"ALTER TABLE "user" DROP COLUMN IF EXISTS disabled_at"#).await?;
+
+        Ok(())
+    }
+}
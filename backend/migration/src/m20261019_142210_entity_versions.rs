@@ -0,0 +1,36 @@
+use sea_orm::ConnectionTrait;
+use sea_orm_migration::prelude::*;
+
+/// Adds the version used for `ETag`s and `If-Match` to accounts, budgets and transactions of databases created before
+/// them.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "ALTER TABLE account ADD COLUMN IF NOT EXISTS version INTEGER NOT NULL DEFAULT 0;
+                ALTER TABLE budget ADD COLUMN IF NOT EXISTS version INTEGER NOT NULL DEFAULT 0;
+                ALTER TABLE transaction ADD COLUMN IF NOT EXISTS version INTEGER NOT NULL DEFAULT 0;",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "ALTER TABLE account DROP COLUMN IF EXISTS version;
+                ALTER TABLE budget DROP COLUMN IF EXISTS version;
+                ALTER TABLE transaction DROP COLUMN IF EXISTS version;",
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+use serde::Serialize;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Error, Serialize)]
+pub enum JwtError {
+    #[error("Token is not a signed JWT")]
+    Malformed,
+    #[error("Token is not valid base64url: {0}")]
+    InvalidEncoding(String),
+    #[error("Token is not valid JSON: {0}")]
+    InvalidJson(String),
+    #[error("Unsupported signing algorithm '{0}'")]
+    UnsupportedAlgorithm(String),
+    #[error("No key found to verify the token")]
+    UnknownKey,
+    #[error("Key can not verify '{0}' signatures")]
+    InvalidKey(String),
+    #[error("Invalid signature")]
+    InvalidSignature,
+    #[error("Invalid issuer '{0}'")]
+    InvalidIssuer(String),
+    #[error("Token was not issued for this audience")]
+    InvalidAudience,
+    #[error("Token has expired")]
+    Expired,
+    #[error("Invalid nonce")]
+    InvalidNonce,
+}
@@ -1,3 +1,6 @@
 pub mod datetime;
+pub mod jwt;
+pub mod signature;
 pub mod snowflake;
+pub mod totp;
 pub mod util;
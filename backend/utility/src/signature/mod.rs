@@ -0,0 +1,79 @@
+use std::fmt::Write;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+pub const SIGNATURE_PREFIX: &str = "sha256=";
+
+/// HMAC-SHA256 signatures over `{timestamp}.{body}`, the timestamp is part of the signed content so receivers
+/// can reject replayed requests. Signatures are formatted as `sha256=<lowercase hex>`.
+pub fn sign(secret: &[u8], timestamp: i64, body: &[u8]) -> String {
+    let digest = mac(secret, timestamp, body).finalize().into_bytes();
+
+    digest.iter().fold(String::from(SIGNATURE_PREFIX), |mut signature, byte| {
+        let _ = write!(signature, "{:02x}", byte);
+        signature
+    })
+}
+
+pub fn verify(secret: &[u8], timestamp: i64, body: &[u8], signature: &str) -> bool {
+    let Some(expected) = signature.strip_prefix(SIGNATURE_PREFIX).and_then(decode_hex) else {
+        return false;
+    };
+
+    // compares in constant time
+    mac(secret, timestamp, body).verify_slice(&expected).is_ok()
+}
+
+fn mac(secret: &[u8], timestamp: i64, body: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length!");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+
+    mac
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+
+    (0..hex.len()).step_by(2).map(|index| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok()).collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::all)]
+mod tests {
+    use super::*;
+
+    const SECRET: &[u8] = b"whsec_test";
+    const TIMESTAMP: i64 = 1700000000;
+    const BODY: &[u8] = br#"{"event":"account.created"}"#;
+    // printf '1700000000.{"event":"account.created"}' | openssl dgst -sha256 -hmac whsec_test
+    const SIGNATURE: &str = "sha256=949d3cca7549a05c4152ecfa880f1e9d5ba9b7083016031cc913f3b9da60c156";
+
+    #[test]
+    fn test_sign() {
+        assert_eq!(sign(SECRET, TIMESTAMP, BODY), SIGNATURE);
+    }
+
+    #[test]
+    fn test_verify() {
+        assert!(verify(SECRET, TIMESTAMP, BODY, SIGNATURE));
+    }
+
+    #[test]
+    fn test_verify_rejects_tampering() {
+        assert!(!verify(b"other_secret", TIMESTAMP, BODY, SIGNATURE));
+        assert!(!verify(SECRET, TIMESTAMP + 1, BODY, SIGNATURE));
+        assert!(!verify(SECRET, TIMESTAMP, br#"{"event":"account.deleted"}"#, SIGNATURE));
+    }
+
+    #[test]
+    fn test_verify_rejects_malformed_signatures() {
+        assert!(!verify(SECRET, TIMESTAMP, BODY, SIGNATURE.trim_start_matches(SIGNATURE_PREFIX)));
+        assert!(!verify(SECRET, TIMESTAMP, BODY, "sha256=abc"));
+        assert!(!verify(SECRET, TIMESTAMP, BODY, "sha256=zz"));
+    }
+}
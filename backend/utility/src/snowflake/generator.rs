@@ -71,6 +71,16 @@ impl SnowflakeGenerator {
         Ok(((current_timestamp << (NODE_ID_BITS + SEQUENCE_BITS)) | (self.node_id << SEQUENCE_BITS) | sequence) as i64)
     }
 
+    /// The smallest id that can be generated at the time, every id generated before it is smaller.
+    pub fn first_id_at(&self, epoch_millis: u64) -> i64 {
+        (epoch_millis.saturating_sub(self.epoch) << (NODE_ID_BITS + SEQUENCE_BITS)) as i64
+    }
+
+    /// The time the id was generated at.
+    pub fn epoch_millis_of(&self, id: i64) -> u64 {
+        ((id as u64) >> (NODE_ID_BITS + SEQUENCE_BITS)) + self.epoch
+    }
+
     fn timestamp(&self) -> Result<u64, TimeError> {
         Ok(get_epoch_millis()? - self.epoch)
     }
@@ -117,6 +127,17 @@ mod tests {
         assert!(id2 > id1);
     }
 
+    #[test]
+    fn test_first_id_at() {
+        let generator = SnowflakeGenerator::new(1, 0).expect("Failed to create SnowflakeGenerator");
+        let before = get_epoch_millis().expect("Failed to get time");
+        let id = generator.next_id().expect("Failed to generate ID");
+        assert!(generator.first_id_at(before) <= id);
+        assert!(generator.first_id_at(before + 1000) > id);
+        assert!(generator.epoch_millis_of(id) >= before);
+        assert_eq!(generator.epoch_millis_of(generator.first_id_at(before)), before);
+    }
+
     #[test]
     fn test_new_snowflake_generator_from_env() {
         set_var("NODE_ID", "1");
@@ -0,0 +1,14 @@
+use serde::Serialize;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Error, Serialize)]
+pub enum TotpError {
+    #[error("Invalid base32 character '{0}' in secret")]
+    InvalidSecretCharacter(char),
+    #[error("Secret must not be empty")]
+    EmptySecret,
+    #[error("Encryption key must be 32 bytes long")]
+    InvalidEncryptionKey,
+    #[error("Secret could not be decrypted")]
+    DecryptionFailed,
+}
@@ -0,0 +1,259 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use sha1::Sha1;
+use time::OffsetDateTime;
+
+use crate::totp::error::TotpError;
+
+pub mod error;
+
+pub const STEP_SECONDS: i64 = 30;
+pub const DIGITS: usize = 6;
+const SECRET_LENGTH: usize = 20;
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+pub const ENCRYPTION_KEY_LENGTH: usize = 32;
+
+/// Time-based one-time passwords as specified in RFC 6238 (HMAC-SHA1, 6 digits, 30 second steps).
+/// The time is always passed in, so callers decide which clock is used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Totp {
+    secret: Vec<u8>,
+}
+
+impl Totp {
+    pub fn generate() -> Self {
+        let mut secret = vec![0; SECRET_LENGTH];
+        rand::thread_rng().fill_bytes(&mut secret);
+
+        Self {
+            secret,
+        }
+    }
+
+    pub fn from_secret(secret: Vec<u8>) -> Self {
+        Self {
+            secret,
+        }
+    }
+
+    pub fn from_base32(encoded: &str) -> Result<Self, TotpError> {
+        let mut secret = Vec::with_capacity(encoded.len() * 5 / 8);
+        let mut buffer: u64 = 0;
+        let mut bits = 0;
+        for char in encoded.chars().filter(|char| *char != '=' && !char.is_whitespace()) {
+            let value = BASE32_ALPHABET
+                .iter()
+                .position(|letter| *letter as char == char.to_ascii_uppercase())
+                .ok_or(TotpError::InvalidSecretCharacter(char))?;
+            buffer = (buffer << 5) | value as u64;
+            bits += 5;
+            if bits >= 8 {
+                bits -= 8;
+                secret.push((buffer >> bits) as u8);
+            }
+        }
+
+        if secret.is_empty() {
+            return Err(TotpError::EmptySecret);
+        }
+
+        Ok(Self::from_secret(secret))
+    }
+
+    pub fn to_base32(&self) -> String {
+        let mut encoded = String::with_capacity(self.secret.len().div_ceil(5) * 8);
+        let mut buffer: u64 = 0;
+        let mut bits = 0;
+        for byte in &self.secret {
+            buffer = (buffer << 8) | *byte as u64;
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                encoded.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+            }
+        }
+        if bits > 0 {
+            encoded.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+        }
+
+        encoded
+    }
+
+    /// Encrypts the secret with AES-256-GCM for storing it. The result is the base64 encoded random nonce followed
+    /// by the ciphertext and its tag.
+    pub fn encrypt(&self, key: &[u8]) -> Result<String, TotpError> {
+        let mut nonce = [0; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let mut ciphertext = self.secret.clone();
+        encryption_key(key)?
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut ciphertext)
+            .map_err(|_| TotpError::InvalidEncryptionKey)?;
+
+        let mut encrypted = nonce.to_vec();
+        encrypted.append(&mut ciphertext);
+
+        Ok(STANDARD.encode(encrypted))
+    }
+
+    /// Decrypts a secret created by [Totp::encrypt], fails if it was encrypted with another key or altered.
+    pub fn decrypt(key: &[u8], encrypted: &str) -> Result<Self, TotpError> {
+        let encrypted = STANDARD.decode(encrypted).map_err(|_| TotpError::DecryptionFailed)?;
+        if encrypted.len() < NONCE_LEN {
+            return Err(TotpError::DecryptionFailed);
+        }
+
+        let (nonce, ciphertext) = encrypted.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| TotpError::DecryptionFailed)?;
+        let mut ciphertext = ciphertext.to_vec();
+        let secret = encryption_key(key)?
+            .open_in_place(nonce, Aad::empty(), &mut ciphertext)
+            .map_err(|_| TotpError::DecryptionFailed)?;
+        if secret.is_empty() {
+            return Err(TotpError::EmptySecret);
+        }
+
+        Ok(Self::from_secret(secret.to_vec()))
+    }
+
+    pub fn step(at: OffsetDateTime) -> i64 {
+        at.unix_timestamp().div_euclid(STEP_SECONDS)
+    }
+
+    pub fn code(&self, at: OffsetDateTime) -> String {
+        self.code_at_step(Self::step(at))
+    }
+
+    pub fn code_at_step(&self, step: i64) -> String {
+        let mut mac = Hmac::<Sha1>::new_from_slice(&self.secret).expect("HMAC accepts keys of any length!");
+        mac.update(&step.to_be_bytes());
+        let hash = mac.finalize().into_bytes();
+
+        // dynamic truncation, RFC 4226 section 5.3
+        let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+        let binary = u32::from_be_bytes([hash[offset] & 0x7f, hash[offset + 1], hash[offset + 2], hash[offset + 3]]);
+
+        format!("{:0width$}", binary % 10u32.pow(DIGITS as u32), width = DIGITS)
+    }
+
+    /// Returns the step the code belongs to. The steps right before and after `at` are accepted as well to
+    /// tolerate clock drift, callers should reject steps that were already used.
+    pub fn verify(&self, code: &str, at: OffsetDateTime) -> Option<i64> {
+        let code = code.trim();
+        if code.len() != DIGITS {
+            return None;
+        }
+
+        let step = Self::step(at);
+        (step - 1..=step + 1)
+            .find(|candidate| constant_time_eq(self.code_at_step(*candidate).as_bytes(), code.as_bytes()))
+    }
+
+    /// The `otpauth://` URI authenticator apps expect, usually shown as a QR code.
+    pub fn provisioning_uri(&self, issuer: &str, account: &str) -> String {
+        format!(
+            "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+            percent_encode(issuer),
+            percent_encode(account),
+            self.to_base32(),
+            percent_encode(issuer),
+            DIGITS,
+            STEP_SECONDS
+        )
+    }
+}
+
+fn encryption_key(key: &[u8]) -> Result<LessSafeKey, TotpError> {
+    if key.len() != ENCRYPTION_KEY_LENGTH {
+        return Err(TotpError::InvalidEncryptionKey);
+    }
+
+    UnboundKey::new(&AES_256_GCM, key).map(LessSafeKey::new).map_err(|_| TotpError::InvalidEncryptionKey)
+}
+
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    left.len() == right.len() && left.iter().zip(right).fold(0, |diff, (left, right)| diff | (left ^ right)) == 0
+}
+
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::all)]
+mod tests {
+    use super::*;
+
+    // RFC 6238 appendix B, truncated to 6 digits
+    const RFC_SECRET: &[u8] = b"12345678901234567890";
+
+    fn at(timestamp: i64) -> OffsetDateTime {
+        OffsetDateTime::from_unix_timestamp(timestamp).expect("Failed to create OffsetDateTime")
+    }
+
+    #[test]
+    fn test_rfc_6238_vectors() {
+        let totp = Totp::from_secret(RFC_SECRET.to_vec());
+        assert_eq!(totp.code(at(59)), "287082");
+        assert_eq!(totp.code(at(1111111109)), "081804");
+        assert_eq!(totp.code(at(1111111111)), "050471");
+        assert_eq!(totp.code(at(1234567890)), "005924");
+        assert_eq!(totp.code(at(2000000000)), "279037");
+        assert_eq!(totp.code(at(20000000000)), "353130");
+    }
+
+    #[test]
+    fn test_verify_accepts_adjacent_steps() {
+        let totp = Totp::from_secret(RFC_SECRET.to_vec());
+        let now = at(1111111109);
+        let step = Totp::step(now);
+
+        assert_eq!(totp.verify("081804", now), Some(step));
+        assert_eq!(totp.verify(&totp.code(at(1111111109 - 30)), now), Some(step - 1));
+        assert_eq!(totp.verify(&totp.code(at(1111111109 + 30)), now), Some(step + 1));
+        assert_eq!(totp.verify(&totp.code(at(1111111109 + 90)), now), None);
+        assert_eq!(totp.verify("12345", now), None);
+    }
+
+    #[test]
+    fn test_base32_round_trip() {
+        let totp = Totp::from_secret(RFC_SECRET.to_vec());
+        assert_eq!(totp.to_base32(), "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+        assert_eq!(Totp::from_base32("gezdgnbvgy3tqojqgezdgnbvgy3tqojq").expect("Failed to decode secret"), totp);
+
+        let generated = Totp::generate();
+        assert_eq!(Totp::from_base32(&generated.to_base32()).expect("Failed to decode secret"), generated);
+        assert!(Totp::from_base32("not base32!").is_err());
+    }
+
+    #[test]
+    fn test_encryption_round_trip() {
+        let key = [7; ENCRYPTION_KEY_LENGTH];
+        let totp = Totp::from_secret(RFC_SECRET.to_vec());
+        let encrypted = totp.encrypt(&key).expect("Failed to encrypt secret");
+
+        assert_ne!(encrypted, totp.encrypt(&key).expect("Failed to encrypt secret"));
+        assert_eq!(Totp::decrypt(&key, &encrypted).expect("Failed to decrypt secret"), totp);
+        assert!(Totp::decrypt(&[8; ENCRYPTION_KEY_LENGTH], &encrypted).is_err());
+        assert!(Totp::decrypt(&key, &totp.to_base32()).is_err());
+        assert!(totp.encrypt(&[7; 16]).is_err());
+    }
+
+    #[test]
+    fn test_provisioning_uri() {
+        let totp = Totp::from_secret(RFC_SECRET.to_vec());
+        assert_eq!(
+            totp.provisioning_uri("financrr", "jane doe"),
+            "otpauth://totp/financrr:jane%20doe?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=financrr&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+}